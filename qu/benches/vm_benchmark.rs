@@ -1,12 +1,61 @@
 
 extern crate criterion;
 
+use criterion::black_box;
 use criterion::criterion_group;
 use criterion::criterion_main;
 use criterion::Criterion;
 
+use qu::Qu;
+use qu::QuParser;
 
-fn speed(_c: &mut Criterion) {
+
+/// A script that counts through the fibonacci sequence.
+const FIBONACCI:&str = "
+var nterms int = 40
+var n1 int = 0
+var n2 int = 1
+var count int = 0
+
+# Count through the sequence
+while count < nterms:
+	var nth int = n1 + n2
+	n1 = n2
+	n2 = nth
+	count = count + 1
+
+return n1
+";
+
+
+/// Returns a large script made of many small functions.
+fn large_script() -> String {
+	let mut script = String::new();
+	for i in 0..500 {
+		script.push_str(&format!(
+			"#/ Function {i} /#\nfn function_{i}(x int) int:\n\tvar y float = 1.5 # Unused\n\treturn x * {i} + 2 - 1\n\n"
+		));
+	}
+	script
+}
+
+
+fn speed(c: &mut Criterion) {
+	let script = large_script();
+	c.bench_function("parse large script", |b| b.iter(|| {
+		QuParser::new().parse(black_box(&script)).unwrap();
+	}));
+
+	c.bench_function("compile large script", |b| b.iter(|| {
+		let mut qu = Qu::new();
+		qu.compile(black_box(&script)).unwrap();
+	}));
+
+	c.bench_function("run fibonacci", |b| b.iter(|| {
+		let mut qu = Qu::new();
+		let result:i32 = *qu.run_and_get(black_box(FIBONACCI)).unwrap();
+		black_box(result);
+	}));
 }
 
 
@@ -16,4 +65,4 @@ criterion_group!(
 	targets = speed
 );
 
-criterion_main!(bench);
\ No newline at end of file
+criterion_main!(bench);
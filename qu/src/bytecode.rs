@@ -0,0 +1,1897 @@
+//! Saving compiled scripts as `.quc` files and loading them back.
+//!
+//! A `.quc` file holds the items that scripts added to [`Definitions`]:
+//! their constants, classes, functions, function groups, static variables
+//! and bytecode, along with the entries they added to registered modules,
+//! classes and function groups. Registered items aren't saved. The ones that
+//! the scripts refer to are listed by name (Ex: `math.abs(int) -> int`) and
+//! bound to the items registered in the loading [`Qu`](crate::Qu) instance,
+//! which the scripts' items are added after.
+//!
+//! Loaded files are checked for ids of items they don't have, registers
+//! outside of their frames and jumps outside of their code blocks. The
+//! classes of the values in registers aren't checked, so files should only
+//! be loaded from trusted sources.
+//!
+//! A file starts with [`MAGIC`], the version of its format and a checksum of
+//! the rest of the file. Numbers are stored in little endian.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::mem::size_of;
+
+use crate::Bool;
+use crate::Callable;
+use crate::Class;
+use crate::Float;
+use crate::Function;
+use crate::Int;
+use crate::Module;
+use crate::QuErrorKind;
+use crate::QuMsg;
+use crate::Register;
+use crate::Str;
+use crate::Uuid;
+use crate::compiler::CommonItem;
+use crate::compiler::Constant;
+use crate::compiler::ConstantId;
+use crate::compiler::Definitions;
+use crate::compiler::FunctionGroup;
+use crate::compiler::FunctionGroupId;
+use crate::compiler::FunctionIdentity;
+use crate::compiler::FunctionMetadata;
+use crate::compiler::FunctionReference;
+use crate::compiler::Implementation;
+use crate::compiler::ModuleId;
+use crate::compiler::StaticVariable;
+use crate::compiler::copy_script_value;
+use crate::import::ClassField;
+use crate::import::ClassId;
+use crate::import::FunctionId;
+use crate::import::QuStruct;
+use crate::objects::DynFunction;
+use crate::objects::new_class_id;
+use crate::tokens::QuSpan;
+use crate::vm::FRAME_SIZE;
+use crate::vm::QuOp;
+use crate::vm::RegId;
+
+/// The bytes every `.quc` file starts with.
+const MAGIC:&[u8; 4] = b"QUC\0";
+/// The version of the `.quc` format that this version of Qu saves. Files of
+/// other versions can't be loaded.
+pub const QUC_VERSION:u32 = 3;
+/// The number of bytes taken by the magic bytes, version and checksum.
+const HEADER_SIZE:usize = 16;
+
+// How the values of constants are stored
+const CONSTANT_INT:u8 = 0;
+const CONSTANT_FLOAT:u8 = 1;
+const CONSTANT_BOOL:u8 = 2;
+const CONSTANT_STR:u8 = 3;
+const CONSTANT_FUNCTION:u8 = 4;
+const CONSTANT_CLASS:u8 = 5;
+const CONSTANT_MODULE:u8 = 6;
+/// A constant registered in a module or class, which is bound by name.
+const CONSTANT_REGISTERED:u8 = 7;
+
+// What registered function groups and constants are bound through
+const OWNER_MODULE:u8 = 0;
+const OWNER_CLASS:u8 = 1;
+
+
+/// Saves the items that scripts added to `definitions` as the bytes of a
+/// `.quc` file whose script starts at `code_block` in the module
+/// `module_id`. `registered` holds the registered items alone.
+pub(crate) fn save(
+	definitions:&Definitions,
+	registered:&Definitions,
+	code_block:usize,
+	module_id:ModuleId,
+) -> Result<Vec<u8>, QuMsg> {
+	if !is_registered_first(definitions, registered) {
+		return Err(QuMsg::registered_after_compiling());
+	}
+	let mut w = QucWriter::new(definitions, registered);
+	w.usize(code_block);
+	w.module_id(module_id);
+
+	// Classes declared by scripts get new ids when loaded, so they're listed
+	// before anything refers to them
+	let mut script_classes:Vec<ClassId> = definitions.classes
+		.keys()
+		.filter(|class_id| !registered.classes.contains_key(class_id))
+		.copied()
+		.collect();
+	script_classes.sort();
+	w.usize(script_classes.len());
+	for class_id in &script_classes {
+		w.usize(class_id.0);
+	}
+	for class_id in &script_classes {
+		w.class(definitions.get_class(*class_id)?);
+	}
+
+	// Scripts add entries to registered modules and classes, such as by
+	// importing items or declaring functions that take registered classes
+	let changed_modules:Vec<ModuleId> = (0..registered.modules.len())
+		.filter(|id| {
+			definitions.modules[*id].common != registered.modules[*id].common
+		})
+		.collect();
+	w.usize(changed_modules.len());
+	for module_id in changed_modules {
+		w.module_id(module_id);
+		w.common_changes(
+			&definitions.modules[module_id].common,
+			&registered.modules[module_id].common,
+		);
+	}
+	let mut changed_classes = vec![];
+	for (class_id, class) in &registered.classes {
+		let common = &definitions.get_class(*class_id)?.common;
+		if *common != class.common {
+			changed_classes.push((*class_id, common, &class.common));
+		}
+	}
+	changed_classes.sort_by_key(|(class_id, _, _)| *class_id);
+	w.usize(changed_classes.len());
+	for (class_id, common, registered_common) in changed_classes {
+		w.class_id(class_id);
+		w.common_changes(common, registered_common);
+	}
+
+	let constants = &definitions.constants[registered.constants.len()..];
+	w.usize(constants.len());
+	for constant in constants {
+		w.constant(constant)?;
+	}
+
+	let functions = &definitions.functions[registered.functions.len()..];
+	w.usize(functions.len());
+	for function in functions {
+		w.function(function);
+	}
+
+	let groups = &definitions.function_groups[registered.function_groups.len()..];
+	w.usize(groups.len());
+	for group in groups {
+		w.str(&group.name);
+		w.group_entries(&group.map);
+	}
+	// Functions are only ever added to the end of groups
+	let changed_groups:Vec<FunctionGroupId> = (0..registered.function_groups.len())
+		.filter(|id| {
+			definitions.function_groups[*id].map.len()
+				!= registered.function_groups[*id].map.len()
+		})
+		.collect();
+	w.usize(changed_groups.len());
+	for group_id in changed_groups {
+		w.group_id(group_id);
+		let start = registered.function_groups[group_id].map.len();
+		w.group_entries(&definitions.function_groups[group_id].map[start..]);
+	}
+
+	w.usize(definitions.static_variables.len());
+	for variable in &definitions.static_variables {
+		w.str(&variable.name);
+		w.class_id(variable.class_id);
+		w.usize(variable.offset);
+	}
+	w.usize(definitions.static_memory_size);
+
+	w.usize(definitions.byte_code_blocks.len());
+	for (i, block) in definitions.byte_code_blocks.iter().enumerate() {
+		w.usize(block.len());
+		for op in block.iter() {
+			w.op(op);
+		}
+		let spans = definitions.byte_code_spans.get(i);
+		for pc in 0..block.len() {
+			w.span(spans.and_then(|spans| spans.get(pc).copied().flatten()));
+		}
+	}
+
+	w.names(
+		&definitions.private_constants,
+		&registered.private_constants,
+		QucWriter::constant_id,
+	);
+
+	// The registered items are only known once everything refers to them
+	let body = std::mem::take(&mut w.bytes);
+	w.bindings()?;
+	w.bytes.extend(body);
+
+	let mut bytes = Vec::with_capacity(HEADER_SIZE + w.bytes.len());
+	bytes.extend_from_slice(MAGIC);
+	bytes.extend_from_slice(&QUC_VERSION.to_le_bytes());
+	bytes.extend_from_slice(&checksum(&w.bytes).to_le_bytes());
+	bytes.append(&mut w.bytes);
+	return Ok(bytes);
+}
+
+
+/// Loads the `.quc` file in `bytes`, adding its items after the items in
+/// `registered` and binding the registered items it refers to by name.
+///
+/// Returns new [`Definitions`] with everything in the file, along with the
+/// code block and module that the file's script starts at.
+pub(crate) fn load(
+	bytes:&[u8],
+	registered:&Definitions,
+) -> Result<(Definitions, usize, ModuleId), QuMsg> {
+	if bytes.len() < HEADER_SIZE || &bytes[..MAGIC.len()] != MAGIC {
+		return Err(QuMsg::invalid_bytecode("it isn't a compiled Qu script"));
+	}
+	let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+	if version != QUC_VERSION {
+		return Err(QuMsg::invalid_bytecode(&format!(
+			"it was saved in version {version} of the format, but only version {QUC_VERSION} can be loaded",
+		)));
+	}
+	let expected_checksum = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+	if checksum(&bytes[HEADER_SIZE..]) != expected_checksum {
+		return Err(QuMsg::invalid_bytecode("it's corrupted"));
+	}
+
+	let mut r = QucReader::new(&bytes[HEADER_SIZE..], registered);
+	r.bindings(registered)?;
+	let code_block = r.usize()?;
+	let module_id = r.module_id()?;
+
+	let mut d = registered.clone();
+	d.uuid = Uuid::new_v4();
+
+	let mut script_classes = vec![];
+	for _ in 0..r.usize()? {
+		let saved_id = r.usize()?;
+		let class_id = new_class_id();
+		r.script_classes.insert(saved_id, class_id);
+		script_classes.push(class_id);
+	}
+	for class_id in script_classes {
+		let class = r.class()?;
+		d.classes.insert(class_id, class);
+	}
+
+	for _ in 0..r.usize()? {
+		let module_id = r.module_id()?;
+		r.common_changes(&mut d.modules[module_id].common)?;
+	}
+	for _ in 0..r.usize()? {
+		let class_id = r.class_id()?;
+		let common = &mut d.get_class_mut(class_id)?.common;
+		r.common_changes(common)?;
+	}
+
+	for _ in 0..r.usize()? {
+		let constant = r.constant()?;
+		d.constants.push(constant);
+	}
+
+	for _ in 0..r.usize()? {
+		let function = r.function()?;
+		d.functions.push(function);
+	}
+
+	for _ in 0..r.usize()? {
+		let name = r.str()?;
+		let map = r.group_entries()?;
+		d.function_groups.push(FunctionGroup {map, name});
+	}
+	for _ in 0..r.usize()? {
+		let group_id = r.group_id()?;
+		let entries = r.group_entries()?;
+		d.function_groups.get_mut(group_id)
+			.ok_or_else(|| missing_item("function group"))?
+			.map
+			.extend(entries);
+	}
+
+	for _ in 0..r.usize()? {
+		d.static_variables.push(StaticVariable {
+			name: r.str()?,
+			class_id: r.class_id()?,
+			offset: r.usize()?,
+		});
+	}
+	d.static_memory_size = r.usize()?;
+
+	for _ in 0..r.usize()? {
+		let len = r.usize()?;
+		let mut block = vec![];
+		for _ in 0..len {
+			block.push(r.op()?);
+		}
+		let mut spans = vec![];
+		for _ in 0..len {
+			spans.push(r.span()?);
+		}
+		d.byte_code_blocks.push(block.into());
+		d.byte_code_spans.push(spans);
+	}
+
+	let private_constants = r.names(QucReader::constant_id)?;
+	d.private_constants.extend(private_constants);
+
+	if r.position != r.bytes.len() {
+		return Err(QuMsg::invalid_bytecode("it has bytes after its end"));
+	}
+	QucValidator {d: &d}.definitions(code_block, module_id)?;
+
+	return Ok((d, code_block, module_id));
+}
+
+
+/// Returns whether the items in `registered` come before every item that
+/// scripts added to `definitions`. They don't if items were registered after
+/// a script was compiled.
+fn is_registered_first(definitions:&Definitions, registered:&Definitions) -> bool {
+	let has_functions = definitions.functions.len() >= registered.functions.len()
+		&& definitions.functions.iter()
+			.zip(&registered.functions)
+			.all(|(function, registered)| function.identity == registered.identity);
+	let has_groups = definitions.function_groups.len() >= registered.function_groups.len()
+		&& definitions.function_groups.iter()
+			.zip(&registered.function_groups)
+			.all(|(group, registered)| group.name == registered.name);
+	return has_functions
+		&& has_groups
+		&& definitions.constants.len() >= registered.constants.len()
+		&& definitions.modules.len() == registered.modules.len()
+		&& registered.classes.keys().all(|id| definitions.classes.contains_key(id));
+}
+
+
+/// Returns the id of the class registered in `host` as `name` in `module`.
+fn bind_class(
+	host:&Definitions,
+	module:&str,
+	name:&str,
+	size:u8,
+) -> Result<ClassId, QuMsg> {
+	let Ok(registered_module) = host.get_module_by_name(module) else {
+		return Err(QuMsg::unregistered_item(
+			QuErrorKind::Registration, "module", module,
+		));
+	};
+	let path = format!("{module}.{name}");
+	let class_id = registered_module.common.class_map
+		.get(name)
+		.copied()
+		.ok_or_else(|| QuMsg::unregistered_item(
+			QuErrorKind::UndefinedType, "class", &path,
+		))?;
+	let registered_size = host.get_class(class_id)?.size;
+	if registered_size != size {
+		return Err(QuMsg::registered_class_mismatch(
+			&path, registered_size as usize, size as usize,
+		));
+	}
+	return Ok(class_id);
+}
+
+
+/// Returns every function in the function groups of modules and classes,
+/// paired with the path of the module or class (Ex: `__main__.Point`).
+/// Functions of modules are listed before functions of classes.
+fn function_owners(d:&Definitions) -> Vec<(String, FunctionId)> {
+	fn push_functions(
+		owner:String,
+		common:&CommonItem,
+		d:&Definitions,
+		owners:&mut Vec<(String, FunctionId)>,
+	) {
+		let mut groups:Vec<(&String, &usize)> = common.function_groups_map
+			.iter()
+			.collect();
+		groups.sort();
+		for (_, group_id) in groups {
+			let Ok(group) = d.get_function_group(*group_id) else {continue};
+			for (_, fn_id) in &group.map {
+				owners.push((owner.clone(), *fn_id));
+			}
+		}
+	}
+
+	let mut owners = vec![];
+	for module in &d.modules {
+		push_functions(module.common.name.clone(), &module.common, d, &mut owners);
+	}
+	for module in &d.modules {
+		let mut classes:Vec<(&String, &ClassId)> = module.common.class_map
+			.iter()
+			.collect();
+		classes.sort();
+		for (name, class_id) in classes {
+			let Ok(class) = d.get_class(*class_id) else {continue};
+			let owner = format!("{}.{}", module.common.name, name);
+			push_functions(owner, &class.common, d, &mut owners);
+		}
+	}
+	return owners;
+}
+
+
+/// Returns the path of a function in a module or class
+/// (Ex: `math.abs(int) -> int`).
+fn function_path(
+	owner:&str,
+	fn_id:FunctionId,
+	d:&Definitions,
+) -> Result<String, QuMsg> {
+	let identity = &d.get_function(fn_id)?.identity;
+	return Ok(format!("{owner}.{}", identity.display_pretty(d)));
+}
+
+
+/// Returns the name of the module that each class is defined in.
+fn class_modules(d:&Definitions) -> HashMap<ClassId, &str> {
+	let mut modules = HashMap::new();
+	for module in &d.modules {
+		for class_id in module.common.class_map.values() {
+			modules.insert(*class_id, module.common.name.as_str());
+		}
+	}
+	return modules;
+}
+
+
+/// Returns the registered module or class whose common item `find` names
+/// an item in, along with that name. Modules are searched first.
+fn find_owner(
+	d:&Definitions,
+	find:impl Fn(&CommonItem) -> Option<&String>,
+) -> Option<(ItemOwner, String)> {
+	for (module_id, module) in d.modules.iter().enumerate() {
+		if let Some(name) = find(&module.common) {
+			return Some((ItemOwner::Module(module_id), name.clone()));
+		}
+	}
+	let mut class_ids:Vec<&ClassId> = d.classes.keys().collect();
+	class_ids.sort();
+	for class_id in class_ids {
+		if let Some(name) = find(&d.classes[class_id].common) {
+			return Some((ItemOwner::Class(*class_id), name.clone()));
+		}
+	}
+	return None;
+}
+
+
+/// Returns the error for a file that refers to an item it doesn't have.
+fn missing_item(item:&str) -> QuMsg {
+	return QuMsg::invalid_bytecode(&format!("it refers to a missing {item}"));
+}
+
+
+/// Returns the 64 bit FNV-1a hash of `bytes`.
+pub(crate) fn checksum(bytes:&[u8]) -> u64 {
+	let mut hash:u64 = 0xcbf29ce484222325;
+	for byte in bytes {
+		hash ^= *byte as u64;
+		hash = hash.wrapping_mul(0x100000001b3);
+	}
+	return hash;
+}
+
+
+/// A registered module or class, through which registered function groups
+/// and constants are bound.
+#[derive(Clone, Copy)]
+enum ItemOwner {
+	Module(ModuleId),
+	Class(ClassId),
+}
+
+
+/// The registered items of one kind that a file refers to, in the order
+/// they were first referred to.
+#[derive(Default)]
+struct BoundItems<T> {
+	items: Vec<T>,
+	indexes: HashMap<T, usize>,
+} impl<T: Copy + Eq + Hash> BoundItems<T> {
+	/// Returns the index of `item`, adding it if it isn't listed yet.
+	fn index(&mut self, item:T) -> usize {
+		if let Some(index) = self.indexes.get(&item) {
+			return *index;
+		}
+		self.indexes.insert(item, self.items.len());
+		self.items.push(item);
+		return self.items.len() - 1;
+	}
+}
+
+
+/// Writes the contents of a `.quc` file after its header.
+///
+/// Items that scripts added are written as their index among the items
+/// scripts added. Registered items are written as their index among the
+/// registered items the file refers to, which are written by
+/// [`QucWriter::bindings`].
+struct QucWriter<'a> {
+	bytes: Vec<u8>,
+	definitions: &'a Definitions,
+	registered: &'a Definitions,
+	modules: BoundItems<ModuleId>,
+	classes: BoundItems<ClassId>,
+	functions: BoundItems<FunctionId>,
+	groups: BoundItems<FunctionGroupId>,
+	constants: BoundItems<ConstantId>,
+} impl<'a> QucWriter<'a> {
+	fn new(definitions:&'a Definitions, registered:&'a Definitions) -> Self {
+		return Self {
+			bytes: vec![],
+			definitions,
+			registered,
+			modules: BoundItems::default(),
+			classes: BoundItems::default(),
+			functions: BoundItems::default(),
+			groups: BoundItems::default(),
+			constants: BoundItems::default(),
+		};
+	}
+
+
+	/// Writes the registered items that the file refers to, by name.
+	fn bindings(&mut self) -> Result<(), QuMsg> {
+		let registered = self.registered;
+
+		// Groups and constants are bound through modules, classes and
+		// functions, so they're written first to list those too
+		let groups = self.groups.items.clone();
+		self.usize(groups.len());
+		for group_id in groups {
+			let owner = find_owner(registered, |common| {
+				common.function_groups_map.iter()
+					.find(|(_, id)| **id == group_id)
+					.map(|(name, _)| name)
+			});
+			let Some((owner, name)) = owner else {
+				return Err(QuMsg::of_kind(QuErrorKind::InvalidBytecode, format!(
+					"Can't save function group '{}' because it isn't in any module or class.",
+					registered.get_function_group(group_id)?.name,
+				)));
+			};
+			self.owner(owner);
+			self.str(&name);
+		}
+		let constants = self.constants.items.clone();
+		self.usize(constants.len());
+		for const_id in constants {
+			self.bound_constant(const_id)?;
+		}
+		let dependents = std::mem::take(&mut self.bytes);
+
+		self.usize(self.modules.items.len());
+		for module_id in self.modules.items.clone() {
+			self.str(&registered.get_module(module_id)?.common.name);
+		}
+
+		let class_modules = class_modules(registered);
+		self.usize(self.classes.items.len());
+		for class_id in self.classes.items.clone() {
+			let class = registered.get_class(class_id)?;
+			let Some(module) = class_modules.get(&class_id) else {
+				return Err(QuMsg::of_kind(QuErrorKind::InvalidBytecode, format!(
+					"Can't save class '{}' because it isn't in any module.",
+					class.common.name,
+				)));
+			};
+			self.str(module);
+			self.str(&class.common.name);
+			self.u8(class.size);
+		}
+
+		let mut owners = HashMap::new();
+		for (owner, fn_id) in function_owners(registered) {
+			owners.entry(fn_id).or_insert(owner);
+		}
+		self.usize(self.functions.items.len());
+		for fn_id in self.functions.items.clone() {
+			let Some(owner) = owners.get(&fn_id) else {
+				return Err(QuMsg::of_kind(QuErrorKind::InvalidBytecode, format!(
+					"Can't save function '{}' because it isn't in any module or class.",
+					registered.get_function(fn_id)?.identity.display_pretty(registered),
+				)));
+			};
+			self.str(owner);
+			self.str(&registered.get_function(fn_id)?.identity.name);
+			self.str(&function_path(owner, fn_id, registered)?);
+		}
+
+		self.bytes.extend(dependents);
+		return Ok(());
+	}
+
+
+	/// Writes how to bind a registered constant: through the function,
+	/// class or module it holds, or by its name in a module or class.
+	fn bound_constant(&mut self, const_id:ConstantId) -> Result<(), QuMsg> {
+		let registered = self.registered;
+		let constant = &registered.constants[const_id];
+		let class_id = constant.class_id;
+		if class_id == Function::id() {
+			let fn_id = constant.get_value::<Function>().id;
+			if registered.get_function(fn_id)?.const_id == const_id {
+				self.u8(CONSTANT_FUNCTION);
+				self.function_id(fn_id);
+				return Ok(());
+			}
+		} else if class_id == Class::id() {
+			let value_class = constant.get_value::<Class>().id;
+			if registered.get_class(value_class)?.common.const_id == const_id {
+				self.u8(CONSTANT_CLASS);
+				self.class_id(value_class);
+				return Ok(());
+			}
+		} else if class_id == Module::id() {
+			let module_id = constant.get_value::<Module>().id;
+			if registered.get_module(module_id)?.common.const_id == const_id {
+				self.u8(CONSTANT_MODULE);
+				self.module_id(module_id);
+				return Ok(());
+			}
+		}
+
+		let owner = find_owner(registered, |common| {
+			common.constants_map.iter()
+				.find(|(_, id)| **id == const_id)
+				.map(|(name, _)| name)
+		});
+		let Some((owner, name)) = owner else {
+			return Err(QuMsg::unsaveable_constant(
+				&constant.name,
+				&registered.get_class(class_id)?.common.name,
+			));
+		};
+		self.u8(CONSTANT_REGISTERED);
+		self.owner(owner);
+		self.str(&name);
+		self.class_id(class_id);
+		return Ok(());
+	}
+
+
+	fn u8(&mut self, value:u8) {
+		self.bytes.push(value);
+	}
+
+
+	fn bool(&mut self, value:bool) {
+		self.u8(value as u8);
+	}
+
+
+	fn u32(&mut self, value:u32) {
+		self.bytes.extend_from_slice(&value.to_le_bytes());
+	}
+
+
+	fn u64(&mut self, value:u64) {
+		self.bytes.extend_from_slice(&value.to_le_bytes());
+	}
+
+
+	fn usize(&mut self, value:usize) {
+		self.u64(value as u64);
+	}
+
+
+	fn i64(&mut self, value:i64) {
+		self.bytes.extend_from_slice(&value.to_le_bytes());
+	}
+
+
+	fn str(&mut self, text:&str) {
+		self.usize(text.len());
+		self.bytes.extend_from_slice(text.as_bytes());
+	}
+
+
+	/// Writes whether an item is registered, followed by its index.
+	fn item(&mut self, is_registered:bool, index:usize) {
+		self.bool(is_registered);
+		self.usize(index);
+	}
+
+
+	fn module_id(&mut self, id:ModuleId) {
+		// Scripts don't add modules
+		let index = self.modules.index(id);
+		self.usize(index);
+	}
+
+
+	fn class_id(&mut self, id:ClassId) {
+		match self.registered.classes.contains_key(&id) {
+			true => {
+				let index = self.classes.index(id);
+				self.item(true, index);
+			},
+			// Listed at the start of the file
+			false => self.item(false, id.0),
+		}
+	}
+
+
+	fn function_id(&mut self, id:FunctionId) {
+		let registered_count = self.registered.functions.len();
+		match id.0 < registered_count {
+			true => {
+				let index = self.functions.index(id);
+				self.item(true, index);
+			},
+			false => self.item(false, id.0 - registered_count),
+		}
+	}
+
+
+	fn group_id(&mut self, id:FunctionGroupId) {
+		let registered_count = self.registered.function_groups.len();
+		match id < registered_count {
+			true => {
+				let index = self.groups.index(id);
+				self.item(true, index);
+			},
+			false => self.item(false, id - registered_count),
+		}
+	}
+
+
+	fn constant_id(&mut self, id:ConstantId) {
+		let registered_count = self.registered.constants.len();
+		match id < registered_count {
+			true => {
+				let index = self.constants.index(id);
+				self.item(true, index);
+			},
+			false => self.item(false, id - registered_count),
+		}
+	}
+
+
+	fn owner(&mut self, owner:ItemOwner) {
+		match owner {
+			ItemOwner::Module(module_id) => {
+				self.u8(OWNER_MODULE);
+				self.module_id(module_id);
+			},
+			ItemOwner::Class(class_id) => {
+				self.u8(OWNER_CLASS);
+				self.class_id(class_id);
+			},
+		}
+	}
+
+
+	fn class_ids(&mut self, ids:&[ClassId]) {
+		self.usize(ids.len());
+		for id in ids {
+			self.class_id(*id);
+		}
+	}
+
+
+	fn reg(&mut self, reg:RegId) {
+		self.usize(reg.0);
+	}
+
+
+	fn regs(&mut self, regs:&[RegId]) {
+		self.usize(regs.len());
+		for reg in regs {
+			self.reg(*reg);
+		}
+	}
+
+
+	/// Writes the entries of `map` that `base` lacks or maps to another
+	/// value, sorted so that saving is deterministic.
+	fn names<V: Copy + PartialEq>(
+		&mut self,
+		map:&HashMap<String, V>,
+		base:&HashMap<String, V>,
+		write:fn(&mut Self, V),
+	) {
+		let mut entries:Vec<(&String, &V)> = map.iter()
+			.filter(|(name, value)| base.get(*name) != Some(value))
+			.collect();
+		entries.sort_by(|a, b| a.0.cmp(b.0));
+		self.usize(entries.len());
+		for (name, value) in entries {
+			self.str(name);
+			write(self, *value);
+		}
+	}
+
+
+	fn common(&mut self, common:&CommonItem) {
+		self.str(&common.name);
+		self.constant_id(common.const_id);
+		self.common_changes(common, &CommonItem::default());
+	}
+
+
+	/// Writes the entries of `common` that `base` lacks or maps to other
+	/// items.
+	fn common_changes(&mut self, common:&CommonItem, base:&CommonItem) {
+		self.names(&common.constants_map, &base.constants_map, Self::constant_id);
+		self.names(&common.class_map, &base.class_map, Self::class_id);
+		self.names(
+			&common.function_groups_map,
+			&base.function_groups_map,
+			Self::group_id,
+		);
+		self.names(
+			&common.static_variables_map,
+			&base.static_variables_map,
+			Self::usize,
+		);
+
+		let mut implementations:Vec<(&ClassId, &Implementation)> = common
+			.implementations
+			.iter()
+			.filter(|(trait_id, implementation)| {
+				base.implementations.get(trait_id) != Some(implementation)
+			})
+			.collect();
+		implementations.sort_by(|a, b| a.0.cmp(b.0));
+		self.usize(implementations.len());
+		for (trait_id, implementation) in implementations {
+			self.class_id(*trait_id);
+			let mut functions:Vec<(&FunctionId, &FunctionId)> = implementation
+				.functions
+				.iter()
+				.collect();
+			functions.sort();
+			self.usize(functions.len());
+			for (trait_fn, fn_id) in functions {
+				self.function_id(*trait_fn);
+				self.function_id(*fn_id);
+			}
+			let mut groups:Vec<(&FunctionGroupId, &FunctionGroupId)> = implementation
+				.function_groups
+				.iter()
+				.collect();
+			groups.sort();
+			self.usize(groups.len());
+			for (trait_group, group_id) in groups {
+				self.group_id(*trait_group);
+				self.group_id(*group_id);
+			}
+		}
+	}
+
+
+	fn class(&mut self, class:&QuStruct) {
+		self.u8(class.size);
+		self.bool(class.is_trait);
+		self.bool(class.from_script);
+		self.bool(class.is_tuple);
+		self.bool(class.needs_drop);
+		match &class.function_type {
+			Some((parameters, return_type)) => {
+				self.bool(true);
+				self.class_ids(parameters);
+				self.class_id(*return_type);
+			},
+			None => self.bool(false),
+		}
+		self.usize(class.fields.len());
+		for field in &class.fields {
+			self.str(&field.name);
+			self.class_id(field.class_id);
+			self.usize(field.offset);
+		}
+		self.common(&class.common);
+	}
+
+
+	fn constant(&mut self, constant:&Constant) -> Result<(), QuMsg> {
+		self.str(&constant.name);
+		match constant.class_id {
+			id if id == Int::id() => {
+				self.u8(CONSTANT_INT);
+				self.i64(*constant.get_value::<Int>() as i64);
+			},
+			id if id == Float::id() => {
+				self.u8(CONSTANT_FLOAT);
+				self.u32(constant.get_value::<Float>().to_bits());
+			},
+			id if id == Bool::id() => {
+				self.u8(CONSTANT_BOOL);
+				// Bools take fewer bytes than their class
+				self.bool(constant.value.first().is_some_and(|byte| *byte != 0));
+			},
+			id if id == Str::id() => {
+				self.u8(CONSTANT_STR);
+				self.str(constant.get_value::<Str>().as_str());
+			},
+			id if id == Function::id() => {
+				self.u8(CONSTANT_FUNCTION);
+				self.function_id(constant.get_value::<Function>().id);
+			},
+			id if id == Class::id() => {
+				self.u8(CONSTANT_CLASS);
+				self.class_id(constant.get_value::<Class>().id);
+			},
+			id if id == Module::id() => {
+				self.u8(CONSTANT_MODULE);
+				self.module_id(constant.get_value::<Module>().id);
+			},
+			id => return Err(QuMsg::unsaveable_constant(
+				&constant.name,
+				&self.definitions.get_class(id)?.common.name,
+			)),
+		}
+		return Ok(());
+	}
+
+
+	fn identity(&mut self, identity:&FunctionIdentity) {
+		self.str(&identity.name);
+		self.class_ids(&identity.parameters);
+		self.class_id(identity.return_type);
+	}
+
+
+	fn function(&mut self, function:&FunctionMetadata) {
+		self.identity(&function.identity);
+		match function.code_block {
+			FunctionReference::Internal(code_block) => {
+				self.u8(0);
+				self.usize(code_block);
+			},
+			// The only external functions scripts add are the `copy`
+			// functions of their classes
+			FunctionReference::External(_) => self.u8(1),
+			FunctionReference::Abstract => self.u8(2),
+			// Functions of lists and maps defined on demand are saved as
+			// their position in `DynFunction::ALL`
+			FunctionReference::Dyn(function) => {
+				self.u8(3);
+				let index = DynFunction::ALL.iter()
+					.position(|f| *f == function)
+					.unwrap();
+				self.u8(index as u8);
+			},
+		}
+		self.constant_id(function.const_id);
+		self.bool(function.mutable_self);
+	}
+
+
+	fn group_entries(&mut self, entries:&[(FunctionIdentity, FunctionId)]) {
+		self.usize(entries.len());
+		for (identity, fn_id) in entries {
+			self.identity(identity);
+			self.function_id(*fn_id);
+		}
+	}
+
+
+	/// Writes an op as its position in [`QuOp`] followed by its fields.
+	fn op(&mut self, op:&QuOp) {
+		match op {
+			QuOp::Call(fn_id, args, output) => {
+				self.u8(0);
+				self.function_id(*fn_id);
+				self.regs(args);
+				self.reg(*output);
+			},
+			QuOp::CallV(trait_id, class_id, fn_id, args, output) => {
+				self.u8(1);
+				self.class_id(*trait_id);
+				self.class_id(*class_id);
+				self.function_id(*fn_id);
+				self.regs(args);
+				self.reg(*output);
+			},
+			QuOp::CallValue(callable, args, output) => {
+				self.u8(2);
+				self.reg(*callable);
+				self.regs(args);
+				self.reg(*output);
+			},
+			QuOp::End => self.u8(3),
+			QuOp::Hold(reg) => {
+				self.u8(4);
+				self.reg(*reg);
+			},
+			QuOp::JumpBy(by) => {
+				self.u8(5);
+				self.i64(*by as i64);
+			},
+			QuOp::JumpByIfNot(by) => {
+				self.u8(6);
+				self.i64(*by as i64);
+			},
+			QuOp::LoadArg(index, output) => {
+				self.u8(7);
+				self.u8(*index);
+				self.reg(*output);
+			},
+			QuOp::LoadConstant(const_id, output) => {
+				self.u8(8);
+				self.constant_id(*const_id);
+				self.reg(*output);
+			},
+			QuOp::LoadStatic(variable_id, output) => {
+				self.u8(9);
+				self.usize(*variable_id);
+				self.reg(*output);
+			},
+			QuOp::MakeCallable(fn_id, captures, output) => {
+				self.u8(10);
+				self.function_id(*fn_id);
+				self.regs(captures);
+				self.reg(*output);
+			},
+			QuOp::Return(class_id) => {
+				self.u8(11);
+				self.class_id(*class_id);
+			},
+			QuOp::StoreStatic(reg, variable_id) => {
+				self.u8(12);
+				self.reg(*reg);
+				self.usize(*variable_id);
+			},
+		}
+	}
+
+
+	fn span(&mut self, span:Option<QuSpan>) {
+		let Some(span) = span else {
+			self.bool(false);
+			return;
+		};
+		self.bool(true);
+		self.usize(span.start);
+		self.usize(span.end);
+		self.u32(span.row);
+		self.u32(span.column);
+	}
+}
+
+
+/// Reads the contents of a `.quc` file after its header.
+struct QucReader<'a> {
+	bytes: &'a [u8],
+	position: usize,
+	// The ids of the registered items that the file refers to
+	modules: Vec<ModuleId>,
+	classes: Vec<ClassId>,
+	functions: Vec<FunctionId>,
+	groups: Vec<FunctionGroupId>,
+	constants: Vec<ConstantId>,
+	/// The ids that the classes declared by scripts are loaded as.
+	script_classes: HashMap<usize, ClassId>,
+	// The number of registered functions, groups and constants, which the
+	// ones scripts added are loaded after
+	function_count: usize,
+	group_count: usize,
+	constant_count: usize,
+} impl<'a> QucReader<'a> {
+	fn new(bytes:&'a [u8], registered:&Definitions) -> Self {
+		return Self {
+			bytes,
+			position: 0,
+			modules: vec![],
+			classes: vec![],
+			functions: vec![],
+			groups: vec![],
+			constants: vec![],
+			script_classes: HashMap::new(),
+			function_count: registered.functions.len(),
+			group_count: registered.function_groups.len(),
+			constant_count: registered.constants.len(),
+		};
+	}
+
+
+	/// Reads the registered items that the file refers to, and binds them to
+	/// the items registered in `host` by name.
+	fn bindings(&mut self, host:&Definitions) -> Result<(), QuMsg> {
+		for _ in 0..self.usize()? {
+			let name = self.str()?;
+			let module_id = host.get_module_id(&name).ok_or_else(|| {
+				QuMsg::unregistered_item(QuErrorKind::Registration, "module", &name)
+			})?;
+			self.modules.push(module_id);
+		}
+
+		for _ in 0..self.usize()? {
+			let module = self.str()?;
+			let name = self.str()?;
+			let size = self.u8()?;
+			self.classes.push(bind_class(host, &module, &name, size)?);
+		}
+
+		let function_count = self.usize()?;
+		let mut host_paths = HashMap::new();
+		if function_count > 0 {
+			for (owner, fn_id) in function_owners(host) {
+				let path = function_path(&owner, fn_id, host)?;
+				host_paths.entry(path).or_insert(fn_id);
+			}
+		}
+		for _ in 0..function_count {
+			let owner = self.str()?;
+			let name = self.str()?;
+			let path = self.str()?;
+			if let Some(fn_id) = host_paths.get(&path) {
+				self.functions.push(*fn_id);
+				continue;
+			}
+			// Tell apart functions that don't exist from ones that were
+			// registered with other parameters
+			let mut candidates = vec![];
+			for (host_owner, host_id) in function_owners(host) {
+				if host_owner == owner && host.get_function(host_id)?.identity.name == name {
+					candidates.push(function_path(&host_owner, host_id, host)?);
+				}
+			}
+			let module = owner.split('.').next().unwrap_or_default();
+			if host.get_module_id(module).is_none() {
+				return Err(QuMsg::unregistered_item(
+					QuErrorKind::Registration, "module", module,
+				));
+			}
+			if candidates.is_empty() {
+				return Err(QuMsg::unregistered_item(
+					QuErrorKind::UndefinedFunction, "function", &path,
+				));
+			}
+			return Err(QuMsg::registered_fn_mismatch(&path, &candidates));
+		}
+
+		for _ in 0..self.usize()? {
+			let (owner, common) = self.owner(host)?;
+			let name = self.str()?;
+			let group_id = common.function_groups_map.get(&name).ok_or_else(|| {
+				QuMsg::unregistered_item(
+					QuErrorKind::UndefinedFunction,
+					"function",
+					&format!("{owner}.{name}"),
+				)
+			})?;
+			self.groups.push(*group_id);
+		}
+
+		for _ in 0..self.usize()? {
+			let const_id = match self.u8()? {
+				CONSTANT_FUNCTION => {
+					let fn_id = self.function_id()?;
+					host.get_function(fn_id)?.const_id
+				},
+				CONSTANT_CLASS => {
+					let class_id = self.class_id()?;
+					host.get_class(class_id)?.common.const_id
+				},
+				CONSTANT_MODULE => {
+					let module_id = self.module_id()?;
+					host.get_module(module_id)?.common.const_id
+				},
+				CONSTANT_REGISTERED => {
+					let (owner, common) = self.owner(host)?;
+					let name = self.str()?;
+					let class_id = self.class_id()?;
+					common.constants_map.get(&name)
+						.copied()
+						.filter(|id| host.constants[*id].class_id == class_id)
+						.ok_or_else(|| QuMsg::unregistered_item(
+							QuErrorKind::Registration,
+							"constant",
+							&format!("{owner}.{name}"),
+						))?
+				},
+				_ => return Err(QuMsg::invalid_bytecode("a constant is malformed")),
+			};
+			self.constants.push(const_id);
+		}
+
+		return Ok(());
+	}
+
+
+	fn take(&mut self, len:usize) -> Result<&'a [u8], QuMsg> {
+		let end = self.position
+			.checked_add(len)
+			.filter(|end| *end <= self.bytes.len())
+			.ok_or_else(|| QuMsg::invalid_bytecode("it ends too early"))?;
+		let bytes = &self.bytes[self.position..end];
+		self.position = end;
+		return Ok(bytes);
+	}
+
+
+	fn u8(&mut self) -> Result<u8, QuMsg> {
+		return Ok(self.take(1)?[0]);
+	}
+
+
+	fn bool(&mut self) -> Result<bool, QuMsg> {
+		return match self.u8()? {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(QuMsg::invalid_bytecode("a boolean is malformed")),
+		};
+	}
+
+
+	fn u32(&mut self) -> Result<u32, QuMsg> {
+		return Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()));
+	}
+
+
+	fn u64(&mut self) -> Result<u64, QuMsg> {
+		return Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()));
+	}
+
+
+	fn usize(&mut self) -> Result<usize, QuMsg> {
+		return usize::try_from(self.u64()?).map_err(|_| QuMsg::invalid_bytecode(
+			"it has numbers too big for this platform",
+		));
+	}
+
+
+	fn i64(&mut self) -> Result<i64, QuMsg> {
+		return Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()));
+	}
+
+
+	fn str(&mut self) -> Result<String, QuMsg> {
+		let len = self.usize()?;
+		let bytes = self.take(len)?;
+		return String::from_utf8(bytes.to_vec()).map_err(|_| {
+			QuMsg::invalid_bytecode("it has text that isn't UTF-8")
+		});
+	}
+
+
+	/// Reads whether an item is registered, followed by its index.
+	fn item(&mut self) -> Result<(bool, usize), QuMsg> {
+		return Ok((self.bool()?, self.usize()?));
+	}
+
+
+	/// Returns the id of an item that scripts added, which is loaded after
+	/// the `registered_count` registered items of its kind.
+	fn added_id(registered_count:usize, index:usize) -> Result<usize, QuMsg> {
+		return registered_count.checked_add(index)
+			.ok_or_else(|| QuMsg::invalid_bytecode("it has an index too big"));
+	}
+
+
+	/// Returns the registered item at `index`, if the file lists it.
+	fn bound_id<T: Copy>(items:&[T], index:usize) -> Result<T, QuMsg> {
+		return items.get(index).copied().ok_or_else(|| QuMsg::invalid_bytecode(
+			"it refers to a registered item it doesn't list",
+		));
+	}
+
+
+	fn module_id(&mut self) -> Result<ModuleId, QuMsg> {
+		let index = self.usize()?;
+		return Self::bound_id(&self.modules, index);
+	}
+
+
+	fn class_id(&mut self) -> Result<ClassId, QuMsg> {
+		return match self.item()? {
+			(true, index) => Self::bound_id(&self.classes, index),
+			(false, saved_id) => self.script_classes.get(&saved_id)
+				.copied()
+				.ok_or_else(|| QuMsg::invalid_bytecode(
+					"it refers to a class it doesn't declare",
+				)),
+		};
+	}
+
+
+	fn function_id(&mut self) -> Result<FunctionId, QuMsg> {
+		return match self.item()? {
+			(true, index) => Self::bound_id(&self.functions, index),
+			(false, index) => Ok(FunctionId(
+				Self::added_id(self.function_count, index)?
+			)),
+		};
+	}
+
+
+	fn group_id(&mut self) -> Result<FunctionGroupId, QuMsg> {
+		return match self.item()? {
+			(true, index) => Self::bound_id(&self.groups, index),
+			(false, index) => Self::added_id(self.group_count, index),
+		};
+	}
+
+
+	fn constant_id(&mut self) -> Result<ConstantId, QuMsg> {
+		return match self.item()? {
+			(true, index) => Self::bound_id(&self.constants, index),
+			(false, index) => Self::added_id(self.constant_count, index),
+		};
+	}
+
+
+	/// Reads a registered module or class, returning its path and its common
+	/// item in `host`.
+	fn owner<'h>(
+		&mut self,
+		host:&'h Definitions,
+	) -> Result<(String, &'h CommonItem), QuMsg> {
+		let common = match self.u8()? {
+			OWNER_MODULE => &host.get_module(self.module_id()?)?.common,
+			OWNER_CLASS => &host.get_class(self.class_id()?)?.common,
+			_ => return Err(QuMsg::invalid_bytecode("an item is malformed")),
+		};
+		return Ok((common.name.clone(), common));
+	}
+
+
+	fn class_ids(&mut self) -> Result<Box<[ClassId]>, QuMsg> {
+		let mut ids = vec![];
+		for _ in 0..self.usize()? {
+			ids.push(self.class_id()?);
+		}
+		return Ok(ids.into());
+	}
+
+
+	fn reg(&mut self) -> Result<RegId, QuMsg> {
+		return Ok(RegId(self.usize()?));
+	}
+
+
+	fn regs(&mut self) -> Result<Box<[RegId]>, QuMsg> {
+		let mut regs = vec![];
+		for _ in 0..self.usize()? {
+			regs.push(self.reg()?);
+		}
+		return Ok(regs.into());
+	}
+
+
+	fn names<V>(
+		&mut self,
+		read:fn(&mut Self) -> Result<V, QuMsg>,
+	) -> Result<HashMap<String, V>, QuMsg> {
+		let mut map = HashMap::new();
+		for _ in 0..self.usize()? {
+			let name = self.str()?;
+			map.insert(name, read(self)?);
+		}
+		return Ok(map);
+	}
+
+
+	fn common(&mut self) -> Result<CommonItem, QuMsg> {
+		let mut common = CommonItem {
+			name: self.str()?,
+			const_id: self.constant_id()?,
+			..Default::default()
+		};
+		self.common_changes(&mut common)?;
+		return Ok(common);
+	}
+
+
+	/// Reads entries written by [`QucWriter::common_changes`] into `common`.
+	fn common_changes(&mut self, common:&mut CommonItem) -> Result<(), QuMsg> {
+		common.constants_map.extend(self.names(Self::constant_id)?);
+		common.class_map.extend(self.names(Self::class_id)?);
+		common.function_groups_map.extend(self.names(Self::group_id)?);
+		common.static_variables_map.extend(self.names(Self::usize)?);
+		for _ in 0..self.usize()? {
+			let trait_id = self.class_id()?;
+			let mut implementation = Implementation::default();
+			for _ in 0..self.usize()? {
+				let trait_fn = self.function_id()?;
+				implementation.functions.insert(trait_fn, self.function_id()?);
+			}
+			for _ in 0..self.usize()? {
+				let trait_group = self.group_id()?;
+				implementation.function_groups.insert(trait_group, self.group_id()?);
+			}
+			common.implementations.insert(trait_id, implementation);
+		}
+		return Ok(());
+	}
+
+
+	fn class(&mut self) -> Result<QuStruct, QuMsg> {
+		let size = self.u8()?;
+		let is_trait = self.bool()?;
+		let from_script = self.bool()?;
+		let is_tuple = self.bool()?;
+		let needs_drop = self.bool()?;
+		let function_type = match self.bool()? {
+			true => Some((self.class_ids()?, self.class_id()?)),
+			false => None,
+		};
+		let mut fields = vec![];
+		for _ in 0..self.usize()? {
+			fields.push(ClassField {
+				name: self.str()?,
+				class_id: self.class_id()?,
+				offset: self.usize()?,
+			});
+		}
+		return Ok(QuStruct {
+			size,
+			is_trait,
+			from_script,
+			is_tuple,
+			function_type,
+			needs_drop,
+			fields,
+			common: self.common()?,
+		});
+	}
+
+
+	fn constant(&mut self) -> Result<Constant, QuMsg> {
+		let name = self.str()?;
+		let constant = match self.u8()? {
+			CONSTANT_INT => Constant::new(name, self.i64()? as Int),
+			CONSTANT_FLOAT => Constant::new(name, Float::from_bits(self.u32()?)),
+			CONSTANT_BOOL => Constant::new(name, self.bool()?),
+			CONSTANT_STR => Constant::new(name, Str::from(self.str()?)),
+			CONSTANT_FUNCTION => {
+				let id = self.function_id()?;
+				Constant::new(name, Function {id})
+			},
+			CONSTANT_CLASS => {
+				let id = self.class_id()?;
+				Constant::new(name, Class {id})
+			},
+			CONSTANT_MODULE => {
+				let id = self.module_id()?;
+				Constant::new(name, Module {id})
+			},
+			_ => return Err(QuMsg::invalid_bytecode("a constant is malformed")),
+		};
+		return Ok(constant);
+	}
+
+
+	fn identity(&mut self) -> Result<FunctionIdentity, QuMsg> {
+		return Ok(FunctionIdentity {
+			name: self.str()?,
+			parameters: self.class_ids()?,
+			return_type: self.class_id()?,
+		});
+	}
+
+
+	fn function(&mut self) -> Result<FunctionMetadata, QuMsg> {
+		let identity = self.identity()?;
+		let code_block = match self.u8()? {
+			0 => FunctionReference::Internal(self.usize()?),
+			1 => FunctionReference::External(&copy_script_value),
+			2 => FunctionReference::Abstract,
+			3 => {
+				let function = DynFunction::ALL.get(self.u8()? as usize)
+					.ok_or_else(|| QuMsg::invalid_bytecode(
+						"a function is malformed",
+					))?;
+				FunctionReference::Dyn(*function)
+			},
+			_ => return Err(QuMsg::invalid_bytecode("a function is malformed")),
+		};
+		return Ok(FunctionMetadata {
+			identity,
+			code_block,
+			const_id: self.constant_id()?,
+			mutable_self: self.bool()?,
+		});
+	}
+
+
+	fn group_entries(&mut self) -> Result<Vec<(FunctionIdentity, FunctionId)>, QuMsg> {
+		let mut entries = vec![];
+		for _ in 0..self.usize()? {
+			let identity = self.identity()?;
+			entries.push((identity, self.function_id()?));
+		}
+		return Ok(entries);
+	}
+
+
+	fn op(&mut self) -> Result<QuOp, QuMsg> {
+		let op = match self.u8()? {
+			0 => QuOp::Call(self.function_id()?, self.regs()?, self.reg()?),
+			1 => QuOp::CallV(
+				self.class_id()?,
+				self.class_id()?,
+				self.function_id()?,
+				self.regs()?,
+				self.reg()?,
+			),
+			2 => QuOp::CallValue(self.reg()?, self.regs()?, self.reg()?),
+			3 => QuOp::End,
+			4 => QuOp::Hold(self.reg()?),
+			5 => QuOp::JumpBy(self.i64()? as isize),
+			6 => QuOp::JumpByIfNot(self.i64()? as isize),
+			7 => QuOp::LoadArg(self.u8()?, self.reg()?),
+			8 => QuOp::LoadConstant(self.constant_id()?, self.reg()?),
+			9 => QuOp::LoadStatic(self.usize()?, self.reg()?),
+			10 => QuOp::MakeCallable(
+				self.function_id()?,
+				self.regs()?,
+				self.reg()?,
+			),
+			11 => QuOp::Return(self.class_id()?),
+			12 => QuOp::StoreStatic(self.reg()?, self.usize()?),
+			_ => return Err(QuMsg::invalid_bytecode("an op is malformed")),
+		};
+		return Ok(op);
+	}
+
+
+	fn span(&mut self) -> Result<Option<QuSpan>, QuMsg> {
+		if !self.bool()? {
+			return Ok(None);
+		}
+		return Ok(Some(QuSpan {
+			start: self.usize()?,
+			end: self.usize()?,
+			row: self.u32()?,
+			column: self.u32()?,
+		}));
+	}
+}
+
+
+/// Checks that everything in loaded [`Definitions`] refers to items that
+/// exist, and that ops only use registers in their frames, so that running
+/// a loaded script can't index outside of what it was given.
+struct QucValidator<'a> {
+	d: &'a Definitions,
+} impl<'a> QucValidator<'a> {
+	fn definitions(&self, code_block:usize, module_id:ModuleId) -> Result<(), QuMsg> {
+		let d = self.d;
+		self.block(code_block)?;
+		self.module(module_id)?;
+		for module in &d.modules {
+			self.common(&module.common)?;
+		}
+		for module_id in d.module_map.values() {
+			self.module(*module_id)?;
+		}
+
+		let mut laid_out = HashSet::new();
+		for (class_id, class) in &d.classes {
+			self.class_layout(*class_id, &mut vec![], &mut laid_out)?;
+			self.common(&class.common)?;
+			if let Some((parameters, return_type)) = &class.function_type {
+				for class_id in parameters.iter() {
+					self.class(*class_id)?;
+				}
+				self.class(*return_type)?;
+			}
+		}
+
+		for constant in &d.constants {
+			self.constant_value(constant)?;
+		}
+		for const_id in d.private_constants.values() {
+			self.constant(*const_id)?;
+		}
+
+		// Ops that load arguments are checked against the parameters of the
+		// functions their code block is compiled from
+		let mut block_parameters:Vec<Option<&[ClassId]>> =
+			vec![None; d.byte_code_blocks.len()];
+		for function in &d.functions {
+			let identity = &function.identity;
+			self.identity(identity)?;
+			self.constant(function.const_id)?;
+			if let FunctionReference::Internal(code_block) = function.code_block {
+				self.block(code_block)?;
+				let parameters = &mut block_parameters[code_block];
+				if parameters.is_none_or(|other| identity.parameters.len() < other.len()) {
+					*parameters = Some(&identity.parameters);
+				}
+			}
+			if function.mutable_self {
+				// `self` is moved back to the caller from past the output
+				let Some(self_class) = identity.parameters.first() else {
+					return Err(QuMsg::invalid_bytecode(
+						"a function changes a `self` it doesn't take",
+					));
+				};
+				let size = self.class(identity.return_type)?.size as usize
+					+ self.class(*self_class)?.size as usize;
+				Self::register(RegId(0), size)?;
+			}
+		}
+		for group in &d.function_groups {
+			for (identity, fn_id) in &group.map {
+				self.identity(identity)?;
+				self.function(*fn_id)?;
+			}
+		}
+
+		for variable in &d.static_variables {
+			let end = variable.offset
+				.checked_add(self.class(variable.class_id)?.size as usize);
+			if end.is_none_or(|end| end > d.static_memory_size) {
+				return Err(QuMsg::invalid_bytecode(
+					"a static variable is outside of static memory",
+				));
+			}
+		}
+		// Static memory is allocated when scripts run
+		if d.static_memory_size > d.static_variables.len() * FRAME_SIZE {
+			return Err(QuMsg::invalid_bytecode(
+				"it has more static memory than its variables take",
+			));
+		}
+
+		for (code_block, parameters) in block_parameters.into_iter().enumerate() {
+			self.ops(code_block, parameters)?;
+		}
+		return Ok(());
+	}
+
+
+	fn block(&self, code_block:usize) -> Result<&'a [QuOp], QuMsg> {
+		return self.d.byte_code_blocks.get(code_block)
+			.map(|block| &block[..])
+			.ok_or_else(|| missing_item("code block"));
+	}
+
+
+	fn module(&self, id:ModuleId) -> Result<&'a CommonItem, QuMsg> {
+		return self.d.modules.get(id)
+			.map(|module| &module.common)
+			.ok_or_else(|| missing_item("module"));
+	}
+
+
+	fn class(&self, id:ClassId) -> Result<&'a QuStruct, QuMsg> {
+		return self.d.classes.get(&id).ok_or_else(|| missing_item("class"));
+	}
+
+
+	fn function(&self, id:FunctionId) -> Result<&'a FunctionMetadata, QuMsg> {
+		return self.d.functions.get(id.0).ok_or_else(|| missing_item("function"));
+	}
+
+
+	fn group(&self, id:FunctionGroupId) -> Result<&'a FunctionGroup, QuMsg> {
+		return self.d.function_groups.get(id)
+			.ok_or_else(|| missing_item("function group"));
+	}
+
+
+	fn constant(&self, id:ConstantId) -> Result<&'a Constant, QuMsg> {
+		return self.d.constants.get(id).ok_or_else(|| missing_item("constant"));
+	}
+
+
+	fn static_variable(&self, id:usize) -> Result<&'a StaticVariable, QuMsg> {
+		return self.d.static_variables.get(id)
+			.ok_or_else(|| missing_item("static variable"));
+	}
+
+
+	fn identity(&self, identity:&FunctionIdentity) -> Result<(), QuMsg> {
+		for class_id in identity.parameters.iter() {
+			self.class(*class_id)?;
+		}
+		self.class(identity.return_type)?;
+		return Ok(());
+	}
+
+
+	/// Checks that a register and the `size` bytes after it are in a frame.
+	fn register(reg:RegId, size:usize) -> Result<(), QuMsg> {
+		if reg.0.checked_add(size).is_none_or(|end| end > FRAME_SIZE) {
+			return Err(QuMsg::invalid_bytecode(
+				"it uses a register outside of its frame",
+			));
+		}
+		return Ok(());
+	}
+
+
+	fn common(&self, common:&CommonItem) -> Result<(), QuMsg> {
+		self.constant(common.const_id)?;
+		for const_id in common.constants_map.values() {
+			self.constant(*const_id)?;
+		}
+		for class_id in common.class_map.values() {
+			self.class(*class_id)?;
+		}
+		for group_id in common.function_groups_map.values() {
+			self.group(*group_id)?;
+		}
+		for variable_id in common.static_variables_map.values() {
+			self.static_variable(*variable_id)?;
+		}
+		for (trait_id, implementation) in &common.implementations {
+			if !self.class(*trait_id)?.is_trait {
+				return Err(QuMsg::invalid_bytecode(
+					"it implements a class that isn't a trait",
+				));
+			}
+			// Virtual calls pass the trait function's arguments to the
+			// function that overrides it
+			for (trait_fn, fn_id) in &implementation.functions {
+				let expected = self.function(*trait_fn)?.identity.parameters.len();
+				if self.function(*fn_id)?.identity.parameters.len() != expected {
+					return Err(QuMsg::invalid_bytecode(
+						"it overrides a trait function with one of other parameters",
+					));
+				}
+			}
+			for (trait_group, group_id) in &implementation.function_groups {
+				self.group(*trait_group)?;
+				self.group(*group_id)?;
+			}
+		}
+		return Ok(());
+	}
+
+
+	/// Checks that the fields of a class fit in it, and that it doesn't
+	/// contain itself. `containing` are the classes whose fields led to it.
+	fn class_layout(
+		&self,
+		class_id:ClassId,
+		containing:&mut Vec<ClassId>,
+		laid_out:&mut HashSet<ClassId>,
+	) -> Result<(), QuMsg> {
+		if laid_out.contains(&class_id) {
+			return Ok(());
+		}
+		if containing.contains(&class_id) || containing.len() > FRAME_SIZE {
+			return Err(QuMsg::invalid_bytecode("a class contains itself"));
+		}
+		let class = self.class(class_id)?;
+		containing.push(class_id);
+		for field in &class.fields {
+			let end = field.offset
+				.checked_add(self.class(field.class_id)?.size as usize);
+			if end.is_none_or(|end| end > class.size as usize) {
+				return Err(QuMsg::invalid_bytecode(
+					"a class has a field outside of its bytes",
+				));
+			}
+			self.class_layout(field.class_id, containing, laid_out)?;
+		}
+		containing.pop();
+		laid_out.insert(class_id);
+		return Ok(());
+	}
+
+
+	fn constant_value(&self, constant:&Constant) -> Result<(), QuMsg> {
+		// Functions aren't registered as a class
+		if constant.class_id == Function::id() {
+			if constant.value.len() != Function::size() as usize {
+				return Err(QuMsg::invalid_bytecode("a constant doesn't fit its class"));
+			}
+			self.function(constant.get_value::<Function>().id)?;
+			return Ok(());
+		}
+		let class = self.class(constant.class_id)?;
+		if constant.value.len() > class.size as usize {
+			return Err(QuMsg::invalid_bytecode("a constant doesn't fit its class"));
+		}
+		let holds = |id:ClassId, size:u8| {
+			constant.class_id == id && constant.value.len() == size as usize
+		};
+		if holds(Class::id(), Class::size()) {
+			self.class(constant.get_value::<Class>().id)?;
+		} else if holds(Module::id(), Module::size()) {
+			self.module(constant.get_value::<Module>().id)?;
+		}
+		return Ok(());
+	}
+
+
+	/// Checks a call of `fn_id` with `args` that outputs to `output`.
+	fn call(&self, fn_id:FunctionId, args:&[RegId], output:RegId) -> Result<(), QuMsg> {
+		let identity = &self.function(fn_id)?.identity;
+		if args.len() != identity.parameters.len() {
+			return Err(QuMsg::invalid_bytecode(
+				"a call passes the wrong number of arguments",
+			));
+		}
+		for (arg, class_id) in args.iter().zip(identity.parameters.iter()) {
+			Self::register(*arg, self.class(*class_id)?.size as usize)?;
+		}
+		return Self::register(output, self.class(identity.return_type)?.size as usize);
+	}
+
+
+	/// Checks the ops of a code block. `parameters` are the parameters of the
+	/// function the block is compiled from, if any.
+	fn ops(&self, code_block:usize, parameters:Option<&[ClassId]>) -> Result<(), QuMsg> {
+		let block = self.block(code_block)?;
+		for (pc, op) in block.iter().enumerate() {
+			match op {
+				QuOp::Call(fn_id, args, output) => self.call(*fn_id, args, *output)?,
+				QuOp::CallV(trait_id, class_id, fn_id, args, output) => {
+					if !self.class(*trait_id)?.is_trait {
+						return Err(QuMsg::invalid_bytecode(
+							"a virtual call's trait isn't a trait",
+						));
+					}
+					self.class(*class_id)?;
+					// The class of the first argument picks the function
+					if args.is_empty() {
+						return Err(QuMsg::invalid_bytecode(
+							"a call passes the wrong number of arguments",
+						));
+					}
+					self.call(*fn_id, args, *output)?;
+				},
+				QuOp::CallValue(callable, args, output) => {
+					// The arguments are checked when the callable is called
+					Self::register(*callable, size_of::<Callable>())?;
+					for arg in args.iter() {
+						Self::register(*arg, 0)?;
+					}
+					Self::register(*output, 0)?;
+				},
+				QuOp::End => {},
+				QuOp::Hold(reg) => Self::register(*reg, size_of::<Bool>())?,
+				QuOp::JumpBy(by) | QuOp::JumpByIfNot(by) => {
+					// The program counter moves past the target afterwards
+					let target = (pc as isize).checked_add(*by);
+					if target.is_none_or(|target| target < 0 || target as usize >= block.len()) {
+						return Err(QuMsg::invalid_bytecode(
+							"a jump goes outside of its code block",
+						));
+					}
+				},
+				QuOp::LoadArg(index, output) => {
+					let parameter = parameters
+						.and_then(|parameters| parameters.get(*index as usize));
+					let Some(class_id) = parameter else {
+						return Err(QuMsg::invalid_bytecode(
+							"an op loads an argument that its function doesn't take",
+						));
+					};
+					Self::register(*output, self.class(*class_id)?.size as usize)?;
+				},
+				QuOp::LoadConstant(const_id, output) => {
+					Self::register(*output, self.constant(*const_id)?.value.len())?;
+				},
+				QuOp::LoadStatic(variable_id, reg) | QuOp::StoreStatic(reg, variable_id) => {
+					let class_id = self.static_variable(*variable_id)?.class_id;
+					Self::register(*reg, self.class(class_id)?.size as usize)?;
+				},
+				QuOp::MakeCallable(fn_id, captures, output) => {
+					let parameters = &self.function(*fn_id)?.identity.parameters;
+					// Captured values are the function's last parameters
+					let Some(first_capture) = parameters.len().checked_sub(captures.len()) else {
+						return Err(QuMsg::invalid_bytecode(
+							"a callable captures more values than its function takes",
+						));
+					};
+					for (reg, class_id) in captures.iter().zip(&parameters[first_capture..]) {
+						Self::register(*reg, self.class(*class_id)?.size as usize)?;
+					}
+					Self::register(*output, size_of::<Callable>())?;
+				},
+				QuOp::Return(class_id) => {
+					// The value a script returns is read from its first register
+					Self::register(RegId(0), self.class(*class_id)?.size as usize)?;
+				},
+			}
+		}
+		return Ok(());
+	}
+}
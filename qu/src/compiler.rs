@@ -8,17 +8,27 @@ use crate::Float;
 use crate::Int;
 use crate::Module;
 use crate::QuAdd;
+use crate::QuIterator;
 use crate::QuDiv;
 use crate::QuEqual;
+use crate::QuHash;
 use crate::QuGreater;
+use crate::QuGreaterEq;
 use crate::QuLesser;
+use crate::QuLesserEq;
 use crate::QuMod;
 use crate::QuMul;
+use crate::QuNeg;
+use crate::QuNot;
 use crate::QuNotEqual;
 use crate::QuPow;
+use crate::QuSqrt;
 use crate::QuSub;
+use crate::Str;
 use crate::import::FunctionId;
 use crate::objects;
+use crate::tokens::QuSpan;
+use crate::tokens::Indentation;
 use crate::vm::QuOp;
 use crate::vm::QuOp::*;
 use crate::QuParser;
@@ -26,11 +36,15 @@ use crate::Register;
 use crate::TypedRegId;
 use crate::Void;
 use crate::Uuid;
+use crate::import::ArgsAPI;
 use crate::import::ExternalFunctionPointer;
 use crate::import::ModuleBody;
 use crate::import::ModuleBuilder;
 use crate::import::QuStruct;
+use crate::import::ClassField;
 use crate::import::ClassId;
+use crate::import::DYN_HEADER_SIZE;
+use crate::import::DYN_PAYLOAD_SIZE;
 use crate::import::Registerer;
 use crate::parser::KEYWORD_BOOL_FALSE;
 use crate::parser::KEYWORD_BOOL_TRUE;
@@ -39,21 +53,33 @@ use crate::parser::KEYWORD_WHILE;
 use crate::parser::QuOperator;
 use crate::parser::parsed::*;
 
+use crate::QuErrorKind;
 use crate::QuMsg;
-use crate::QuToken;
+use crate::tokens::QuToken;
+use crate::objects::DynFunction;
 use crate::objects::FUNDAMENTALS_MODULE;
+use crate::vm::MAIN_MODULE;
 use crate::vm::RegId;
 
 use core::fmt;
 use core::panic;
+use std::any::Any;
 use std::any::TypeId;
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::mem::needs_drop;
 use std::mem::size_of;
+use std::rc::Rc;
 use std::hash::Hash;
 use std::sync::RwLock;
 
 pub const CONSTRUCTOR_NAME:&str = ".new";
+/// The name of the functions that initialize instances of script classes.
+pub const INITIALIZER_NAME:&str = "init";
+/// The name of the instance that a class's functions are called on.
+pub const SELF_NAME:&str = "self";
+/// The name of the functions compiled from lambdas.
+pub const LAMBDA_NAME:&str = "<lambda>";
 // TODO: Fix compiler's documentation
 
 // TODO: Make bank store which definitions obj the mappings are for
@@ -64,7 +90,14 @@ pub(crate) static
 
 pub type ConstantId = usize;
 
-#[derive(Debug, Clone)]
+
+/// The `copy` function of classes declared in scripts, tuples and function
+/// types.
+pub(crate) fn copy_script_value(api:&mut ArgsAPI) -> Result<(), QuMsg> {
+	return api.copy_arg(0);
+}
+
+#[derive(Debug, Clone, PartialEq)]
 /// Reresents a Qu item.
 pub struct CommonItem {
 	/// The name of this item.
@@ -218,13 +251,35 @@ pub struct CommonItem {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Constant {
 	pub name: String,
 	pub value: Box<[u8]>,
 	pub class_id: ClassId,
+	/// Owns the value if it needs to be dropped (Ex: a [`Str`]). `value` only
+	/// holds a bitwise copy of it, like the stack does of the values the
+	/// [`QuVm`] owns.
+	/// 
+	/// [`QuVm`]: crate::QuVm
+	_owned: Option<Rc<dyn Any>>,
 } impl Constant {
-	fn get_value<T: Register + 'static>(&self) -> &T {
+	/// Constructs a constant that owns `value`.
+	pub(crate) fn new<T: Register + 'static>(name:String, value:T) -> Self {
+		let value = Rc::new(value);
+		let bytes = unsafe { std::slice::from_raw_parts(
+			(&*value as *const T).cast::<u8>(),
+			size_of::<T>(),
+		) };
+		return Self {
+			name,
+			value: bytes.into(),
+			class_id: T::id(),
+			_owned: needs_drop::<T>().then_some(value as Rc<dyn Any>),
+		};
+	}
+
+
+	pub(crate) fn get_value<T: Register + 'static>(&self) -> &T {
 		assert_eq!(T::size() as usize, self.value.len());
 		assert_eq!(self.class_id, T::id());
 		let x:*const [u8] = &*self.value;
@@ -232,6 +287,19 @@ pub struct Constant {
 	}
 }
 
+/// A variable that is stored outside of the stack, such as the top-level
+/// variables of a module. Static variables live as long as the [`QuVm`] that
+/// runs them.
+/// 
+/// [`QuVm`]: crate::QuVm
+#[derive(Debug, Clone)]
+pub struct StaticVariable {
+	pub name: String,
+	pub class_id: ClassId,
+	/// The location of the variable in the Vm's static memory.
+	pub(crate) offset: usize,
+}
+
 /// The context for what is being compiled. Records things like temporary
 /// variables and import shortcuts.
 #[derive(Debug, Default, Clone)]
@@ -598,6 +666,47 @@ struct Context {
 	}
 
 
+	/// Returns the variable named `identity` if it's declared in the current
+	/// frame.
+	fn find_frame_variable(&self, identity:&str) -> Option<VariableId> {
+		let frame_data = self.get_current_context_frame().get_frame();
+		for scope in frame_data.scopes.iter().rev() {
+			let item = scope.definitions_map
+				.iter()
+				.rev()
+				.find(|pair| pair.0 == identity);
+			match item {
+				Some((_, ItemId::Variable(id))) => return Some(*id),
+				Some(_) => return None,
+				None => {},
+			}
+		}
+		None
+	}
+
+
+	/// Returns true if the current scope is inside a loop of the current
+	/// frame.
+	fn in_loop(&self) -> bool {
+		self.get_current_context_frame()
+			.get_frame()
+			.scopes
+			.iter()
+			.any(|scope| scope.is_loop)
+	}
+
+
+	/// Returns the id of the module being compiled if the current scope is
+	/// the module's outermost scope.
+	fn get_module_root(&self) -> Option<ModuleId> {
+		match self.get_current_context_frame() {
+			ContextFrame::Module(id, frame_data)
+				if frame_data.scopes.len() == 1 => Some(*id),
+			_ => None,
+		}
+	}
+
+
 	fn get_current_context_frame(&self) -> &ContextFrame {
 		self.frames
 			.last()
@@ -740,6 +849,16 @@ struct Context {
     		..Default::default()
 		});
 	}
+
+
+	/// Opens a scope for the body of a loop.
+	fn open_loop_scope(&mut self) {
+		self.open_scope();
+		self.get_current_context_frame_mut()
+			.get_frame_mut()
+			.get_current_scope_mut()
+			.is_loop = true;
+	}
 }
 
 
@@ -788,7 +907,7 @@ enum ContextFrame {
 
 
 pub type RegistrationMethod = dyn Fn(&mut Registerer) -> Result<(), QuMsg>;
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Definitions {
 	pub(crate) uuid: Uuid,
 	pub constants: Vec<Constant>,
@@ -797,7 +916,15 @@ pub struct Definitions {
 	pub function_groups: Vec<FunctionGroup>,
 	pub modules: Vec<ModuleMetadata>,
 
-	pub(crate) byte_code_blocks: Vec<Vec<QuOp>>,
+	pub static_variables: Vec<StaticVariable>,
+
+	/// The bytecode of each function and module. Shared with the Vm while
+	/// it runs a block, so that ops can be borrowed during calls.
+	pub(crate) byte_code_blocks: Vec<Rc<[QuOp]>>,
+	/// The script spans of each op in [`Definitions::byte_code_blocks`].
+	pub(crate) byte_code_spans: Vec<Vec<Option<QuSpan>>>,
+	/// The number of bytes needed to store every static variable.
+	pub(crate) static_memory_size: usize,
 
 	/// A map of names to module IDs.
 	pub module_map: HashMap<String, ModuleId>,
@@ -884,19 +1011,7 @@ pub struct Definitions {
 		name: String,
 		value: T,
 	) -> Result<ConstantId, QuMsg> {
-		let class_id = T::id();
-
-		let allocated_value_ptr:*mut T = Box::leak(Box::new(value));
-		let vec = unsafe { Vec::from_raw_parts(
-			allocated_value_ptr as *mut u8,
-			size_of::<T>(),
-			size_of::<T>(),
-		) };
-		let constant = Constant {
-			name: name,
-			value: vec.into_boxed_slice(),
-			class_id,
-		};
+		let constant = Constant::new(name, value);
 		let constant_id = self.constants.len();
 		self.constants.push(constant);
 		Ok(constant_id)
@@ -915,6 +1030,31 @@ pub struct Definitions {
 	}
 
 
+	/// Removes a function from every function group, so that it can't be
+	/// called, and unbinds the groups it leaves empty. Its code block is
+	/// dropped, as it may never be filled.
+	pub(crate) fn unbind_function(&mut self, fn_id: FunctionId) {
+		let mut emptied = vec![];
+		for (group_id, group) in self.function_groups.iter_mut().enumerate() {
+			let len = group.map.len();
+			group.map.retain(|(_, id)| *id != fn_id);
+			if group.map.len() != len && group.map.is_empty() {
+				emptied.push(group_id);
+			}
+		}
+		let commons = self.modules.iter_mut()
+			.map(|module| &mut module.common)
+			.chain(self.classes.values_mut().map(|class| &mut class.common));
+		for common in commons {
+			common.function_groups_map
+				.retain(|_, group_id| !emptied.contains(group_id));
+		}
+		if let Some(function) = self.functions.get_mut(fn_id.0) {
+			function.code_block = FunctionReference::Abstract;
+		}
+	}
+
+
 	/// Adds a constant and binds a names to it relative to the given item.
 	pub fn define_constant_in_item<T: Register + 'static>(
 		&mut self,
@@ -941,6 +1081,118 @@ pub struct Definitions {
 	}
 
 
+	/// Defines a static variable in a module and binds a name to it.
+	/// 
+	/// If the module already had a variable by the same name then the name is
+	/// rebound to the new variable.
+	pub fn define_static_variable(
+		&mut self,
+		module_id: ModuleId,
+		name: String,
+		class_id: ClassId,
+	) -> Result<VariableId, QuMsg> {
+		let size = self.get_class(class_id)?.size as usize;
+		let offset = self.static_memory_size;
+		self.static_memory_size += size;
+
+		let variable_id = self.static_variables.len();
+		self.static_variables.push(StaticVariable {
+			name: name.clone(),
+			class_id,
+			offset,
+		});
+		self.get_module_mut(module_id)?
+			.common
+			.static_variables_map
+			.insert(name, variable_id);
+
+		Ok(variable_id)
+	}
+
+
+	/// Defines a class declared in a script in a module and binds a name to
+	/// it.
+	pub(crate) fn define_class(
+		&mut self,
+		module_id: ModuleId,
+		class: QuStruct,
+	) -> Result<ClassId, QuMsg> {
+		// Copies of instances are made byte by byte, then given their own
+		// copies of the fields that need dropping.
+		self.define_class_with_copy(
+			module_id,
+			class,
+			FunctionReference::External(&copy_script_value),
+		)
+	}
+
+
+	/// Defines a class in a module and binds a name to it, like
+	/// [`Definitions::define_class`], with `copy` as its `copy` function.
+	pub(crate) fn define_class_with_copy(
+		&mut self,
+		module_id: ModuleId,
+		mut class: QuStruct,
+		copy: FunctionReference,
+	) -> Result<ClassId, QuMsg> {
+		let class_id = objects::new_class_id();
+		let name = class.common.name.clone();
+		self.get_module_mut(module_id)?
+			.common
+			.class_map
+			.insert(name.clone(), class_id);
+		class.common.const_id = self.add_constant(
+			name,
+			Class { id: class_id },
+		)?;
+		self.classes.insert(class_id, class);
+
+		self.define_function_in_item(
+			ItemId::Class(class_id),
+			FunctionMetadata {
+				identity: FunctionIdentity {
+					name: "copy".into(),
+					parameters: Box::new([class_id]),
+					return_type: class_id,
+				},
+				code_block: copy,
+				..Default::default()
+			},
+			None,
+			false,
+		)?;
+
+		Ok(class_id)
+	}
+
+
+	/// Defines a function of a list or map class defined on demand, whose
+	/// body is a [`DynFunction`].
+	fn define_dyn_function(
+		&mut self,
+		class_id: ClassId,
+		name: &str,
+		parameters: Vec<ClassId>,
+		return_type: ClassId,
+		function: DynFunction,
+	) -> Result<FunctionId, QuMsg> {
+		self.define_function_in_item(
+			ItemId::Class(class_id),
+			FunctionMetadata {
+				identity: FunctionIdentity {
+					name: name.into(),
+					parameters: parameters.into(),
+					return_type,
+				},
+				code_block: FunctionReference::Dyn(function),
+				..Default::default()
+			},
+			None,
+			false,
+		)
+	}
+
+
 	pub fn define_module(
 		&mut self,
 		name: String,
@@ -1009,6 +1261,32 @@ pub struct Definitions {
 	}
 
 
+	/// Returns the trait that declares the function, or [`None`] if the
+	/// function is not declared by a trait.
+	pub(crate) fn get_function_trait(
+		&self,
+		id: FunctionId,
+	) -> Result<Option<ClassId>, QuMsg> {
+		let identity = &self.get_function(id)?.identity;
+		let Some(first) = identity.parameters.first() else {
+			return Ok(None);
+		};
+		let class = self.get_class(*first)?;
+		if !class.is_trait {
+			return Ok(None);
+		}
+		let Some(group_id) = class.common.function_groups_map
+			.get(&identity.name)
+			else { return Ok(None) };
+		let is_declared = self.get_function_group(*group_id)?
+			.map
+			.iter()
+			.any(|(_, fn_id)| *fn_id == id);
+
+		Ok(is_declared.then_some(*first))
+	}
+
+
 	#[duplicate_item(
 		get_function_group SelfType FunctionGroupRef get_ref;
 		[get_function_group] [&Self] [&FunctionGroup] [get];
@@ -1039,7 +1317,7 @@ pub struct Definitions {
 	}
 
 
-	fn get_module(&self, id:ModuleId) -> Result<&ModuleMetadata, QuMsg> {
+	pub(crate) fn get_module(&self, id:ModuleId) -> Result<&ModuleMetadata, QuMsg> {
 		if id >= self.modules.len() {
 			return Err(format!(
 				"There is module at index '{}' defined.", id
@@ -1099,9 +1377,7 @@ pub struct Definitions {
 				return Ok(*module.common.class_map.get(name).unwrap());
 			}
 		}
-		Err(format!(
-			"There's no class with name '{}' defined.", name,
-		).into())
+		Err(QuMsg::undefined_type_access(name))
 	}
 
 
@@ -1164,6 +1440,58 @@ pub struct Definitions {
 	}
 
 
+	/// Makes the functions of a trait with the given name copy the changes
+	/// they make to `self` back to the caller's value. Implementations of the
+	/// functions made afterwards do the same.
+	pub(crate) fn set_mutable_self(
+		&mut self,
+		trait_id: ClassId,
+		name: &str,
+	) -> Result<(), QuMsg> {
+		let group_id = self.get_class(trait_id)?
+			.common
+			.get_function_group_id(name)?;
+		let fn_ids:Vec<FunctionId> = self.get_function_group(group_id)?
+			.map
+			.iter()
+			.map(|(_, fn_id)| *fn_id)
+			.collect();
+		for fn_id in fn_ids {
+			self.get_function_mut(fn_id)?.mutable_self = true;
+		}
+		Ok(())
+	}
+
+
+	/// Returns the function that `class_id` implements the function `name`
+	/// of the trait `trait_id` with, or the trait's own function if the class
+	/// doesn't override it.
+	pub(crate) fn trait_function_of(
+		&self,
+		trait_id: ClassId,
+		class_id: ClassId,
+		name: &str,
+	) -> Result<FunctionId, QuMsg> {
+		let group_id = self.get_class(trait_id)?
+			.common
+			.get_function_group_id(name)?;
+		let Some((_, trait_fn_id)) = self.get_function_group(group_id)?
+			.map
+			.first()
+		else {
+			return Err(QuMsg::undefined_fn_access(name));
+		};
+		let implemented = self.get_class(class_id)?
+			.common
+			.get_trait_implementation(trait_id)?
+			.get_implemented_item_maybe(ItemId::Function(*trait_fn_id));
+		match implemented {
+			Some(ItemId::Function(fn_id)) => Ok(fn_id),
+			_ => Ok(*trait_fn_id),
+		}
+	}
+
+
 	pub fn register(
 		&mut self,
 		body:&RegistrationMethod,
@@ -1230,7 +1558,7 @@ pub struct Definitions {
 		trait_id:ClassId,
 		parent_item:ItemId,
 		external_function:FunctionMetadata,
-	) -> Result<(), QuMsg> {
+	) -> Result<FunctionId, QuMsg> {
 		let trait_group_id = self.get_class(trait_id)?
 			.common
 			.get_function_group_id(
@@ -1248,15 +1576,17 @@ pub struct Definitions {
 				.return_type,
 			self
 		) {
-			panic!(
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
 				"Couldn't implment function, {2}, in class, {0}, for trait, {1}, because the trait's function signature, {1}.{3}, doesn't match.",
 				self.get_class(class_id)?.common.name,
 				self.get_class(trait_id)?.common.name,
 				external_function.identity.display_pretty(self),
 				trait_fn.identity.display_pretty(self),
-			)
+			)));
 		}
 		
+		let mut external_function = external_function;
+		external_function.mutable_self = trait_fn.mutable_self;
 		let impl_fn_id = self.add_function(external_function);
 
 		// Add to class's and module/parent's function groups
@@ -1276,7 +1606,7 @@ pub struct Definitions {
 		implementations.functions.insert(trait_fn_id, impl_fn_id);
 		implementations.function_groups.insert(trait_group_id, impl_group_id);
 
-		Ok(())
+		Ok(impl_fn_id)
 	}
 
 
@@ -1290,7 +1620,12 @@ pub struct Definitions {
 		// Manage classes map
 		let class_id:ClassId = T::id();
 		let module = self.get_module_mut(module_id)?;
-		assert!(!module.common.class_map.contains_key(class_name));
+		if module.common.class_map.contains_key(class_name) {
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't register class '{}' because the module already has a class with that name.",
+				class_name,
+			)));
+		}
 		module.common.class_map.insert(class_name.into(), class_id);
 
 		
@@ -1313,6 +1648,7 @@ pub struct Definitions {
 			size_of::<T>(),
 		);
 		class.common.const_id = const_id;
+		class.needs_drop = needs_drop::<T>();
 		self.classes.insert(class_id, class);
 
 		Ok(class_id)
@@ -1344,8 +1680,8 @@ struct FrameData {
 
 #[derive(Debug, Default, Clone)]
 pub struct FunctionGroup {
-	map: Vec<(FunctionIdentity, FunctionId)>,
-	name: String,
+	pub(crate) map: Vec<(FunctionIdentity, FunctionId)>,
+	pub(crate) name: String,
 } impl FunctionGroup {
 	fn new(name: String) -> Self {
 		Self {
@@ -1354,16 +1690,16 @@ pub struct FunctionGroup {
 		}
 	}
 
-	fn get_fn_id(
+	pub(crate) fn get_fn_id(
 		&self,
 		by_identity:&FunctionIdentity,
 		d:&Definitions,
 	) -> Result<FunctionId, QuMsg> {
 		let Some(fn_id) = self.get_fn_id_maybe(by_identity, d)
-			else { return Err(format!(
+			else { return Err(QuMsg::of_kind(QuErrorKind::UndefinedFunction, format!(
 				"Couldn't find function, {}, in group.",
 				by_identity.display_pretty(d),
-			).into())};
+			)))};
 		Ok(fn_id)
 	}
 	
@@ -1420,7 +1756,7 @@ pub struct FunctionIdentity {
 		return true;
 	}
 
-	fn display_pretty(&self, d:&Definitions) -> String {
+	pub(crate) fn display_pretty(&self, d:&Definitions) -> String {
 		let mut args = "".to_owned();
 		for (i, arg) in self.parameters.iter().enumerate() {
 			args.push_str(
@@ -1473,6 +1809,11 @@ pub struct FunctionIdentity {
 pub(crate) enum FunctionReference {
 	Internal(usize),
 	External(&'static ExternalFunctionPointer),
+	/// A function of a list or map class of items that have no Rust type.
+	Dyn(DynFunction),
+	/// A function of a trait that has no body. Classes that implement the
+	/// trait have to implement the function.
+	Abstract,
 } impl fmt::Debug for FunctionReference {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -1480,6 +1821,9 @@ pub(crate) enum FunctionReference {
 				f.debug_tuple("Internal").field(arg0).finish(),
             Self::External(_) =>
 				f.debug_tuple("External").field(&()).finish(),
+            Self::Dyn(arg0) =>
+				f.debug_tuple("Dyn").field(arg0).finish(),
+            Self::Abstract => f.write_str("Abstract"),
         }
     }
 } impl Default for FunctionReference {
@@ -1495,12 +1839,15 @@ pub struct FunctionMetadata {
 	/// start this function.
 	pub(crate) code_block: FunctionReference,
 	pub const_id: ConstantId,
+	/// Whether changes the function makes to its first parameter, `self`, are
+	/// copied back to the caller's value (Ex: `Iterator.next`).
+	pub(crate) mutable_self: bool,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub(crate) struct Implementation {
-	functions: HashMap<FunctionId, FunctionId>,
-	function_groups: HashMap<FunctionGroupId, FunctionGroupId>,
+	pub(crate) functions: HashMap<FunctionId, FunctionId>,
+	pub(crate) function_groups: HashMap<FunctionGroupId, FunctionGroupId>,
 } impl Implementation {
 	fn get_implemented_item(&self, item_id:ItemId) -> Result<ItemId, QuMsg> {
 		if let Some(id) = self.get_implemented_item_maybe(item_id) {
@@ -1512,7 +1859,10 @@ pub(crate) struct Implementation {
 		).into())
 	}
 
-	fn get_implemented_item_maybe(&self, item_id:ItemId) -> Option<ItemId> {
+	pub(crate) fn get_implemented_item_maybe(
+		&self,
+		item_id:ItemId,
+	) -> Option<ItemId> {
 		if let ItemId::Function(id) = item_id {
 			return self.functions
 				.get(&id)
@@ -1565,6 +1915,8 @@ struct Scope {
 	definitions_map: Vec<(String, ItemId)>,
 	/// The number of bytes that have been allocated to the stack in this scope.
 	stack_size: usize,
+	/// Whether this scope is the body of a loop.
+	is_loop: bool,
 } impl Scope {
 	fn define_variable(
 		&mut self,
@@ -1703,7 +2055,7 @@ struct Scope {
 }
 
 
-type VariableId = usize;
+pub type VariableId = usize;
 
 
 #[derive(Debug, Default, Clone)]
@@ -1712,10 +2064,30 @@ struct VariableMetadata {
 }
 
 
+/// The value at the start of a chain of dot indexes (Ex: the 'foo' of
+/// foo.bar.baz).
+enum FieldRoot {
+	/// A variable on the stack. Its fields are read and assigned in place.
+	Variable,
+	/// A static variable that was loaded into a register. Its fields have to
+	/// be stored back into it after being assigned.
+	Static(VariableId, RegId),
+	/// A value that isn't stored anywhere, like the output of a function.
+	Temporary,
+}
+
+
 /// Compiles [QuLeaf]s into Qu bytecode.
 #[derive(Debug, Default, Clone)]
 pub struct QuCompiler {
 	context: Context,
+	/// The id that the first static variable defined by this compiler gets.
+	/// Static variables before it were defined by previous compilations.
+	first_static_id: VariableId,
+	/// The warnings and notes found while compiling.
+	diagnostics: Vec<QuMsg>,
+	/// How the indentation of lines is measured.
+	indentation: Indentation,
 } impl QuCompiler {
 	/// Creates and returns a new [QuCompiler].
 	pub fn new() -> Self {
@@ -1760,76 +2132,720 @@ pub struct QuCompiler {
 		let Some(identity) = option_identity else {
 			return definitions.class_id::<Void>();
 		};
-		let class_id = definitions.find_class_id(
-			&identity.slice
+		let class_id = Self::class_id_from_name(
+			&identity.slice,
+			definitions,
 		)?;
 		
 		Ok(class_id)
 	}
 
 
-	/// Compiles a copy from one register to another.
-	fn cmp_copy_register(
-		&self,
-		from: TypedRegId,
-		to: RegId,
-		definitions: &mut Definitions
-	) -> Result<QuAsmBuilder, QuMsg>{
-		let identity = &FunctionIdentity {
-			name: "copy".into(),
-			parameters: Box::new([from.class_id()]),
-			return_type: Default::default(),
+	/// Returns the class named `name`. Tuple classes, function types, lists
+	/// and maps are defined the first time they're named (Ex: (int, float),
+	/// fn(int) int, List[P]).
+	fn class_id_from_name(
+		name: &str,
+		definitions: &mut Definitions,
+	) -> Result<ClassId, QuMsg> {
+		if let Some(signature) = name.strip_prefix("fn(") {
+			// The parameters end at the parenthesy that closes "fn("
+			let mut depth = 0;
+			let close = signature.char_indices()
+				.find(|(_, c)| {
+					match c {
+						'[' | '(' => depth += 1,
+						']' | ')' if depth == 0 => return true,
+						']' | ')' => depth -= 1,
+						_ => {},
+					}
+					false
+				})
+				.map(|(i, _)| i)
+				.ok_or_else(|| QuMsg::undefined_type_access(name))?;
+			let mut parameters = vec![];
+			for parameter in Self::split_type_names(&signature[..close]) {
+				parameters.push(Self::class_id_from_name(parameter, definitions)?);
+			}
+			let return_type = match signature[close+1..].trim() {
+				"" => definitions.class_id::<Void>()?,
+				return_type => Self::class_id_from_name(return_type, definitions)?,
+			};
+			return Self::function_type_class(&parameters, return_type, definitions);
+		}
+
+		if let Some(item) = name.strip_prefix("List[")
+			.and_then(|name| name.strip_suffix(']'))
+		{
+			let item_class = Self::class_id_from_name(item, definitions)?;
+			return Self::list_class(item_class, definitions);
+		}
+
+		if let Some(entries) = name.strip_prefix("Map[")
+			.and_then(|name| name.strip_suffix(']'))
+		{
+			if let [key, value] = Self::split_type_names(entries)[..] {
+				let key_class = Self::class_id_from_name(key, definitions)?;
+				let value_class = Self::class_id_from_name(value, definitions)?;
+				return Self::map_class(key_class, value_class, definitions);
+			}
+		}
+
+		let Some(elements) = name.strip_prefix('(')
+			.and_then(|name| name.strip_suffix(')'))
+			else {
+				return definitions.find_class_id(name);
+			};
+		let mut element_classes = vec![];
+		for element in Self::split_type_names(elements) {
+			element_classes.push(Self::class_id_from_name(element, definitions)?);
+		}
+		Self::tuple_class(&element_classes, definitions)
+	}
+
+
+	/// Splits a list of type names separated by commas (Ex: "int, Map[str,
+	/// int]") into the names. Commas inside brackets or parenthesis belong
+	/// to the names.
+	fn split_type_names(names: &str) -> Vec<&str> {
+		let mut split = vec![];
+		let mut depth = 0;
+		let mut start = 0;
+		for (i, c) in names.char_indices() {
+			match c {
+				'[' | '(' => depth += 1,
+				']' | ')' => depth -= 1,
+				',' if depth == 0 => {
+					split.push(names[start..i].trim());
+					start = i + 1;
+				},
+				_ => {},
+			}
+		}
+		split.push(names[start..].trim());
+		split.retain(|name| !name.is_empty());
+		split
+	}
+
+
+	/// Returns the class of tuples with elements of `element_classes`,
+	/// defining it if it isn't yet.
+	/// 
+	/// Tuple classes are defined in the fundamentals module, so that every
+	/// module shares them. Their elements are laid out like the fields of a
+	/// class declared in a script, and are named after their position.
+	fn tuple_class(
+		element_classes: &[ClassId],
+		definitions: &mut Definitions,
+	) -> Result<ClassId, QuMsg> {
+		let mut names = vec![];
+		for class_id in element_classes {
+			names.push(definitions.get_class(*class_id)?.common.name.clone());
+		}
+		let name = match names.len() {
+			1 => format!("({},)", names[0]),
+			_ => format!("({})", names.join(", ")),
 		};
-		
-		let group_id = definitions.get_class(from.class_id())?
-			.common
-			.get_function_group_id(&identity.name)?;
-		let fn_id = definitions
-			.get_function_group(group_id)?
-			.get_fn_id(&identity, &definitions)?;
-		let fn_data = definitions.get_function(fn_id)?;
+		if let Ok(class_id) = definitions.find_class_id(&name) {
+			return Ok(class_id);
+		}
 
-		let mut b: QuAsmBuilder = QuAsmBuilder::new();
-		b.add_op(QuOp::Call(
-			fn_id,
-			Box::new([from.into()]),
-			to.into(),
-		));
-		b.return_reg.1 = fn_data.identity.return_type;
+		let mut fields = vec![];
+		let mut size = 0;
+		for (i, class_id) in element_classes.iter().enumerate() {
+			fields.push(ClassField {
+				name: i.to_string(),
+				class_id: *class_id,
+				offset: size,
+			});
+			size += definitions.get_class(*class_id)?.size as usize;
+		}
+		if size >= u8::MAX as usize {
+			return Err(format!(
+				"Tuple '{}' is too big. Its elements take {} bytes, but tuples can take at most {} bytes.",
+				name,
+				size,
+				u8::MAX - 1,
+			).into());
+		}
 
-		Ok(b)
+		let module_id = *definitions.module_map
+			.get(FUNDAMENTALS_MODULE)
+			.unwrap();
+		let mut class = QuStruct::new(name, size);
+		class.from_script = true;
+		class.is_tuple = true;
+		class.fields = fields;
+		definitions.define_class(module_id, class)
 	}
 
 
-	/// Compiles an expression into bytecode.
-	fn cmp_expr(
+	/// Returns the function type of functions with `parameters` that return
+	/// `return_type`, defining it if it isn't yet.
+	/// 
+	/// Values of function types are [`objects::Callable`]s, no matter the
+	/// signature. Like tuples, function types are defined in the
+	/// fundamentals module.
+	pub(crate) fn function_type_class(
+		parameters: &[ClassId],
+		return_type: ClassId,
+		definitions: &mut Definitions,
+	) -> Result<ClassId, QuMsg> {
+		let mut names = vec![];
+		for class_id in parameters {
+			names.push(definitions.get_class(*class_id)?.common.name.clone());
+		}
+		let mut name = format!("fn({})", names.join(", "));
+		if return_type != definitions.class_id::<Void>()? {
+			let return_name = &definitions.get_class(return_type)?.common.name;
+			name = format!("{name} {return_name}");
+		}
+		if let Ok(class_id) = definitions.find_class_id(&name) {
+			return Ok(class_id);
+		}
+
+		let module_id = *definitions.module_map
+			.get(FUNDAMENTALS_MODULE)
+			.unwrap();
+		let mut class = QuStruct::new(name, objects::Callable::size() as usize);
+		class.function_type = Some((parameters.into(), return_type));
+		definitions.define_class(module_id, class)
+	}
+
+
+	/// Returns the classes of the elements of `tuple_class`, or [`None`] if
+	/// it isn't a tuple.
+	fn tuple_element_classes(
+		tuple_class: ClassId,
+		definitions: &Definitions,
+	) -> Option<Vec<ClassId>> {
+		let class = definitions.get_class(tuple_class).ok()?;
+		if !class.is_tuple {
+			return None;
+		}
+		Some(class.fields.iter().map(|field| field.class_id).collect())
+	}
+
+
+	/// Compiles a class declaration.
+	/// 
+	/// Instances of classes declared in scripts are stored by value, with
+	/// their fields laid out one after another in the order they are
+	/// declared. Each `init` function of the class is compiled into a
+	/// constructor, and every other function gets `self` as its first
+	/// parameter.
+	fn cmp_class_decl(
 		&mut self,
-		expression:&Expression,
-		reg: RegId,
-		reg_type: Option<ClassId>,
+		class_declaration: &ClassDeclaration,
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
-		let builder = match expression {
-			Expression::Call(
-				call_expression,
-			) => {
-				let mut parameters = vec![];
-				for parameter in &call_expression.parameters.elements {
-					parameters.push(parameter);
-				}
-				self.cmp_fn_call(
-					&call_expression,
-					reg,
-					definitions,
-				)
-			}
-			Expression::DotIndex(
-				dot_index,
-			) => self.cmp_expr_dot_index(
-				dot_index,
+		let name = &class_declaration.name.slice;
+		let Some(module_id) = self.context.get_module_root() else {
+			return Err(format!(
+				"Class '{}' has to be declared in the outermost scope of a module.",
+				name,
+			).into());
+		};
+		if self.context.has_item(name, definitions) {
+			return Err(format!(
+				"An item by '{}' is already defined.", name,
+			).into());
+		}
+		let statements = &class_declaration.body.code_block.statements;
+
+		// Lay out fields
+		let mut fields:Vec<ClassField> = vec![];
+		let mut field_declarations = vec![];
+		let mut size = 0;
+		for statement in statements {
+			let Statement::VarDeclaration(field) = statement else {continue};
+			if fields.iter().any(|other| other.name == field.name.slice) {
+				return Err(format!(
+					"Class '{}' already has a field named '{}'.",
+					name,
+					field.name.slice,
+				).into());
+			}
+			if field.static_type.is_none() {
+				return Err(format!(
+					"Field '{}' of class '{}' has to be given a type.",
+					field.name.slice,
+					name,
+				).into());
+			}
+			let class_id = self.class_id_from_option_identity(
+				&field.static_type,
+				definitions,
+			)?;
+			fields.push(ClassField {
+				name: field.name.slice.clone(),
+				class_id,
+				offset: size,
+			});
+			size += definitions.get_class(class_id)?.size as usize;
+			field_declarations.push(field.as_ref());
+		}
+		if size >= u8::MAX as usize {
+			return Err(format!(
+				"Class '{}' is too big. Its fields take {} bytes, but classes can take at most {} bytes.",
+				name,
+				size,
+				u8::MAX - 1,
+			).into());
+		}
+
+		let mut class = QuStruct::new(name.clone(), size);
+		class.from_script = true;
+		class.fields = fields;
+		let class_id = definitions.define_class(module_id, class)?;
+
+		// The class is named while its body compiles, so that its functions
+		// can refer to it, but only keeps its name if the body compiles
+		self.context.open_frame(ContextFrame::class(class_id));
+		let result = self.cmp_class_body(
+			class_declaration,
+			class_id,
+			module_id,
+			&field_declarations,
+			definitions,
+		);
+		self.context.close_frame();
+		if let Err(msg) = result {
+			definitions.get_module_mut(module_id)?
+				.common
+				.class_map
+				.remove(name);
+			return Err(msg);
+		}
+
+		Ok(QuAsmBuilder::new())
+	}
+
+
+	/// Compiles the trait implementations and functions of a class declared
+	/// in a script.
+	fn cmp_class_body(
+		&mut self,
+		class_declaration: &ClassDeclaration,
+		class_id: ClassId,
+		module_id: ModuleId,
+		field_declarations: &[&VarDeclaration],
+		definitions: &mut Definitions,
+	) -> Result<(), QuMsg> {
+		let name = &class_declaration.name.slice;
+		let statements = &class_declaration.body.code_block.statements;
+
+		// Implement traits before compiling functions, so that functions can
+		// use the class as any of its traits
+		let mut trait_ids = vec![];
+		for statement in statements {
+			let Statement::Impl(impl_block) = statement else {continue};
+			let trait_id = self.find_trait(&impl_block.name, definitions)?;
+			definitions.impl_trait_in_item(trait_id, class_id)?;
+			trait_ids.push(trait_id);
+		}
+
+		// Compile functions
+		let has_initializer = statements.iter().any(|statement| matches!(
+			statement,
+			Statement::FunctionDeclaration(function)
+				if function.identity.name.slice == INITIALIZER_NAME,
+		));
+		if !has_initializer {
+			// Classes without initializers get a constructor that only
+			// initializes fields
+			self.cmp_constructor_decl(
+				class_id,
+				vec![],
+				&CodeBlock::default(),
+				field_declarations,
+				definitions,
+			)?;
+		}
+		for statement in statements {
+			match statement {
+				Statement::VarDeclaration(_) => {},
+				Statement::FunctionDeclaration(function)
+					if function.identity.name.slice == INITIALIZER_NAME =>
+				{
+					if let Some(return_type) = &function.identity.return_type {
+						return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+							"The '{}' function of class '{}' can't have a return type, but found '{}'.",
+							INITIALIZER_NAME,
+							name,
+							return_type.slice,
+						)));
+					}
+					let parameters = self.cmp_fn_parameters(
+						&function.identity,
+						definitions,
+					)?;
+					self.cmp_constructor_decl(
+						class_id,
+						parameters,
+						&function.body.code_block,
+						field_declarations,
+						definitions,
+					)?;
+				},
+				Statement::FunctionDeclaration(function) => {
+					self.cmp_method_decl(
+						class_id,
+						&function.identity,
+						&function.body,
+						definitions,
+					)?;
+				},
+				Statement::Impl(impl_block) => {
+					let trait_id = self.find_trait(
+						&impl_block.name,
+						definitions,
+					)?;
+					let impl_statements = impl_block.body
+						.iter()
+						.flat_map(|body| &body.code_block.statements);
+					for impl_statement in impl_statements {
+						let Statement::FunctionDeclaration(function)
+							= impl_statement
+							else {
+								return Err(format!(
+									"Only functions can be declared in the implementation of trait '{}'.",
+									impl_block.name.slice,
+								).into());
+							};
+						self.cmp_impl_fn_decl(
+							class_id,
+							trait_id,
+							ItemId::Module(module_id),
+							&function.identity,
+							&function.body,
+							definitions,
+						)?;
+					}
+				},
+				_ => return Err(format!(
+					"Only fields, functions, and trait implementations can be declared in class '{}'.",
+					name,
+				).into()),
+			}
+		}
+
+		for trait_id in trait_ids {
+			Self::check_trait_implemented(class_id, trait_id, definitions)?;
+		}
+
+		Ok(())
+	}
+
+
+	/// Compiles a constructor for a class declared in a script.
+	/// 
+	/// The constructor is defined in the class as a [`CONSTRUCTOR_NAME`]
+	/// function. It initializes the fields of the new instance before
+	/// running `body`, which can access the instance through `self`.
+	fn cmp_constructor_decl(
+		&mut self,
+		class_id: ClassId,
+		parameters: Vec<(String, ClassId)>,
+		body: &CodeBlock,
+		field_declarations: &[&VarDeclaration],
+		definitions: &mut Definitions,
+	) -> Result<(), QuMsg> {
+		let identity = FunctionIdentity {
+			name: CONSTRUCTOR_NAME.into(),
+			parameters: parameters.iter().map(|param| param.1).collect(),
+			return_type: class_id,
+		};
+		let func_id = definitions.define_function_in_item(
+			ItemId::Class(class_id),
+			FunctionMetadata {
+				identity,
+				code_block: FunctionReference::Internal(
+					definitions.byte_code_blocks.len()
+				),
+				..Default::default()
+			},
+			None,
+			false,
+		)?;
+
+		self.cmp_fn_body(
+			func_id,
+			&parameters,
+			Some(field_declarations),
+			body,
+			definitions,
+		)
+	}
+
+
+	/// Compiles a trait declaration.
+	/// 
+	/// Functions declared in a trait have no body. Every class that
+	/// implements the trait has to implement them.
+	fn cmp_trait_decl(
+		&mut self,
+		trait_declaration: &TraitDeclaration,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let name = &trait_declaration.name.slice;
+		let Some(module_id) = self.context.get_module_root() else {
+			return Err(format!(
+				"Trait '{}' has to be declared in the outermost scope of a module.",
+				name,
+			).into());
+		};
+		if self.context.has_item(name, definitions) {
+			return Err(format!(
+				"An item by '{}' is already defined.", name,
+			).into());
+		}
+
+		let mut class = QuStruct::new(
+			name.clone(),
+			DYN_HEADER_SIZE + DYN_PAYLOAD_SIZE,
+		);
+		class.is_trait = true;
+		class.from_script = true;
+		let trait_id = definitions.define_class(module_id, class)?;
+
+		for function in &trait_declaration.functions {
+			let mut parameters = vec![trait_id];
+			parameters.extend(
+				self.cmp_fn_parameters(&function.identity, definitions)?
+					.iter()
+					.map(|param| param.1)
+			);
+			let return_type = self.class_id_from_option_identity(
+				&function.identity.return_type,
+				definitions,
+			)?;
+			definitions.define_function_in_item(
+				ItemId::Module(module_id),
+				FunctionMetadata {
+					identity: FunctionIdentity {
+						name: function.identity.name.slice.clone(),
+						parameters: parameters.into_boxed_slice(),
+						return_type,
+					},
+					code_block: FunctionReference::Abstract,
+					..Default::default()
+				},
+				Some(trait_id),
+				true,
+			)?;
+		}
+
+		Ok(QuAsmBuilder::new())
+	}
+
+
+	/// Returns the id of the trait with the given name.
+	fn find_trait(
+		&self,
+		name: &QuToken,
+		definitions: &Definitions,
+	) -> Result<ClassId, QuMsg> {
+		let item = self.context.find_item_filtered_maybe(
+			&name.slice,
+			&|item| matches!(item, ItemId::Class(_)),
+			definitions,
+		);
+		match item {
+			Some(ItemId::Class(id)) if definitions.get_class(id)?.is_trait => {
+				Ok(id)
+			},
+			Some(_) => Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"'{}' can't be implemented because it isn't a trait.",
+				name.slice,
+			))),
+			None => Err(QuMsg::of_kind(QuErrorKind::UndefinedType, format!(
+				"Could not find a trait named '{}'.",
+				name.slice,
+			))),
+		}
+	}
+
+
+	/// Returns an error if a class doesn't implement every function without
+	/// a body of a trait.
+	fn check_trait_implemented(
+		class_id: ClassId,
+		trait_id: ClassId,
+		definitions: &Definitions,
+	) -> Result<(), QuMsg> {
+		let class = definitions.get_class(class_id)?;
+		let implementation = class.common
+			.get_trait_implementation(trait_id)?;
+		let mut missing:Vec<FunctionId> = implementation.functions
+			.iter()
+			.filter(|(trait_fn_id, impl_fn_id)| trait_fn_id == impl_fn_id)
+			.map(|(trait_fn_id, _)| *trait_fn_id)
+			.collect();
+		missing.sort();
+		for trait_fn_id in missing {
+			let trait_fn = definitions.get_function(trait_fn_id)?;
+			if let FunctionReference::Abstract = trait_fn.code_block {
+				return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+					"Class '{}' doesn't implement function '{}' required by trait '{}'.",
+					class.common.name,
+					trait_fn.identity.display_pretty(definitions),
+					definitions.get_class(trait_id)?.common.name,
+				)));
+			}
+		}
+
+		Ok(())
+	}
+
+
+	/// Assembles creating the default value of a class at `reg`.
+	/// 
+	/// The default value is made by calling the constructor of the class
+	/// that takes no arguments. Classes without one default to zero.
+	fn asm_default_value(
+		class_id: ClassId,
+		reg: RegId,
+		definitions: &mut Definitions,
+	) -> QuAsmBuilder {
+		let constructor_sig = FunctionIdentity {
+			name: CONSTRUCTOR_NAME.into(),
+			..Default::default()
+		};
+		let constructor_id = definitions.get_class(class_id)
+			.ok()
+			.and_then(|class| {
+				class.common.function_groups_map.get(CONSTRUCTOR_NAME)
+			})
+			.and_then(|group_id| {
+				definitions.function_groups[*group_id]
+					.get_fn_id_maybe(&constructor_sig, definitions)
+			});
+
+		match constructor_id {
+			Some(constructor_id) => {
+				let mut b = QuAsmBuilder::new();
+				b.add_return_op(
+					QuOp::Call(constructor_id, Box::new([]), reg),
+					class_id,
+				);
+				b
+			},
+			None => QuAsmBuilder::from_const(
+				definitions.get_private_constant(
+					"0",
+					&||{0},
+				),
+				reg,
+				definitions,
+			),
+		}
+	}
+
+
+	/// Compiles a copy from one register to another.
+	fn cmp_copy_register(
+		&self,
+		from: TypedRegId,
+		to: RegId,
+		definitions: &mut Definitions
+	) -> Result<QuAsmBuilder, QuMsg>{
+		let identity = &FunctionIdentity {
+			name: "copy".into(),
+			parameters: Box::new([from.class_id()]),
+			return_type: Default::default(),
+		};
+		
+		let group_id = definitions.get_class(from.class_id())?
+			.common
+			.get_function_group_id(&identity.name)?;
+		let fn_id = definitions
+			.get_function_group(group_id)?
+			.get_fn_id(&identity, &definitions)?;
+		let fn_data = definitions.get_function(fn_id)?;
+
+		let mut b: QuAsmBuilder = QuAsmBuilder::new();
+		b.add_op(QuOp::Call(
+			fn_id,
+			Box::new([from.into()]),
+			to.into(),
+		));
+		b.set_output(to, fn_data.identity.return_type);
+
+		Ok(b)
+	}
+
+
+	/// Compiles an expression into bytecode.
+	fn cmp_expr(
+		&mut self,
+		expression:&Expression,
+		reg: RegId,
+		reg_type: Option<ClassId>,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		if let Some(reg_type) = reg_type {
+			if definitions.get_class(reg_type)?.is_trait {
+				return self.cmp_expr_dyn(
+					expression,
+					reg,
+					reg_type,
+					definitions,
+				).map_err(|msg| msg.or_span(expression.span()));
+			}
+		}
+
+		let mut builder = match expression {
+			Expression::Call(
+				call_expression,
+			) => {
+				let mut parameters = vec![];
+				for parameter in &call_expression.parameters.elements {
+					parameters.push(parameter);
+				}
+				self.cmp_fn_call(
+					&call_expression,
+					reg,
+					definitions,
+				)
+			}
+			Expression::DotIndex(
+				dot_index,
+			) => self.cmp_expr_dot_index(
+				dot_index,
+				reg,
+				definitions,
+			),
+			Expression::Index(
+				index,
+			) => self.cmp_expr_index(
+				index,
 				reg,
 				definitions,
 			),
+			Expression::Lambda(
+				lambda,
+			) => self.cmp_expr_lambda(
+				lambda,
+				reg,
+				definitions,
+			),
+			Expression::List(
+				list,
+			) => self.cmp_expr_list(
+				list,
+				reg,
+				reg_type,
+				definitions,
+			),
+			Expression::Map(
+				map,
+			) => self.cmp_expr_map(
+				map,
+				reg,
+				reg_type,
+				definitions,
+			),
 			Expression::Operation(
 				operation_expression,
 			) => self.cmp_expr_operation(
@@ -1842,28 +2858,34 @@ pub struct QuCompiler {
 			Expression::Bool(
 				bool_literal,
 			) => self.cmp_expr_bool(&bool_literal.value, reg, definitions),
+			Expression::Unary(
+				unary,
+			) => self.cmp_expr_unary(unary, reg, definitions),
 			Expression::Tuple(
 				tuple,
 			) => self.cmp_expr_tuple(
-					&tuple.elements,
-					reg,
-					definitions,
-				),
+				tuple,
+				reg,
+				reg_type,
+				definitions,
+			),
 			Expression::Var(
 				var_expression,
 			) => self.cmp_expr_var(
 					&var_expression,
 					reg,
+					reg_type,
 					definitions,
 				),
 			Expression::Number(number) =>
 				self.cmp_expr_number(
-					&number.value,
-					&number.decimal,
+					number.number,
 					reg,
 					reg_type,
 					definitions,
 				),
+			Expression::String(string) =>
+				self.cmp_expr_string(&string.text, reg, definitions),
     		Expression::As(as_expr) => {
 				self.context.open_scope();
 				let mut b = self.cmp_alloc_expression(
@@ -1873,31 +2895,121 @@ pub struct QuCompiler {
 				let ItemId::Class(class_id) = self.context
 					.find_item(&as_expr.right.slice, definitions)?
 					else {
-						todo!("Add err msg");
+						return Err(QuMsg::undefined_type_access(
+							&as_expr.right.slice
+						));
 					};
 				b.as_type = Some(class_id);
 				self.context.close_scope();
 				Ok(b)
 				
 			},
-			}?;
+			}.map_err(|msg| msg.or_span(expression.span()))?;
+		builder.set_span(expression.span());
 
 		// Type check
 		let void_id = definitions.class_id::<Void>()?;
 		if builder.return_reg.class_id() != void_id {
 			if let Some(reg_type) = reg_type {
 				if builder.return_reg.class_id() != reg_type {
-					return Err(format!(
-						"Attempted to assign a value of type '{}' to a location of type '{}'.",
-						definitions.get_class(builder.return_reg.class_id())?
+					let msg = QuMsg::assign_type_mismatch(
+						&definitions.get_class(builder.return_reg.class_id())?
 							.common.name,
-						definitions.get_class(reg_type)?.common.name,
-					).into());
+						&definitions.get_class(reg_type)?.common.name,
+					);
+					return Err(msg.or_span(expression.span()));
 				}
 			}
 		}
 
-		Ok(builder)
+		Ok(builder)
+	}
+
+
+	/// Compiles an expression into a trait-typed location.
+	fn cmp_expr_dyn(
+		&mut self,
+		expression: &Expression,
+		reg: RegId,
+		trait_id: ClassId,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		self.context.open_scope();
+		if reg == self.context.next_reg() {
+			self.context.allocate_at(reg, trait_id, definitions)?;
+		}
+		let b = self.cmp_expr(
+			expression,
+			RegId(reg.0 + DYN_HEADER_SIZE),
+			None,
+			definitions,
+		)?;
+		let b = self.asm_upcast(b, reg, trait_id, definitions)?;
+		self.context.close_scope();
+
+		Ok(b)
+	}
+
+
+	/// Assembles converting the value output by `b` into a trait-typed value
+	/// at `reg`.
+	/// 
+	/// Trait-typed values start with a header holding the [`ClassId`] of their
+	/// value, followed by the value itself.
+	fn asm_upcast(
+		&mut self,
+		mut b: QuAsmBuilder,
+		reg: RegId,
+		trait_id: ClassId,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let class_id = b.return_reg.class_id();
+
+		if class_id == trait_id {
+			// Value is already trait-typed, move it
+			if b.return_reg.index() != reg {
+				let b_copy = self.cmp_copy_register(
+					b.return_reg,
+					reg,
+					definitions,
+				)?;
+				b.add_builder(b_copy);
+				b.set_output(reg, trait_id);
+			}
+			return Ok(b);
+		}
+
+		let class = definitions.get_class(class_id)?;
+		if class.is_trait || !class_id.is(trait_id, definitions) {
+			return Err(QuMsg::assign_type_mismatch(
+				&class.common.name,
+				&definitions.get_class(trait_id)?.common.name,
+			));
+		}
+		if class.size as usize > DYN_PAYLOAD_SIZE {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Values of type '{}' are too big to be stored as a '{}'.",
+				class.common.name,
+				definitions.get_class(trait_id)?.common.name,
+			)));
+		}
+		let class_const_id = class.common.const_id;
+
+		let payload_reg = RegId(reg.0 + DYN_HEADER_SIZE);
+		if b.return_reg.index() != payload_reg {
+			let b_copy = self.cmp_copy_register(
+				b.return_reg,
+				payload_reg,
+				definitions,
+			)?;
+			b.add_builder(b_copy);
+		}
+		b.add_op(QuOp::LoadConstant(class_const_id, reg));
+		b.set_output(reg, trait_id);
+		b.constant = None;
+		b.as_type = None;
+
+		Ok(b)
 	}
 
 
@@ -1908,37 +3020,510 @@ pub struct QuCompiler {
 		reg: RegId,
 		definitions: &mut Definitions,
 	)-> Result<QuAsmBuilder, QuMsg> {
-		let b_left = self.cmp_alloc_expression(
-			&dot_index.left,
+		self.context.open_scope();
+		let (mut b, _) = self.cmp_field_location(dot_index, definitions)?;
+		let field_reg = b.return_reg;
+		if field_reg.index() != reg {
+			let b_copy = self.cmp_copy_register(
+				field_reg,
+				reg,
+				definitions,
+			)?;
+			b.add_builder(b_copy);
+			b.set_output(reg, field_reg.class_id());
+		}
+		self.context.close_scope();
+
+		Ok(b)
+	}
+
+
+	/// Compiles an index into a value (Ex: foo[0]).
+	/// 
+	/// Indexing calls the `get` function of the value's class, which is
+	/// given the value and the index.
+	fn cmp_expr_index(
+		&mut self,
+		index: &IndexExpression,
+		reg: RegId,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		self.context.open_scope();
+		let mut b = self.cmp_alloc_expression(&index.left, definitions)?;
+		let left = b.return_reg;
+		let (b_index, get_id) = self.cmp_index_value(
+			index, left, definitions,
+		)?;
+		let item_class = definitions.get_function(get_id)?
+			.identity
+			.return_type;
+
+		let index_reg = b_index.return_reg.index();
+		b.add_builder(b_index);
+		b.add_op(QuOp::Call(
+			get_id,
+			Box::new([left.index(), index_reg]),
+			reg,
+		));
+		self.context.close_scope();
+
+		b.set_output(reg, item_class);
+		Ok(b)
+	}
+
+
+	/// Compiles the index of an index expression. Returns the builder and
+	/// the `get` function of `left`'s class that accepts the index.
+	fn cmp_index_value(
+		&mut self,
+		index: &IndexExpression,
+		left: TypedRegId,
+		definitions: &mut Definitions,
+	) -> Result<(QuAsmBuilder, FunctionId), QuMsg> {
+		let type_name = definitions.get_class(left.class_id())?
+			.common
+			.name
+			.clone();
+		if definitions.get_class(left.class_id())?
+			.common
+			.get_function_group_id("get")
+			.is_err()
+		{
+			return Err(QuMsg::not_indexable(&type_name)
+				.or_span(index.left.span()));
+		}
+
+		let b = self.cmp_alloc_expression(&index.index, definitions)?;
+		let index_class = b.return_reg.class_id();
+		let get_id = Self::find_method(
+			left.class_id(),
+			"get",
+			&[left.class_id(), index_class],
 			definitions,
+		).map_err(|_| QuMsg::invalid_index_type(
+			&type_name,
+			&definitions.get_class(index_class).unwrap().common.name,
+		).or_span(index.index.span()))?;
+		Ok((b, get_id))
+	}
+
+
+	/// Compiles a list literal (Ex: [1, 2, 3]).
+	/// 
+	/// The type of the list's items is taken from `reg_type` if it's a list,
+	/// otherwise from the first item. The list is built in a temporary
+	/// register and then moved to `reg`, because the items may be stored at
+	/// `reg` while the list is being built.
+	fn cmp_expr_list(
+		&mut self,
+		list: &ListLiteral,
+		reg: RegId,
+		reg_type: Option<ClassId>,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		self.context.open_scope();
+		let mut item_class = match reg_type {
+			Some(reg_type) => Self::list_item_class(reg_type, definitions),
+			None => None,
+		};
+
+		// Items
+		let mut b = QuAsmBuilder::new();
+		let mut item_regs = Vec::with_capacity(list.elements.len());
+		for element in &list.elements {
+			let item_reg = self.context.next_reg();
+			let b_item = self.cmp_expr(
+				element,
+				item_reg,
+				item_class,
+				definitions,
+			)?;
+			let class_id = b_item.return_reg.class_id();
+			self.context.allocate_at(item_reg, class_id, definitions)?;
+			item_class = Some(class_id);
+			item_regs.push(item_reg);
+			b.add_builder(b_item);
+		}
+		let Some(item_class) = item_class else {
+			return Err(QuMsg::untyped_empty_collection(
+				"var items List[int] = []",
+			).or_span(list.span));
+		};
+		let list_class = Self::list_class(item_class, definitions)?;
+
+		// Build the list
+		let list_reg = self.context.next_reg();
+		self.context.allocate_at(list_reg, list_class, definitions)?;
+		let constructor_id = Self::find_method(
+			list_class, CONSTRUCTOR_NAME, &[], definitions,
 		)?;
-		let left_class = definitions.get_class(
-			b_left.return_reg.class_id()
+		let append_id = Self::find_method(
+			list_class, "append", &[list_class, item_class], definitions,
 		)?;
-		let indexed_item = left_class.get_item_id(
-			&dot_index.right.slice
+		b.add_op(QuOp::Call(constructor_id, Box::new([]), list_reg));
+		for item_reg in item_regs {
+			b.add_op(QuOp::Call(
+				append_id,
+				Box::new([list_reg, item_reg]),
+				self.context.next_reg(),
+			));
+		}
+		let b_copy = self.cmp_copy_register(
+			TypedRegId::new(list_reg, list_class),
+			reg,
+			definitions,
 		)?;
+		b.add_builder(b_copy);
+		self.context.close_scope();
 
-		let builder = match indexed_item {
-			ItemId::Class(_) => todo!(),
-			ItemId::Constant(_) => todo!(),
-			ItemId::Function(_) => todo!(),
-			ItemId::FunctionGroup(_) => todo!(),
-			ItemId::Module(_) => todo!(),
-			ItemId::StaticVariable(_) => todo!(),
-			ItemId::Variable(variable_id) => {
-				let variable = self.context
-					.get_variable(variable_id)?;
-				self.cmp_copy_register(
-					variable.stack_id,
-					reg,
-					definitions
-				)?
-			},
-			ItemId::None => todo!(),
+		b.set_output(reg, list_class);
+		Ok(b)
+	}
+
+
+	/// Compiles a map literal (Ex: {"a": 1, "b": 2}).
+	/// 
+	/// Like list literals, the types of the keys and values are taken from
+	/// `reg_type` if it's a map, otherwise from the first entry.
+	fn cmp_expr_map(
+		&mut self,
+		map: &MapLiteral,
+		reg: RegId,
+		reg_type: Option<ClassId>,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		self.context.open_scope();
+		let (mut key_class, mut value_class) = match reg_type {
+			Some(reg_type) => Self::map_entry_classes(reg_type, definitions)
+				.map_or((None, None), |(k, v)| (Some(k), Some(v))),
+			None => (None, None),
 		};
 
-		Ok(builder)
+		// Entries
+		let mut b = QuAsmBuilder::new();
+		let mut entry_regs = Vec::with_capacity(map.entries.len());
+		for (key, value) in &map.entries {
+			let key_reg = self.context.next_reg();
+			let b_key = self.cmp_expr(key, key_reg, key_class, definitions)?;
+			let class_id = b_key.return_reg.class_id();
+			self.context.allocate_at(key_reg, class_id, definitions)?;
+			key_class = Some(class_id);
+			b.add_builder(b_key);
+
+			let value_reg = self.context.next_reg();
+			let b_value = self.cmp_expr(
+				value,
+				value_reg,
+				value_class,
+				definitions,
+			)?;
+			let class_id = b_value.return_reg.class_id();
+			self.context.allocate_at(value_reg, class_id, definitions)?;
+			value_class = Some(class_id);
+			b.add_builder(b_value);
+
+			entry_regs.push((key_reg, value_reg));
+		}
+		let (Some(key_class), Some(value_class)) = (key_class, value_class)
+		else {
+			return Err(QuMsg::untyped_empty_collection(
+				"var ages Map[str, int] = {}",
+			).or_span(map.span));
+		};
+		let map_class = Self::map_class(key_class, value_class, definitions)?;
+
+		// Build the map
+		let map_reg = self.context.next_reg();
+		self.context.allocate_at(map_reg, map_class, definitions)?;
+		let constructor_id = Self::find_method(
+			map_class, CONSTRUCTOR_NAME, &[], definitions,
+		)?;
+		let set_id = Self::find_method(
+			map_class,
+			"set",
+			&[map_class, key_class, value_class],
+			definitions,
+		)?;
+		b.add_op(QuOp::Call(constructor_id, Box::new([]), map_reg));
+		for (key_reg, value_reg) in entry_regs {
+			b.add_op(QuOp::Call(
+				set_id,
+				Box::new([map_reg, key_reg, value_reg]),
+				self.context.next_reg(),
+			));
+		}
+		let b_copy = self.cmp_copy_register(
+			TypedRegId::new(map_reg, map_class),
+			reg,
+			definitions,
+		)?;
+		b.add_builder(b_copy);
+		self.context.close_scope();
+
+		b.set_output(reg, map_class);
+		Ok(b)
+	}
+
+
+	/// Returns the class of maps from `key_class` to `value_class`, defining
+	/// it if it isn't yet.
+	/// 
+	/// Maps with keys of registered classes are registered with their own
+	/// Rust types. Other maps are defined in the fundamentals module, like
+	/// tuples, and hash and compare their keys through the key class's `Hash`
+	/// and `Equal` implementations (See [`objects::DynFunction`]).
+	fn map_class(
+		key_class: ClassId,
+		value_class: ClassId,
+		definitions: &mut Definitions,
+	) -> Result<ClassId, QuMsg> {
+		let key_name = definitions.get_class(key_class)?.common.name.clone();
+		let value_name = definitions.get_class(value_class)?.common.name.clone();
+		let name = format!("Map[{key_name}, {value_name}]");
+		let found = definitions.find_class_id(&name).ok()
+			.filter(|id| {
+				Self::map_entry_classes(*id, definitions)
+					== Some((key_class, value_class))
+			});
+		if let Some(class_id) = found {
+			return Ok(class_id);
+		}
+
+		// Keys are hashed and compared by the class's own functions
+		let implements = |trait_id, fn_name, parameters: &[ClassId]| {
+			definitions.trait_function_of(trait_id, key_class, fn_name)
+				.and_then(|fn_id| definitions.get_function(fn_id))
+				.is_ok_and(|function| {
+					*function.identity.parameters == *parameters
+						&& !matches!(
+							function.code_block,
+							FunctionReference::Abstract,
+						)
+				})
+		};
+		let hash_id = definitions.class_id::<QuHash>()?;
+		let equal_id = definitions.class_id::<QuEqual>()?;
+		if !implements(hash_id, "hash", &[key_class])
+			|| !implements(equal_id, "equal", &[key_class, key_class])
+		{
+			return Err(QuMsg::unhashable_map_key(&key_name));
+		}
+		let keys_class = Self::list_class(key_class, definitions)?;
+		let keys_iterator_class = {
+			let iter_id = Self::find_method(
+				keys_class, "iter", &[keys_class], definitions,
+			)?;
+			definitions.get_function(iter_id)?.identity.return_type
+		};
+
+		let module_id = *definitions.module_map
+			.get(FUNDAMENTALS_MODULE)
+			.unwrap();
+		let mut class = QuStruct::new(name, size_of::<objects::DynMap>());
+		class.needs_drop = true;
+		let map_class = definitions.define_class_with_copy(
+			module_id,
+			class,
+			FunctionReference::Dyn(DynFunction::MapCopy),
+		)?;
+		let int = definitions.class_id::<Int>()?;
+		let bool = definitions.class_id::<Bool>()?;
+		let void = definitions.class_id::<Void>()?;
+		for (name, parameters, return_type, function) in [
+			(CONSTRUCTOR_NAME, vec![], map_class, DynFunction::MapNew),
+			("len", vec![map_class], int, DynFunction::MapLen),
+			("has", vec![map_class, key_class], bool, DynFunction::MapHas),
+			("remove", vec![map_class, key_class], bool, DynFunction::MapRemove),
+			("keys", vec![map_class], keys_class, DynFunction::MapKeys),
+			("iter", vec![map_class], keys_iterator_class, DynFunction::MapIter),
+			("get", vec![map_class, key_class], value_class, DynFunction::MapGet),
+			(
+				"set",
+				vec![map_class, key_class, value_class],
+				void,
+				DynFunction::MapSet,
+			),
+		] {
+			definitions.define_dyn_function(
+				map_class, name, parameters, return_type, function,
+			)?;
+		}
+		Ok(map_class)
+	}
+
+
+	/// Returns the classes of the keys and values of `map_class`, or
+	/// [`None`] if it isn't a map.
+	fn map_entry_classes(
+		map_class: ClassId,
+		definitions: &Definitions,
+	) -> Option<(ClassId, ClassId)> {
+		let group_id = definitions.get_class(map_class).ok()?
+			.common
+			.get_function_group_id("get")
+			.ok()?;
+		let group = definitions.get_function_group(group_id).ok()?;
+		for (identity, _) in &group.map {
+			let [first, key_class] = identity.parameters[..] else {
+				continue;
+			};
+			if first != map_class {
+				continue;
+			}
+			let value_class = identity.return_type;
+			let key_name = &definitions.get_class(key_class).ok()?.common.name;
+			let value_name = &definitions.get_class(value_class).ok()?
+				.common
+				.name;
+			let is_map = definitions
+				.find_class_id(&format!("Map[{key_name}, {value_name}]"))
+				.is_ok_and(|id| id == map_class);
+			if is_map {
+				return Some((key_class, value_class));
+			}
+		}
+		None
+	}
+
+
+	/// Returns the class of lists with items of `item_class`, defining it if
+	/// it isn't yet.
+	/// 
+	/// Lists of registered classes are registered with their own Rust types.
+	/// Other lists, and their iterators, are defined in the fundamentals
+	/// module like tuples (See [`objects::DynFunction`]).
+	fn list_class(
+		item_class: ClassId,
+		definitions: &mut Definitions,
+	) -> Result<ClassId, QuMsg> {
+		let item_name = definitions.get_class(item_class)?.common.name.clone();
+		let name = format!("List[{item_name}]");
+		let found = definitions.find_class_id(&name).ok()
+			.filter(|id| {
+				Self::list_item_class(*id, definitions) == Some(item_class)
+			});
+		if let Some(class_id) = found {
+			return Ok(class_id);
+		}
+
+		let module_id = *definitions.module_map
+			.get(FUNDAMENTALS_MODULE)
+			.unwrap();
+		let mut class = QuStruct::new(name, size_of::<objects::DynList>());
+		class.needs_drop = true;
+		let list_class = definitions.define_class_with_copy(
+			module_id,
+			class,
+			FunctionReference::Dyn(DynFunction::ListCopy),
+		)?;
+		let mut class = QuStruct::new(
+			format!("ListIterator[{item_name}]"),
+			size_of::<objects::DynListIterator>(),
+		);
+		class.needs_drop = true;
+		let iterator_class = definitions.define_class_with_copy(
+			module_id,
+			class,
+			FunctionReference::Dyn(DynFunction::IteratorCopy),
+		)?;
+		let int = definitions.class_id::<Int>()?;
+		let bool = definitions.class_id::<Bool>()?;
+		let void = definitions.class_id::<Void>()?;
+		for (class_id, name, parameters, return_type, function) in [
+			(list_class, CONSTRUCTOR_NAME, vec![], list_class, DynFunction::ListNew),
+			(list_class, "len", vec![list_class], int, DynFunction::ListLen),
+			(
+				list_class,
+				"append",
+				vec![list_class, item_class],
+				void,
+				DynFunction::ListAppend,
+			),
+			(list_class, "pop", vec![list_class], item_class, DynFunction::ListPop),
+			(
+				list_class,
+				"get",
+				vec![list_class, int],
+				item_class,
+				DynFunction::ListGet,
+			),
+			(
+				list_class,
+				"set",
+				vec![list_class, int, item_class],
+				void,
+				DynFunction::ListSet,
+			),
+			(
+				list_class,
+				"iter",
+				vec![list_class],
+				iterator_class,
+				DynFunction::ListIter,
+			),
+			(
+				iterator_class,
+				"current",
+				vec![iterator_class],
+				item_class,
+				DynFunction::IteratorCurrent,
+			),
+		] {
+			definitions.define_dyn_function(
+				class_id, name, parameters, return_type, function,
+			)?;
+		}
+
+		let iterator_trait = definitions.class_id::<QuIterator>()?;
+		definitions.impl_trait_in_item(iterator_trait, iterator_class)?;
+		definitions.define_function_implementation(
+			iterator_class,
+			iterator_trait,
+			ItemId::Module(module_id),
+			FunctionMetadata {
+				identity: FunctionIdentity {
+					name: "next".into(),
+					parameters: [iterator_class].into(),
+					return_type: bool,
+				},
+				code_block: FunctionReference::Dyn(DynFunction::IteratorNext),
+				..Default::default()
+			},
+		)?;
+		Ok(list_class)
+	}
+
+
+	/// Returns whether `class_id` is a list, map or list iterator class
+	/// defined on demand. Like script classes, their functions are only found
+	/// through the class, because they don't belong to a module a script
+	/// imports.
+	fn is_dyn_class(class_id: ClassId, definitions: &Definitions) -> bool {
+		Self::find_method(class_id, "copy", &[class_id], definitions)
+			.and_then(|fn_id| definitions.get_function(fn_id))
+			.is_ok_and(|function| {
+				matches!(function.code_block, FunctionReference::Dyn(_))
+			})
+	}
+
+
+	/// Returns the class of the items of `list_class`, or [`None`] if it
+	/// isn't a list.
+	fn list_item_class(
+		list_class: ClassId,
+		definitions: &Definitions,
+	) -> Option<ClassId> {
+		let int = definitions.class_id::<Int>().ok()?;
+		let get_id = Self::find_method(
+			list_class, "get", &[list_class, int], definitions,
+		).ok()?;
+		let item_class = definitions.get_function(get_id).ok()?
+			.identity
+			.return_type;
+		let item_name = &definitions.get_class(item_class).ok()?.common.name;
+		definitions.find_class_id(&format!("List[{item_name}]")).ok()
+			.filter(|id| *id == list_class)
+			.map(|_| item_class)
 	}
 
 
@@ -1960,27 +3545,39 @@ pub struct QuCompiler {
 			QuOperator::Div => QuDiv::id(),
 			QuOperator::Mod => QuMod::id(),
 			QuOperator::Pow => QuPow::id(),
-			QuOperator::Sqrt => todo!(),
+			QuOperator::Sqrt => QuSqrt::id(),
 			QuOperator::Less => QuLesser::id(),
-			QuOperator::LessEq => todo!(),
+			QuOperator::LessEq => QuLesserEq::id(),
 			QuOperator::Great => QuGreater::id(),
-			QuOperator::GreatEq => todo!(),
+			QuOperator::GreatEq => QuGreaterEq::id(),
 			QuOperator::Eq => QuEqual::id(),
 			QuOperator::NotEq => QuNotEqual::id(),
-			QuOperator::And => todo!(),
-			QuOperator::Or => todo!(),
+			QuOperator::And | QuOperator::Or => {
+				let b = self.asm_expr_logic(
+					operator,
+					left,
+					right,
+					reg,
+					definitions,
+				);
+				self.context.close_scope();
+				return b;
+			},
+			QuOperator::Not | QuOperator::Neg => unreachable!(
+				"Unary operators are compiled by cmp_expr_unary"
+			),
 			QuOperator::Is => {
 				let left_is_dynamic = definitions
 					.get_class(left.return_reg.class_id())?
 					.is_trait;
 
 				if right.return_reg.class_id() != Class::id() {
-					return Err(format!(
+					return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
 						"The 'is' expression expected right to be a type, but it's a {}",
 						definitions
 							.get_class(right.return_reg.class_id())?
 							.common.name
-					).into())
+					)))
 				}
 
 				let b = match (left_is_dynamic, &right) {
@@ -2037,18 +3634,121 @@ pub struct QuCompiler {
 							definitions,
 						)?
 					}
-					_ => todo!("Handle comparisons with dunamic left expression")
+					(true, _) => {
+						// The header of a trait-typed value is the class of
+						// the value it holds
+						let mut left = left;
+						left.set_output(left.return_reg.index(), Class::id());
+						left.as_type = None;
+						self.asm_expr_operation(
+							QuOperator::Eq,
+							left,
+							right,
+							reg,
+							reg_type,
+							definitions,
+						)?
+					},
 				};
+				self.context.close_scope();
 				return Ok(b);
 			},
 		};
-		
+
+		let b = self.asm_operator_call(
+			trait_id,
+			&operator,
+			vec!(left, right),
+			reg,
+			definitions,
+		);
+		self.context.close_scope();
+		b
+	}
+
+
+	/// Assembles a short-circuiting *and* or *or* operation.
+	/// 
+	/// The right operand only runs if the left operand doesn't already decide
+	/// the result. Both operands are copied into `reg` after they are
+	/// evaluated, so `reg` may be a variable that the right operand reads.
+	fn asm_expr_logic(
+		&mut self,
+		operator: QuOperator,
+		left: QuAsmBuilder,
+		right: QuAsmBuilder,
+		reg: RegId,
+		definitions: &mut Definitions,
+	)-> Result<QuAsmBuilder, QuMsg> {
+		let bool_id = definitions.class_id::<Bool>()?;
+		for operand in [&left, &right] {
+			if operand.return_reg.class_id() != bool_id {
+				return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+					"The '{}' operator expected bool operands, but got a {}.",
+					operator.name(),
+					definitions
+						.get_class(operand.return_reg.class_id())?
+						.common.name,
+				)));
+			}
+		}
+
+		let left_reg = left.return_reg;
+		let right_reg = right.return_reg;
+		let mut b_copy_left = QuAsmBuilder::new();
+		if left_reg.index() != reg {
+			b_copy_left = self.cmp_copy_register(left_reg, reg, definitions)?;
+		}
+		let mut b_right = right;
+		if right_reg.index() != reg {
+			b_right.add_builder(
+				self.cmp_copy_register(right_reg, reg, definitions)?
+			);
+		}
+
+		let mut b = left;
+		b.add_op(Hold(left_reg.index()));
+		match operator {
+			QuOperator::And => {
+				// Skip right if left is false
+				b.add_op(JumpByIfNot(b_right.len() as isize + 1));
+				b.add_builder(b_right);
+				b.add_op(JumpBy(b_copy_left.len() as isize));
+				b.add_builder(b_copy_left);
+			},
+			QuOperator::Or => {
+				// Skip right if left is true
+				b.add_op(JumpByIfNot(b_copy_left.len() as isize + 1));
+				b.add_builder(b_copy_left);
+				b.add_op(JumpBy(b_right.len() as isize));
+				b.add_builder(b_right);
+			},
+			_ => unreachable!(),
+		}
+		b.set_output(reg, bool_id);
+		b.constant = None;
+		b.as_type = None;
+
+		return Ok(b);
+	}
+
+
+	/// Assembles a call to the function of an operator's trait (Ex: `add` of
+	/// `Add`) with the given operands as arguments.
+	fn asm_operator_call(
+		&mut self,
+		trait_id: ClassId,
+		operator: &QuOperator,
+		operands: Vec<QuAsmBuilder>,
+		reg: RegId,
+		definitions: &mut Definitions,
+	)-> Result<QuAsmBuilder, QuMsg> {
 		let sig = FunctionIdentity {
 			name: operator.name().into(),
-			parameters: Box::new([
-				left.return_reg.class_id(),
-				right.return_reg.class_id(),
-			]),
+			parameters: operands
+				.iter()
+				.map(|operand| operand.return_reg.class_id())
+				.collect(),
 			..Default::default()
 		};
 
@@ -2059,26 +3759,52 @@ pub struct QuCompiler {
 			.get_function_group(trait_group_id)?
 			.get_fn_id(&sig, definitions)?;
 
-		let left_data = definitions.get_class(left.return_reg.class_id())?;
-		let ItemId::Function(fn_id) = left_data
-			.common
-			.get_trait_implementation(trait_id)?
-			.get_implemented_item(ItemId::Function(trait_fn_id))?
-			else {unreachable!()};
+		let left_data = definitions.get_class(operands[0].return_reg.class_id())?;
+		let fn_id = if left_data.is_trait {
+			// The class of left is only known at runtime, call the trait's
+			// function virtually
+			trait_fn_id
+		} else {
+			let ItemId::Function(fn_id) = left_data
+				.common
+				.get_trait_implementation(trait_id)?
+				.get_implemented_item(ItemId::Function(trait_fn_id))?
+				else {unreachable!()};
+
+			// The trait's function takes any of its implementors, but the
+			// implementation only takes the classes it was written for
+			let parameters = &definitions.get_function(fn_id)?.identity.parameters;
+			for (operand, parameter) in operands.iter().zip(parameters.iter()) {
+				let class_id = operand.return_reg.class_id();
+				let matches = match definitions.get_class(*parameter)?.is_trait {
+					true => class_id.is(*parameter, definitions),
+					false => class_id == *parameter,
+				};
+				if !matches {
+					return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+						"The '{}' operator of '{}' expected a {} operand, but got a {}.",
+						operator.name(),
+						left_data.common.name,
+						definitions.get_class(*parameter)?.common.name,
+						definitions.get_class(class_id)?.common.name,
+					)));
+				}
+			}
+			fn_id
+		};
 		let fn_data = definitions.get_function(fn_id)?;
 
-		self.context.close_scope();
-		self.asm_fn_call(
+		return self.asm_fn_call(
 			None,
 			QuAsmBuilder::from_const(
 				fn_data.const_id,
 				0.into(),
 				&definitions,
 			),
-			vec!(left, right),
+			operands,
 			reg,
 			definitions,
-		)
+		);
 	}
 
 
@@ -2105,16 +3831,54 @@ pub struct QuCompiler {
 			definitions,
 		)?;
 
-		self.context.close_scope();
-
-		self.asm_expr_operation(
+		// The scope is closed after assembling so that any registers the
+		// operation allocates don't overlap with its operands.
+		let b = self.asm_expr_operation(
 			operator,
 			b_left,
 			b_right,
 			reg,
 			None,
 			definitions,
-		)
+		);
+
+		self.context.close_scope();
+
+		b
+	}
+
+
+	/// Compiles an expression with a single operand (Ex: not x, -x) into
+	/// bytecode.
+	fn cmp_expr_unary(
+		&mut self,
+		unary: &UnaryExpression,
+		reg: RegId,
+		definitions: &mut Definitions,
+	)-> Result<QuAsmBuilder, QuMsg> {
+		self.context.open_scope();
+
+		let operator = QuOperator::from_unary_symbol(&unary.operator.slice);
+		let trait_id = match operator {
+			QuOperator::Not => QuNot::id(),
+			QuOperator::Neg => QuNeg::id(),
+			_ => unreachable!(),
+		};
+		let b_operand = self.cmp_alloc_expression(
+			&unary.operand,
+			definitions,
+		)?;
+		let b = self.asm_operator_call(
+			trait_id,
+			&operator,
+			vec!(b_operand),
+			reg,
+			definitions,
+		);
+
+		self.context.close_scope();
+
+		b
 	}
 
 
@@ -2159,73 +3923,123 @@ pub struct QuCompiler {
 	fn cmp_expr_number(
 		// TODO: Change output_reg to a struct without type information
 		&mut self,
-		value:&QuToken,
-		decimal:&Option<QuToken>,
+		number:Number,
 		reg:RegId,
 		reg_type: Option<ClassId>,
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
-		let is_flaot = match reg_type {
-			Some(reg_type) =>
-				reg_type == definitions.class_id::<Float>()?,
-			None => decimal.is_some(),
-		};
-
-		let cosnt_id = match is_flaot {
-			false => {
-				let const_id = definitions.get_private_constant(
-					&value.slice,
-					&||{value.slice.parse::<Int>().expect(&format!(
-						"Could not convert text '{}' to int!",
-						value.slice
-					))}
-				);
-				const_id
-			},
-			true => {
-				let float_full = format!(
-					"{}.{}",
-					value.slice,
-					match decimal {
-						Some(tk) => &tk.slice,
-						None => ""
-					},
-				);
-				let const_id = definitions.get_private_constant(
-					&float_full,
-					&||{float_full.parse::<Float>().expect(&format!(
-						"Could not convert text '{}' to float!",
-						float_full
-					))}
-				);
-				const_id
-			},
+		// Ints are converted when a float is expected (Ex: var x float = 5)
+		let number = match (number, reg_type) {
+			(Number::Int(value), Some(reg_type))
+				if reg_type == definitions.class_id::<Float>()? =>
+				Number::Float(value as Float),
+			_ => number,
+		};
+
+		// Constants are named by their value, formatted so that ints and
+		// floats never share a name (Ex: 5 and 5.0)
+		let const_id = match number {
+			Number::Int(value) => definitions.get_private_constant(
+				&format!("{value}"),
+				&||{value},
+			),
+			Number::Float(value) => definitions.get_private_constant(
+				&format!("{value:?}"),
+				&||{value},
+			),
 		};
 
 		return Ok(QuAsmBuilder::from_const(
-			cosnt_id,
+			const_id,
 			reg,
 			&definitions,
 		));
 	}
 
 
-	/// Compiles a tuple construction into bytecode.
+	/// Compiles a constant string expression into bytecode.
+	fn cmp_expr_string(
+		&mut self,
+		text:&str,
+		reg:RegId,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let const_id = definitions.get_private_constant(
+			&format!("{:?}", text),
+			&||{Str::from(text)},
+		);
+
+		Ok(QuAsmBuilder::from_const(const_id, reg, definitions))
+	}
+
+
+	/// Compiles a tuple (Ex: 1, 2.0).
+	/// 
+	/// The types of the elements are taken from `reg_type` if it's a tuple
+	/// with as many elements. The elements are compiled into temporary
+	/// registers and then copied into the tuple at `reg`, because they may
+	/// read the value being replaced at `reg`.
 	fn cmp_expr_tuple(
 		&mut self,
-		_elements:&Vec<Expression>,
-		_reg:RegId,
-		_definitions: &mut Definitions,
+		tuple: &TupleExpression,
+		reg: RegId,
+		reg_type: Option<ClassId>,
+		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
-		todo!("Add tuples");
+		self.context.open_scope();
+		let element_types = reg_type
+			.and_then(|reg_type| {
+				Self::tuple_element_classes(reg_type, definitions)
+			})
+			.filter(|types| types.len() == tuple.len());
+
+		// Elements
+		let mut b = QuAsmBuilder::new();
+		let mut elements = Vec::with_capacity(tuple.len());
+		for (i, element) in tuple.elements.iter().enumerate() {
+			let element_reg = self.context.next_reg();
+			let b_element = self.cmp_expr(
+				element,
+				element_reg,
+				element_types.as_ref().map(|types| types[i]),
+				definitions,
+			)?;
+			let class_id = b_element.return_reg.class_id();
+			self.context.allocate_at(element_reg, class_id, definitions)?;
+			elements.push(TypedRegId::new(element_reg, class_id));
+			b.add_builder(b_element);
+		}
+		let element_classes: Vec<ClassId> = elements.iter()
+			.map(|element| element.class_id())
+			.collect();
+		let tuple_class = Self::tuple_class(&element_classes, definitions)?;
+
+		// Build the tuple
+		let fields = definitions.get_class(tuple_class)?.fields.clone();
+		for (element, field) in elements.into_iter().zip(fields) {
+			let b_copy = self.cmp_copy_register(
+				element,
+				RegId(reg.0 + field.offset),
+				definitions,
+			)?;
+			b.add_builder(b_copy);
+		}
+		self.context.close_scope();
+
+		b.set_output(reg, tuple_class);
+		Ok(b)
 	}
 
 
 	/// Compiles a variable-expression into bytecode.
+	/// 
+	/// Names of functions are compiled into function values. `reg_type` picks
+	/// which function an overloaded name refers to.
 	fn cmp_expr_var(
 		&mut self,
 		var_expression: &VarExpression,
 		reg: RegId,
+		reg_type: Option<ClassId>,
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
 
@@ -2253,8 +4067,28 @@ pub struct QuCompiler {
 				);
 				builder
 			},
-			ItemId::Function(_) => todo!(),
-			ItemId::FunctionGroup(_) => todo!(),
+			ItemId::Function(id) => self.cmp_function_value(
+				&[id],
+				&var_expression.name.slice,
+				reg,
+				reg_type,
+				definitions,
+			)?,
+			ItemId::FunctionGroup(id) => {
+				let functions: Vec<FunctionId> = definitions
+					.get_function_group(id)?
+					.map
+					.iter()
+					.map(|(_, fn_id)| *fn_id)
+					.collect();
+				self.cmp_function_value(
+					&functions,
+					&var_expression.name.slice,
+					reg,
+					reg_type,
+					definitions,
+				)?
+			},
 			ItemId::Module(id) => {
 				let builder = QuAsmBuilder::from_const(
 					definitions.get_module(id)?.common.const_id,
@@ -2263,7 +4097,14 @@ pub struct QuCompiler {
 				);
 				builder
 			},
-			ItemId::StaticVariable(_) => todo!(),
+			ItemId::StaticVariable(id) => {
+				let mut builder = QuAsmBuilder::new();
+				builder.add_return_op(
+					QuOp::LoadStatic(id, reg),
+					definitions.static_variables[id].class_id,
+				);
+				builder
+			},
 			ItemId::Variable(_) => {
 				let ItemId::Variable(var_id) = self.context.find_item(
 					&var_expression.name.slice,
@@ -2297,38 +4138,403 @@ pub struct QuCompiler {
 	}
 
 
-	/// Compiles an *if* statement into bytecode.
-	fn cmp_flow_if(
+	/// Compiles a reference to a named function into a function value.
+	/// 
+	/// `functions` are the functions the name refers to. If `reg_type` is a
+	/// function type then the function with its signature is picked,
+	/// otherwise the name has to refer to a single function.
+	fn cmp_function_value(
 		&mut self,
-		condition:&Expression,
-		body:&CodeScope,
+		functions: &[FunctionId],
+		name: &str,
+		reg: RegId,
+		reg_type: Option<ClassId>,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let function_type = match reg_type {
+			Some(reg_type) => definitions.get_class(reg_type)?
+				.function_type
+				.clone(),
+			None => None,
+		};
+		let mut matches = vec![];
+		for fn_id in functions {
+			let identity = &definitions.get_function(*fn_id)?.identity;
+			let is_match = match &function_type {
+				Some((parameters, return_type)) =>
+					identity.parameters == *parameters
+					&& identity.return_type == *return_type,
+				None => true,
+			};
+			if is_match {
+				matches.push(*fn_id);
+			}
+		}
+		let [fn_id] = matches[..] else {
+			return Err(QuMsg::ambiguous_function_value(name));
+		};
+
+		let identity = definitions.get_function(fn_id)?.identity.clone();
+		let class_id = Self::function_type_class(
+			&identity.parameters,
+			identity.return_type,
+			definitions,
+		)?;
+		let mut b = QuAsmBuilder::new();
+		b.add_return_op(MakeCallable(fn_id, Box::new([]), reg), class_id);
+		Ok(b)
+	}
+
+
+	/// Compiles a lambda (Ex: fn(x int) int: ...) into a function value.
+	/// 
+	/// The lambda's body is compiled into a function. Local variables of the
+	/// enclosing function that the body uses are captured as extra
+	/// parameters. They're copied when the lambda is made, so the lambda
+	/// doesn't see later assignments to them, and its own assignments only
+	/// change its copies. Static variables aren't captured.
+	fn cmp_expr_lambda(
+		&mut self,
+		lambda: &Lambda,
+		reg: RegId,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let mut parameters = self.cmp_fn_parameters(
+			&lambda.identity,
+			definitions,
+		)?;
+		let return_type = self.class_id_from_option_identity(
+			&lambda.identity.return_type,
+			definitions,
+		)?;
+		let parameter_classes: Vec<ClassId> = parameters.iter()
+			.map(|param| param.1)
+			.collect();
+		let class_id = Self::function_type_class(
+			&parameter_classes,
+			return_type,
+			definitions,
+		)?;
+
+		// Captures
+		let mut captures = vec![];
+		for name in lambda.body.code_block.free_names() {
+			if parameters.iter().any(|param| param.0 == name) {
+				continue;
+			}
+			let Some(variable_id) = self.context.find_frame_variable(&name)
+				else {continue};
+			let stack_id = self.context.get_variable(variable_id)?.stack_id;
+			captures.push(stack_id.index());
+			parameters.push((name, stack_id.class_id()));
+		}
+
+		let fn_id = definitions.add_function(FunctionMetadata {
+			identity: FunctionIdentity {
+				name: LAMBDA_NAME.into(),
+				parameters: parameters.iter().map(|param| param.1).collect(),
+				return_type,
+			},
+			code_block: FunctionReference::Internal(
+				definitions.byte_code_blocks.len()
+			),
+			..Default::default()
+		});
+		self.cmp_fn_body(
+			fn_id,
+			&parameters,
+			None,
+			&lambda.body.code_block,
+			definitions,
+		)?;
+
+		let mut b = QuAsmBuilder::new();
+		b.add_return_op(MakeCallable(fn_id, captures.into(), reg), class_id);
+		Ok(b)
+	}
+
+
+	/// Compiles initializing the fields of a new instance of a class
+	/// declared in a script.
+	fn cmp_field_defaults(
+		&mut self,
+		instance: TypedRegId,
+		field_declarations: &[&VarDeclaration],
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let mut b = QuAsmBuilder::new();
+		let fields = definitions.get_class(instance.class_id())?
+			.fields
+			.clone();
+		for (field, declaration) in fields.iter().zip(field_declarations) {
+			let reg = RegId(instance.index().0 + field.offset);
+			let b_field = match &declaration.initial_value {
+				Some(expression) => {
+					self.context.open_scope();
+					let b_field = self.cmp_expr(
+						expression,
+						reg,
+						Some(field.class_id),
+						definitions,
+					);
+					self.context.close_scope();
+					b_field?
+				},
+				None if definitions.get_class(field.class_id)?.is_trait => {
+					return Err(QuMsg::of_kind(QuErrorKind::InvalidVariableDefinition, format!(
+						"The field '{}' has to be given a value because its type, '{}', is a trait.",
+						field.name,
+						definitions.get_class(field.class_id)?.common.name,
+					)));
+				},
+				None => Self::asm_default_value(
+					field.class_id,
+					reg,
+					definitions,
+				),
+			};
+			b.add_builder(b_field);
+		}
+
+		Ok(b)
+	}
+
+
+	/// Compiles an assignment to a field. (Ex: foo.bar = 5).
+	fn cmp_field_assign(
+		&mut self,
+		field_assignment: &FieldAssignment,
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
-		// Get expression register
 		self.context.open_scope();
-		let mut b_cond = self.cmp_alloc_expression(
-			condition,
+		let (mut b, root) = self.cmp_field_location(
+			&field_assignment.field,
 			definitions,
 		)?;
-		// TODO: Allow conversion of other types to bools in condition
-		assert_eq!(b_cond.return_reg.class_id(), definitions.class_id::<Bool>()?);
+		if let FieldRoot::Temporary = root {
+			return Err(format!(
+				"Can't assign to field '{}' because it belongs to a temporary value.",
+				field_assignment.field.right.slice,
+			).into());
+		}
+		let field_reg = b.return_reg;
+		let b_value = self.cmp_expr(
+			&field_assignment.new_value,
+			field_reg.index(),
+			Some(field_reg.class_id()),
+			definitions,
+		)?;
+		b.add_builder(b_value);
+		if let FieldRoot::Static(variable_id, reg) = root {
+			b.add_op(QuOp::StoreStatic(reg, variable_id));
+		}
 		self.context.close_scope();
-	
-		// New frame for the code in the 'if' body
+
+		Ok(b)
+	}
+
+
+	/// Compiles an assignment to an index (Ex: foo[0] = 5).
+	/// 
+	/// Assigning calls the `set` function of the value's class, which is
+	/// given the value, the index, and the new item.
+	fn cmp_index_assign(
+		&mut self,
+		index_assignment: &IndexAssignment,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let index = &index_assignment.index;
 		self.context.open_scope();
-		let b:Result<QuAsmBuilder, QuMsg> = {
-			let body_code = self.cmp_scope(body, definitions)?;
+		let mut b = self.cmp_alloc_expression(&index.left, definitions)?;
+		let left = b.return_reg;
+		let (b_index, get_id) = self.cmp_index_value(
+			index, left, definitions,
+		)?;
+		let index_class = b_index.return_reg.class_id();
+		let item_class = definitions.get_function(get_id)?
+			.identity
+			.return_type;
+		let set_id = Self::find_method(
+			left.class_id(),
+			"set",
+			&[left.class_id(), index_class, item_class],
+			definitions,
+		)?;
 
-			let mut b = QuAsmBuilder::new();
-			b.add_op(JumpByIfNot(body_code.len() as isize));
-			b.add_builder(body_code);
+		let value_reg = self.context.next_reg();
+		let b_value = self.cmp_expr(
+			&index_assignment.new_value,
+			value_reg,
+			Some(item_class),
+			definitions,
+		)?;
+		self.context.allocate_at(value_reg, item_class, definitions)?;
+
+		let index_reg = b_index.return_reg.index();
+		b.add_builder(b_index);
+		b.add_builder(b_value);
+		b.add_op(QuOp::Call(
+			set_id,
+			Box::new([left.index(), index_reg, value_reg]),
+			self.context.next_reg(),
+		));
+		self.context.close_scope();
+
+		Ok(b)
+	}
+
+
+	/// Compiles getting the location of a field. (Ex: foo.bar).
+	/// 
+	/// The output of the returned builder is the location of the field. The
+	/// value the field belongs to may have to be stored in a temporary
+	/// register, so a scope should be opened before calling this.
+	fn cmp_field_location(
+		&mut self,
+		dot_index: &DotIndex,
+		definitions: &mut Definitions,
+	) -> Result<(QuAsmBuilder, FieldRoot), QuMsg> {
+		let (mut b, root) = match &dot_index.left {
+			Expression::DotIndex(left) => {
+				self.cmp_field_location(left, definitions)?
+			},
+			Expression::Var(var) => {
+				let item = self.context.find_item(
+					&var.name.slice,
+					definitions,
+				)?;
+				match item {
+					ItemId::Variable(id) => {
+						let mut b = QuAsmBuilder::new();
+						let stack_id = self.context.get_variable(id)?.stack_id;
+						b.set_output(stack_id.index(), stack_id.class_id());
+						(b, FieldRoot::Variable)
+					},
+					ItemId::StaticVariable(id) => {
+						let b = self.cmp_alloc_expression(
+							&dot_index.left,
+							definitions,
+						)?;
+						let reg = b.return_reg.index();
+						(b, FieldRoot::Static(id, reg))
+					},
+					_ => (
+						self.cmp_alloc_expression(
+							&dot_index.left,
+							definitions,
+						)?,
+						FieldRoot::Temporary,
+					),
+				}
+			},
+			left => (
+				self.cmp_alloc_expression(left, definitions)?,
+				FieldRoot::Temporary,
+			),
+		};
+
+		let left_reg = b.return_reg;
+		let class = definitions.get_class(left_reg.class_id())?;
+		let Some(field) = class.get_field(&dot_index.right.slice) else {
+			return Err(format!(
+				"Class '{}' has no field named '{}'.",
+				class.common.name,
+				dot_index.right.slice,
+			).into());
+		};
+		b.set_output(
+			RegId(left_reg.index().0 + field.offset),
+			field.class_id,
+		);
+		b.constant = None;
+		b.as_type = None;
+
+		Ok((b, root))
+	}
+
+
+	/// Compiles an *if* statement into bytecode.
+	fn cmp_flow_if(
+		&mut self,
+		flow:&FlowStatement,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		// Compile every conditional branch, if first followed by the elifs
+		let mut branches = Vec::with_capacity(flow.elif_branches.len() + 1);
+		for branch in [flow].into_iter().chain(flow.elif_branches.iter()) {
+			let cond = self.cmp_flow_condition(&branch.condition, definitions)?;
+			self.context.open_scope();
+			let body = self.cmp_scope(&branch.body, definitions);
+			self.context.close_scope();
+			branches.push((cond, body?));
+		}
 
-			Ok(b)
+		// Compile the else branch
+		let else_code = match &flow.else_branch {
+			Some(else_branch) => {
+				self.context.open_scope();
+				let body = self.cmp_scope(&else_branch.body, definitions);
+				self.context.close_scope();
+				Some(body?)
+			},
+			None => None,
 		};
+
+		// --- Assemble ---
+		// Each branch is laid out as:
+		//   condition, JumpByIfNot(past body), body, JumpBy(end of chain)
+		// The last branch skips the JumpBy when there's no else branch.
+		let branch_count = branches.len();
+		let mut remaining = else_code.as_ref().map_or(0, |b| b.len());
+		let mut pieces = Vec::with_capacity(branch_count);
+		for (i, (cond, body)) in branches.into_iter().enumerate().rev() {
+			let jumps_to_end = i+1 < branch_count || else_code.is_some();
+			let mut b = cond;
+			if jumps_to_end {
+				b.add_op(JumpByIfNot(body.len() as isize + 1));
+				b.add_builder(body);
+				b.add_op(JumpBy(remaining as isize));
+			} else {
+				b.add_op(JumpByIfNot(body.len() as isize));
+				b.add_builder(body);
+			}
+			remaining += b.len();
+			pieces.push(b);
+		}
+
+		let mut b = QuAsmBuilder::new();
+		for piece in pieces.into_iter().rev() {
+			b.add_builder(piece);
+		}
+		if let Some(else_code) = else_code {
+			b.add_builder(else_code);
+		}
+
+		return Ok(b);
+	}
+
+
+	/// Compiles the condition of a flow statement into bytecode.
+	fn cmp_flow_condition(
+		&mut self,
+		condition:&Expression,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		self.context.open_scope();
+		let b_cond = self.cmp_alloc_expression(
+			condition,
+			definitions,
+		);
 		self.context.close_scope();
-
-		b_cond.add_builder(b?);
-
+		let mut b_cond = b_cond?;
+		// TODO: Allow conversion of other types to bools in condition
+		if b_cond.return_reg.class_id() != definitions.class_id::<Bool>()? {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Flow statement expected a bool condition, but got a {}.",
+				definitions.get_class(b_cond.return_reg.class_id())?.common.name,
+			)));
+		}
+		b_cond.add_op(Hold(b_cond.return_reg.index()));
 		return Ok(b_cond);
 	}
 
@@ -2341,17 +4547,10 @@ pub struct QuCompiler {
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
 		// Get expression register
-		self.context.open_scope();
-		let b_cond = {
-			// Expression code
-			self.cmp_alloc_expression(condition,definitions)?
-		};
-		// TODO: Allow conversion of other types to bools in condition
-		assert_eq!(b_cond.return_reg.class_id(), definitions.class_id::<Bool>()?);
-		self.context.close_scope();
+		let b_cond = self.cmp_flow_condition(condition, definitions)?;
 
-		// New frame for the code in the 'if' body
-		self.context.open_scope();
+		// New frame for the code in the 'while' body
+		self.context.open_loop_scope();
 		let code= {
 			// --- Compile Pieces ---
 			// Code block
@@ -2381,6 +4580,143 @@ pub struct QuCompiler {
 				+ 1
 			) as isize)));
 
+			// Continue jumps back to the expression, break to the end
+			let end = b.len();
+			b.resolve_loop_jumps(0, end);
+
+			Ok::<QuAsmBuilder, QuMsg>(b)
+		}?;
+		self.context.close_scope();
+
+		Ok(code)
+	}
+
+
+	/// Compiles a *for* loop into bytecode.
+	/// 
+	/// The loop runs on a copy of the iterable, which has to implement the
+	/// `Iterator` trait or have an `iter` function that returns one (Ex:
+	/// lists and maps). Each iteration calls `next` on the iterator, which
+	/// advances it in place, and ends the loop once it returns false.
+	/// Otherwise the loop's variable is set to the output of `current`.
+	fn cmp_for_loop(
+		&mut self,
+		for_loop:&ForLoop,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		// New frame for the iterator and the loop variable
+		self.context.open_loop_scope();
+		let code = {
+			// Copy the iterable, so that iterating doesn't change it
+			let iter_reg = self.context.next_reg();
+			let mut b_iter = self.cmp_expr(
+				&for_loop.iterable,
+				iter_reg,
+				None,
+				definitions,
+			)?;
+			let mut iter_class = b_iter.return_reg.class_id();
+			if b_iter.return_reg.index() != iter_reg {
+				let b_copy = self.cmp_copy_register(
+					b_iter.return_reg,
+					iter_reg,
+					definitions,
+				)?;
+				b_iter.add_builder(b_copy);
+			}
+			self.context.allocate_at(iter_reg, iter_class, definitions)?;
+
+			// Get an iterator from the iterable if it isn't one
+			let iterator_id = definitions.class_id::<QuIterator>()?;
+			let not_iterable = |class_id, definitions: &Definitions| {
+				QuMsg::not_iterable(
+					&definitions.get_class(class_id).unwrap().common.name,
+				).or_span(for_loop.iterable.span())
+			};
+			let mut iter_reg = iter_reg;
+			if !iter_class.is(iterator_id, definitions) {
+				let iter_id = Self::find_method(
+					iter_class, "iter", &[iter_class], definitions,
+				).map_err(|_| not_iterable(iter_class, definitions))?;
+				let iterator_class = definitions.get_function(iter_id)?
+					.identity
+					.return_type;
+				if !iterator_class.is(iterator_id, definitions) {
+					return Err(not_iterable(iter_class, definitions));
+				}
+				let iterator_reg = self.context.allocate(
+					iterator_class, definitions,
+				)?.index();
+				b_iter.add_op(Self::asm_method_op(
+					iter_id,
+					iter_class,
+					iter_reg,
+					iterator_reg,
+					definitions,
+				)?);
+				iter_reg = iterator_reg;
+				iter_class = iterator_class;
+			}
+			let next_id = Self::find_method(
+				iter_class, "next", &[iter_class], definitions,
+			)?;
+			let current_id = Self::find_method(
+				iter_class, "current", &[iter_class], definitions,
+			)
+				.map_err(|_| QuMsg::iterator_lacks_current(
+					&definitions.get_class(iter_class).unwrap().common.name,
+				).or_span(for_loop.iterable.span()))?;
+			let item_class = definitions.get_function(current_id)?
+				.identity
+				.return_type;
+
+			// Allocate the output of 'next' and the loop variable
+			let bool_id = definitions.class_id::<Bool>()?;
+			let next_reg = self.context.allocate(bool_id, definitions)?;
+			let item_reg = self.context.define_variable(
+				for_loop.name.slice.clone(),
+				item_class,
+				definitions,
+			)?.stack_id;
+
+			// --- Compile Pieces ---
+			// Code block
+			let block_code = self.cmp_scope(&for_loop.body, definitions)?;
+			let block_code_len = block_code.len();
+
+			let mut b = b_iter;
+			let next_index = b.len();
+
+			// Advance the iterator, skip the rest if it's done
+			b.add_op(Self::asm_method_op(
+				next_id,
+				iter_class,
+				iter_reg,
+				next_reg.index(),
+				definitions,
+			)?);
+			b.add_op(Hold(next_reg.index()));
+			b.add_op(JumpByIfNot(block_code_len as isize + 2));
+
+			// Get the item
+			b.add_op(Self::asm_method_op(
+				current_id,
+				iter_class,
+				iter_reg,
+				item_reg.index(),
+				definitions,
+			)?);
+
+			// Loop body
+			b.add_builder(block_code);
+
+			// Jump back to 'next'
+			b.add_op(JumpBy(-(block_code_len as isize + 5)));
+
+			// Continue jumps back to 'next', break to the end
+			let end = b.len();
+			b.resolve_loop_jumps(next_index, end);
+
 			Ok::<QuAsmBuilder, QuMsg>(b)
 		}?;
 		self.context.close_scope();
@@ -2389,6 +4725,51 @@ pub struct QuCompiler {
 	}
 
 
+	/// Returns the function of `class_id` with the given name and
+	/// parameters.
+	pub(crate) fn find_method(
+		class_id: ClassId,
+		name: &str,
+		parameters: &[ClassId],
+		definitions: &Definitions,
+	) -> Result<FunctionId, QuMsg> {
+		let identity = FunctionIdentity {
+			name: name.into(),
+			parameters: parameters.into(),
+			return_type: Default::default(),
+		};
+		let group_id = definitions.get_class(class_id)?
+			.common
+			.get_function_group_id(name)?;
+		definitions
+			.get_function_group(group_id)?
+			.get_fn_id(&identity, definitions)
+	}
+
+
+	/// Assembles calling a function whose only argument is at `arg`. Functions
+	/// declared by traits are called through the class of the argument.
+	fn asm_method_op(
+		fn_id: FunctionId,
+		class_id: ClassId,
+		arg: RegId,
+		output: RegId,
+		definitions: &Definitions,
+	) -> Result<QuOp, QuMsg> {
+		let op = match definitions.get_function_trait(fn_id)? {
+			Some(trait_id) => QuOp::CallV(
+				trait_id,
+				class_id,
+				fn_id,
+				Box::new([arg]),
+				output,
+			),
+			None => QuOp::Call(fn_id, Box::new([arg]), output),
+		};
+		Ok(op)
+	}
+
+
 	fn asm_fn_call(
 		&mut self,
 		caller: Option<QuAsmBuilder>,
@@ -2403,6 +4784,25 @@ pub struct QuCompiler {
 			else { todo!("Call runtime functions") };
 		let fn_id = d.constants[callable_const_id]
 			.get_value::<objects::Function>().id;
+
+		// Convert arguments for trait-typed parameters
+		let mut args = args;
+		let parameters = d.get_function(fn_id)?.identity.parameters.clone();
+		for (arg, param) in args.iter_mut().zip(parameters.iter()) {
+			if !d.get_class(*param)?.is_trait
+				|| arg.return_reg.class_id() == *param
+			{
+				continue;
+			}
+			let arg_reg = self.context.allocate(*param, d)?.index();
+			*arg = self.asm_upcast(
+				std::mem::take(arg),
+				arg_reg,
+				*param,
+				d,
+			)?;
+		}
+
 		let param_regs:Vec<RegId> = args.iter().map(|b| {
 			b.return_reg.index()
 		}).collect();
@@ -2425,12 +4825,22 @@ pub struct QuCompiler {
 		}
 
 		// Call
-		builder.add_return_op(
-			QuOp::Call(
+		let op = match d.get_function_trait(fn_id)? {
+			Some(trait_id) => QuOp::CallV(
+				trait_id,
+				parameters[0],
+				fn_id,
+				param_regs.into_boxed_slice(),
+				reg,
+			),
+			None => QuOp::Call(
 				fn_id,
 				param_regs.into_boxed_slice(),
 				reg,
 			),
+		};
+		builder.add_return_op(
+			op,
 			d
 				.get_function(fn_id)?
 				.identity
@@ -2447,12 +4857,21 @@ pub struct QuCompiler {
 		reg: RegId,
 		d: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
+		if call_expression.caller.is_none() {
+			let name = &call_expression.name.slice;
+			if let Some(class_id) = self.function_value_class(name, d)? {
+				return self.cmp_call_value(call_expression, class_id, reg, d);
+			}
+		}
+
 		self.context.open_scope();
 
-		let b_caller = match &call_expression.caller {
-			Some(expression) =>
-				Some(self.cmp_alloc_expression(expression, d)?),
-			None => None,
+		let (b_caller, store_caller) = match &call_expression.caller {
+			Some(expression) => {
+				let (b, store) = self.cmp_caller_location(expression, d)?;
+				(Some(b), store)
+			},
+			None => (None, None),
 		};
 
 		let mut b_args = vec!();
@@ -2508,9 +4927,12 @@ pub struct QuCompiler {
 						d,
 					))
 				},
-				ItemId::Constant(_) => todo!("Call lambda/callable"),
-				ItemId::StaticVariable(_) => todo!("Call lambda/callable"),
-				ItemId::Variable(_) => todo!("Call lambda/callable"),
+				// Function values are called by `cmp_call_value`
+				ItemId::Constant(_)
+				| ItemId::StaticVariable(_)
+				| ItemId::Variable(_) => Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+					"Can't call '{}' because it's not a function.", sig.name,
+				))),
 				ItemId::Class(id) => {
 					let constructor_sig = FunctionIdentity {
 						name: CONSTRUCTOR_NAME.into(),
@@ -2524,7 +4946,9 @@ pub struct QuCompiler {
 						.get_fn_id(&constructor_sig, d)?;
 					compile_fn_from_item(ItemId::Function(fn_id), &sig, d)
 				},
-				ItemId::Module(_) => todo!("Throw an error"),
+				ItemId::Module(_) => Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+					"Can't call '{}' because it's a module.", sig.name,
+				))),
 				ItemId::None => unreachable!(),
 			}
 		}
@@ -2584,35 +5008,244 @@ pub struct QuCompiler {
 						context_callable_id
 					}
 					(None, _) => {
-						// Function not found in context, look for any
-						// item in context
-						self.context.find_item(
-							&sig.name,
-							d,
-						)?
+						// Function not found in context, look for a
+						// function declared in the class of the first
+						// argument, then for any item in context
+						let first_arg_class = d.get_class(
+							b_first_arg.return_reg.class_id()
+						)?;
+						let class_callable_id = first_arg_class.common
+							.get_item_id_deep_maybe(&sig.name, d)
+							.filter(|_| first_arg_class.from_script
+								|| Self::is_dyn_class(
+									b_first_arg.return_reg.class_id(),
+									d,
+								))
+							.filter(|item| match item {
+								ItemId::FunctionGroup(id) => d
+									.function_groups[*id]
+									.get_fn_id_maybe(&sig, d)
+									.is_some(),
+								_ => false,
+							});
+						match class_callable_id {
+							Some(class_callable_id) => class_callable_id,
+							None => self.context.find_item_filtered_maybe(
+								&sig.name,
+								&|_| true,
+								d,
+							).ok_or_else(|| {
+								QuMsg::undefined_fn_access(&sig.name)
+							})?,
+						}
 					},
 				};
 
 				compile_fn_from_item(callable_item_id, &sig, d)?
 			},
 			_ => {
-				let item = self.context.find_item(
+				let item = self.context.find_item_filtered_maybe(
 					&sig.name,
+					&|_| true,
 					d,
-				)?;
+				).ok_or_else(|| QuMsg::undefined_fn_access(&sig.name))?;
 				compile_fn_from_item(item, &sig, d)?
 			}
 		};
 
-		self.context.close_scope();
+		// Functions that change `self` in place need an output apart from
+		// the caller, which is stored back if it belongs to a static variable
+		let is_method = call_expression.caller.is_some() && b_caller.is_none();
+		let changes_caller = match (is_method, b_callable.constant) {
+			(true, Some(const_id)) => {
+				let fn_id = d.constants[const_id]
+					.get_value::<objects::Function>()
+					.id;
+				let function = d.get_function(fn_id)?;
+				match (function.mutable_self, b_args.first()) {
+					(true, Some(b_self)) =>
+						Some((b_self.return_reg, function.identity.return_type)),
+					_ => None,
+				}
+			},
+			_ => None,
+		};
+		let mut output = reg;
+		if let Some((self_reg, return_type)) = changes_caller {
+			let self_size = d.get_class(self_reg.class_id())?.size as usize;
+			let return_size = d.get_class(return_type)?.size as usize;
+			let overlaps = reg.0 < self_reg.index().0 + self_size
+				&& self_reg.index().0 < reg.0 + return_size;
+			if overlaps {
+				output = self.context.allocate(return_type, d)?.index();
+			}
+		}
 
-		self.asm_fn_call(
+		// The scope is closed after assembling so that arguments converted
+		// while assembling don't overlap with the other arguments.
+		let b = self.asm_fn_call(
 			b_caller,
 			b_callable,
 			b_args,
-			reg,
+			output,
 			d,
-		)
+		);
+		let b = match (b, changes_caller) {
+			(Ok(mut b), Some((_, return_type))) => {
+				if let Some(store_caller) = store_caller {
+					b.add_op(store_caller);
+				}
+				if output != reg {
+					let b_copy = self.cmp_copy_register(
+						TypedRegId::new(output, return_type),
+						reg,
+						d,
+					);
+					match b_copy {
+						Ok(b_copy) => {
+							b.add_builder(b_copy);
+							b.set_output(reg, return_type);
+						},
+						Err(msg) => {
+							self.context.close_scope();
+							return Err(msg);
+						},
+					}
+				}
+				Ok(b)
+			},
+			(b, _) => b,
+		};
+
+		self.context.close_scope();
+
+		b
+	}
+
+
+	/// Compiles the caller of a method (Ex: the foo.bar of foo.bar.next())
+	/// where it's stored rather than into a copy, so that methods which
+	/// change `self` change the caller.
+	/// 
+	/// Returns the op that stores the caller back into the static variable
+	/// it belongs to, if there is one.
+	fn cmp_caller_location(
+		&mut self,
+		expression: &Expression,
+		d: &mut Definitions,
+	) -> Result<(QuAsmBuilder, Option<QuOp>), QuMsg> {
+		// Find the variable at the start of a chain of dot indexes
+		let mut root = expression;
+		while let Expression::DotIndex(dot_index) = root {
+			root = &dot_index.left;
+		}
+		let root_item = match root {
+			Expression::Var(var) => self.context
+				.find_item(&var.name.slice, d)
+				.ok(),
+			_ => None,
+		};
+		let is_variable = matches!(
+			root_item,
+			Some(ItemId::Variable(_) | ItemId::StaticVariable(_)),
+		);
+
+		if let (Expression::DotIndex(dot_index), true) = (expression, is_variable) {
+			let (b, root) = self.cmp_field_location(dot_index, d)?;
+			let store = match root {
+				FieldRoot::Static(id, reg) => Some(QuOp::StoreStatic(reg, id)),
+				_ => None,
+			};
+			return Ok((b, store));
+		}
+
+		let b = self.cmp_alloc_expression(expression, d)?;
+		let store = match (expression, root_item) {
+			(Expression::Var(_), Some(ItemId::StaticVariable(id))) =>
+				Some(QuOp::StoreStatic(b.return_reg.index(), id)),
+			_ => None,
+		};
+		return Ok((b, store));
+	}
+
+
+	/// Returns the class of the variable named `name` if it holds a function
+	/// value.
+	fn function_value_class(
+		&self,
+		name: &str,
+		d: &Definitions,
+	) -> Result<Option<ClassId>, QuMsg> {
+		let item = self.context.find_item_filtered_maybe(
+			name,
+			&|item| matches!(
+				item,
+				ItemId::Variable(_) | ItemId::StaticVariable(_),
+			),
+			d,
+		);
+		let class_id = match item {
+			Some(ItemId::Variable(id)) =>
+				self.context.get_variable(id)?.stack_id.class_id(),
+			Some(ItemId::StaticVariable(id)) => d.static_variables[id].class_id,
+			_ => return Ok(None),
+		};
+		if d.get_class(class_id)?.function_type.is_none() {
+			return Ok(None);
+		}
+		Ok(Some(class_id))
+	}
+
+
+	/// Compiles a call to the function value held by a variable (Ex: f(1)
+	/// where f is a fn(int) int).
+	/// 
+	/// Arguments are compiled as the function type's parameters, so that
+	/// they're converted to traits as needed.
+	fn cmp_call_value(
+		&mut self,
+		call_expression: &CallExpression,
+		class_id: ClassId,
+		reg: RegId,
+		d: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let name = &call_expression.name;
+		let (parameters, return_type) = d.get_class(class_id)?
+			.function_type
+			.clone()
+			.unwrap();
+		let arguments = &call_expression.parameters.elements;
+		let mismatch = |d:&Definitions| -> Result<QuMsg, QuMsg> {
+			let type_name = &d.get_class(class_id)?.common.name;
+			Ok(QuMsg::callable_args_mismatch(&name.slice, type_name))
+		};
+		if arguments.len() != parameters.len() {
+			return Err(mismatch(d)?);
+		}
+
+		self.context.open_scope();
+		let callee = Expression::Var(Box::new(VarExpression {
+			name: name.clone(),
+			span: name.span(),
+		}));
+		let mut b = self.cmp_alloc_expression(&callee, d)?;
+		let callable = b.return_reg.index();
+
+		let mut args = Vec::with_capacity(arguments.len());
+		for (argument, parameter) in arguments.iter().zip(parameters.iter()) {
+			let arg_reg = self.context.next_reg();
+			let b_arg = self.cmp_expr(argument, arg_reg, Some(*parameter), d)?;
+			if b_arg.return_reg.class_id() != *parameter {
+				return Err(mismatch(d)?.or_span(argument.span()));
+			}
+			self.context.allocate_at(arg_reg, *parameter, d)?;
+			args.push(arg_reg);
+			b.add_builder(b_arg);
+		}
+		self.context.close_scope();
+
+		b.add_return_op(CallValue(callable, args.into(), reg), return_type);
+		Ok(b)
 	}
 
 
@@ -2627,93 +5260,329 @@ pub struct QuCompiler {
 		parsed_identity: &crate::parser::parsed::FunctionIdentity,
 		body: &CodeScope,
 		definitions: &mut Definitions,
-	) -> Result<QuAsmBuilder, QuMsg> {
-		// Compose indentity and parameters list
-		let (identity, parameters) = {
-			let mut parameters_types = vec![];
-			let mut parameters_names = vec![];
-			for param in &parsed_identity.parameters {
-				match &param.static_type {
-					Some(identity) => {
-						let item_id = self.context.find_item_filtered(
-							&identity.slice,
-							&|item| {
-								let ItemId::Class(_) = item
-									else {return false;};
-								return true;
-							},
-							definitions,
-						)?;
-						let ItemId::Class(id) = item_id else {panic!()};
-						parameters_types.push(id);
-						parameters_names.push((param.name().to_owned(), id))
-					},
-					None => {
-						let id = definitions.class_id::<Void>()?;
-						parameters_types.push(id);
-						parameters_names.push((param.name().to_owned(), id))
-					},
-				};
-			}
-			let return_type = self.class_id_from_option_identity(
-				&parsed_identity.return_type,
-				definitions,
-			)?;
-			let identity = FunctionIdentity {
-				name: parsed_identity.name.slice.clone(),
-				parameters: parameters_types.into_boxed_slice(),
-				return_type,
-			};
-			(identity, parameters_names)
-		};
-
-		// Define the function
-		let frame = self.context.frames.iter().rev().next().unwrap();
-		let context_id = ItemId::from(frame);
+	) -> Result<QuAsmBuilder, QuMsg> {
+		// Compose indentity and parameters list
+		let parameters = self.cmp_fn_parameters(parsed_identity, definitions)?;
+		let return_type = self.class_id_from_option_identity(
+			&parsed_identity.return_type,
+			definitions,
+		)?;
+		let identity = FunctionIdentity {
+			name: parsed_identity.name.slice.clone(),
+			parameters: parameters.iter().map(|param| param.1).collect(),
+			return_type,
+		};
+
+		// Define the function
+		let frame = self.context.frames.iter().rev().next().unwrap();
+		let context_id = ItemId::from(frame);
+		let func_id = definitions.define_function_in_item(
+			context_id,
+			FunctionMetadata {
+				identity: identity,
+				code_block: FunctionReference::Internal(
+					definitions.byte_code_blocks.len()
+				),
+				..Default::default()
+			},
+			None,
+			true,
+		)?;
+
+		self.cmp_fn_body(
+			func_id,
+			&parameters,
+			None,
+			&body.code_block,
+			definitions,
+		)?;
+
+		let code = QuAsmBuilder::new();
+		return Ok(code);
+	}
+
+
+	/// Compiles the body of a function and adds its bytecode to
+	/// `definitions`.
+	/// 
+	/// If `field_declarations` is given then the function is compiled as a
+	/// constructor. The new instance is stored in the function's output and is
+	/// bound to `self`, and its fields are initialized before `body` is run.
+	fn cmp_fn_body(
+		&mut self,
+		func_id: FunctionId,
+		parameters: &[(String, ClassId)],
+		field_declarations: Option<&[&VarDeclaration]>,
+		body: &CodeBlock,
+		definitions: &mut Definitions,
+	) -> Result<(), QuMsg> {
+		self.context.open_frame(ContextFrame::function(func_id));
+		let body_code = self.cmp_fn_code(
+			func_id,
+			parameters,
+			field_declarations,
+			body,
+			definitions,
+		);
+		self.context.close_frame();
+		let body_code = match body_code {
+			Ok(body_code) => body_code,
+			Err(msg) => {
+				// The function's code block would be filled by the next
+				// compile, so the function can't be found again
+				definitions.unbind_function(func_id);
+				return Err(msg);
+			},
+		};
+
+		// Compile function body. Functions declared in the body are pushed
+		// first, so the function's code block is only known now.
+		definitions.byte_code_spans.push(body_code.spans);
+		definitions.byte_code_blocks.push(body_code.ops.into());
+		definitions.get_function_mut(func_id)?.code_block
+			= FunctionReference::Internal(definitions.byte_code_blocks.len() - 1);
+
+		Ok(())
+	}
+
+
+	/// Compiles the parameters and code of a function's body, in its
+	/// function's frame.
+	fn cmp_fn_code(
+		&mut self,
+		func_id: FunctionId,
+		parameters: &[(String, ClassId)],
+		field_declarations: Option<&[&VarDeclaration]>,
+		body: &CodeBlock,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let mut b = QuAsmBuilder::new();
+
+		// Allocate the instance being constructed
+		let instance = match field_declarations {
+			Some(_) => {
+				let class_id = definitions.get_function(func_id)?
+					.identity
+					.return_type;
+				Some(self.context.define_variable(
+					SELF_NAME.into(),
+					class_id,
+					definitions,
+				)?.stack_id)
+			},
+			None => None,
+		};
+
+		// Functions that change `self` in place keep their output apart
+		// from `self`, so that the Vm can copy `self` back to the caller
+		let function = definitions.get_function(func_id)?;
+		if function.mutable_self {
+			let return_type = function.identity.return_type;
+			self.context.allocate(return_type, definitions)?;
+		}
+
+		// Allocate parameters
+		let mut i = 0;
+		for (name, class_id) in parameters {
+			let stack_id = self.context.define_variable(
+				name.into(),
+				*class_id,
+				definitions,
+			)?.stack_id;
+			b.add_op(QuOp::LoadArg(i, stack_id.into()));
+			i += 1;
+		}
+
+		// Initialize fields. This has to be done after loading the
+		// arguments, because calls would overwrite them.
+		if let (Some(instance), Some(field_declarations))
+			= (instance, field_declarations)
+		{
+			b.add_builder(self.cmp_field_defaults(
+				instance,
+				field_declarations,
+				definitions,
+			)?);
+		}
+
+		// Compile code block
+		b.add_builder(self.cmp_code_block(body, definitions)?);
+		Ok(b)
+	}
+
+
+	/// Returns the names and classes of the parameters of a function.
+	fn cmp_fn_parameters(
+		&mut self,
+		parsed_identity: &crate::parser::parsed::FunctionIdentity,
+		definitions: &mut Definitions,
+	) -> Result<Vec<(String, ClassId)>, QuMsg> {
+		let mut parameters = vec![];
+		for param in &parsed_identity.parameters {
+			match &param.static_type {
+				Some(identity) if identity.slice.starts_with('(')
+					|| identity.slice.starts_with("fn(")
+					|| identity.slice.contains('[') =>
+				{
+					let id = Self::class_id_from_name(
+						&identity.slice,
+						definitions,
+					)?;
+					parameters.push((param.name().to_owned(), id))
+				},
+				Some(identity) => {
+					let item_id = self.context.find_item_filtered_maybe(
+						&identity.slice,
+						&|item| {
+							let ItemId::Class(_) = item
+								else {return false;};
+							return true;
+						},
+						definitions,
+					);
+					let Some(ItemId::Class(id)) = item_id else {
+						return Err(QuMsg::undefined_type_access(
+							&identity.slice
+						));
+					};
+					parameters.push((param.name().to_owned(), id))
+				},
+				None => {
+					let id = definitions.class_id::<Void>()?;
+					parameters.push((param.name().to_owned(), id))
+				},
+			};
+		}
+		Ok(parameters)
+	}
+
+
+	/// Compiles a function declared in a class. The instance the function is
+	/// called on is passed as its first parameter, `self`.
+	fn cmp_method_decl(
+		&mut self,
+		class_id: ClassId,
+		parsed_identity: &crate::parser::parsed::FunctionIdentity,
+		body: &CodeScope,
+		definitions: &mut Definitions,
+	) -> Result<(), QuMsg> {
+		let (identity, parameters) = self.cmp_method_identity(
+			class_id,
+			parsed_identity,
+			definitions,
+		)?;
 		let func_id = definitions.define_function_in_item(
-			context_id,
+			ItemId::Class(class_id),
 			FunctionMetadata {
-				identity: identity,
+				identity,
 				code_block: FunctionReference::Internal(
 					definitions.byte_code_blocks.len()
 				),
+				mutable_self: body.code_block.changes_var(SELF_NAME),
 				..Default::default()
 			},
 			None,
-			true,
+			false,
 		)?;
 
-		self.context.open_frame(ContextFrame::Function(
-			func_id, FrameData::default(),
-		));
-		let body_code = {
-			let mut b = QuAsmBuilder::new();
+		self.cmp_fn_body(
+			func_id,
+			&parameters,
+			None,
+			&body.code_block,
+			definitions,
+		)
+	}
 
-			// Allocate parameters
-			let mut i = 0;
-			for (name, class_id) in &parameters {
-				let stack_id = self.context.define_variable(
-					name.into(),
-					*class_id,
-					definitions,
-				)?.stack_id;
-				b.add_op(QuOp::LoadArg(i, stack_id.into()));
-				i += 1;
-			}
 
-			// Compile code block
-			b.add_builder(self.cmp_code_block(&body.code_block, definitions)?);
-			Ok::<QuAsmBuilder, QuMsg>(b)
-		}?;
-		self.context.close_frame();
+	/// Returns the identity and parameters of a function declared in a
+	/// class, including the `self` parameter.
+	fn cmp_method_identity(
+		&mut self,
+		class_id: ClassId,
+		parsed_identity: &crate::parser::parsed::FunctionIdentity,
+		definitions: &mut Definitions,
+	) -> Result<(FunctionIdentity, Vec<(String, ClassId)>), QuMsg> {
+		let mut parameters = vec![(SELF_NAME.to_owned(), class_id)];
+		parameters.append(
+			&mut self.cmp_fn_parameters(parsed_identity, definitions)?
+		);
+		let return_type = self.class_id_from_option_identity(
+			&parsed_identity.return_type,
+			definitions,
+		)?;
+		let identity = FunctionIdentity {
+			name: parsed_identity.name.slice.clone(),
+			parameters: parameters.iter().map(|param| param.1).collect(),
+			return_type,
+		};
+		Ok((identity, parameters))
+	}
 
 
-		// Compile function body
-		let bytcode = body_code.ops;
-		definitions.byte_code_blocks.push(bytcode);
+	/// Compiles a function declared in the implementation of a trait in a
+	/// class, which overrides the matching function of the trait.
+	fn cmp_impl_fn_decl(
+		&mut self,
+		class_id: ClassId,
+		trait_id: ClassId,
+		parent_item: ItemId,
+		parsed_identity: &crate::parser::parsed::FunctionIdentity,
+		body: &CodeScope,
+		definitions: &mut Definitions,
+	) -> Result<(), QuMsg> {
+		let (identity, parameters) = self.cmp_method_identity(
+			class_id,
+			parsed_identity,
+			definitions,
+		)?;
 
-		let code = QuAsmBuilder::new();
-		return Ok(code);
+		// Check that the function matches a function of the trait
+		let trait_class = definitions.get_class(trait_id)?;
+		let trait_fn_id = trait_class.common.function_groups_map
+			.get(&identity.name)
+			.and_then(|group_id| {
+				definitions.function_groups[*group_id]
+					.get_fn_id_maybe(&identity, definitions)
+			});
+		let Some(trait_fn_id) = trait_fn_id else {
+			return Err(format!(
+				"Trait '{}' has no function that matches '{}'.",
+				trait_class.common.name,
+				identity.display_pretty(definitions),
+			).into());
+		};
+		let trait_fn = &definitions.get_function(trait_fn_id)?.identity;
+		if !identity.return_type.is(trait_fn.return_type, definitions) {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Function '{}' has to return '{}' to implement '{}' of trait '{}'.",
+				identity.display_pretty(definitions),
+				definitions.get_class(trait_fn.return_type)?.common.name,
+				trait_fn.display_pretty(definitions),
+				trait_class.common.name,
+			)));
+		}
+
+		let func_id = definitions.define_function_implementation(
+			class_id,
+			trait_id,
+			parent_item,
+			FunctionMetadata {
+				identity,
+				code_block: FunctionReference::Internal(
+					definitions.byte_code_blocks.len()
+				),
+				..Default::default()
+			},
+		)?;
+
+		self.cmp_fn_body(
+			func_id,
+			&parameters,
+			None,
+			&body.code_block,
+			definitions,
+		)
 	}
 
 
@@ -2735,33 +5604,30 @@ pub struct QuCompiler {
 						.common
 						.get_item_id(indexer)?
 				},
-				ItemId::Constant(_) => todo!(),
-				ItemId::Function(_) => todo!(),
-				ItemId::FunctionGroup(_) => todo!(),
 				ItemId::Module(id) => {
 					definitions
 						.get_module(id)?
 						.common
 						.get_item_id(indexer)?
 				},
-				ItemId::StaticVariable(_) => todo!(),
-				ItemId::Variable(_) => todo!(),
-				ItemId::None => todo!(),
+				_ => return Err(QuMsg::unimportable_item(indexer)),
 			})
 		};
 
 		let mut identity = &import.identity_path;
 		let mut item:Option<ItemId> = None;
-		loop {
+		let name = loop {
 			match identity {
 				Identity::Single(token) => {
 					if item.is_none() {
 						// First iteration
 						let module_id = definitions.get_module_id(
 							&token.slice,
-						).unwrap();
+						).ok_or_else(|| QuMsg::undefined_module_import(
+							&token.slice,
+						))?;
 						item = Some(ItemId::Module(module_id));
-						break;
+						break &token.slice;
 					}
 
 					item = Some(next_index(
@@ -2770,14 +5636,16 @@ pub struct QuCompiler {
 						&definitions
 					)?);
 
-					break;
+					break &token.slice;
 				},
 				Identity::Index(index) => {
 					if item.is_none() {
 						// First iteration
 						let module_id = definitions.get_module_id(
 							&index.left.slice,
-						).unwrap();
+						).ok_or_else(|| QuMsg::undefined_module_import(
+							&index.left.slice,
+						))?;
 						item = Some(ItemId::Module(module_id));
 						identity = &index.right;
 						continue;
@@ -2791,7 +5659,7 @@ pub struct QuCompiler {
 					identity = &index.right;
 				},
 			}
-		}
+		};
 
 		match item.unwrap() {
 			ItemId::Class(id) => {
@@ -2801,8 +5669,6 @@ pub struct QuCompiler {
 					definitions
 				)?;
 			},
-			ItemId::Constant(_) => todo!(),
-			ItemId::Function(_) => todo!(),
 			ItemId::FunctionGroup(id) => {
 				self.context.import_function(
 					id,
@@ -2817,15 +5683,33 @@ pub struct QuCompiler {
 					definitions
 				)?;
 			},
-			ItemId::StaticVariable(_) => todo!(),
-			ItemId::Variable(_) => todo!(),
-			ItemId::None => todo!(),
+			_ => return Err(QuMsg::unimportable_item(name)),
 		}
 
 		Ok(QuAsmBuilder::new())
 	}
 
 
+	/// Compiles a *break* or *continue* statement into bytecode.
+	/// 
+	/// The jump is left unresolved until the loop that contains it is
+	/// assembled. See [`QuAsmBuilder::resolve_loop_jumps`].
+	fn cmp_loop_jump(
+		&mut self,
+		loop_jump:&LoopJump,
+		kind:LoopJumpKind,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		if !self.context.in_loop() {
+			return Err(QuMsg::loop_jump_outside_loop(
+				&loop_jump.keyword.slice,
+			));
+		}
+		let mut b = QuAsmBuilder::new();
+		b.add_loop_jump(kind);
+		return Ok(b);
+	}
+
+
 	/// Compiles a [QuLeaf] into bytecode.
 	fn cmp_statement(
 		&mut self,
@@ -2833,6 +5717,18 @@ pub struct QuCompiler {
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
 		match statement {
+			Statement::Break(loop_jump) => {
+				return self.cmp_loop_jump(loop_jump, LoopJumpKind::Break);
+			}
+			Statement::ClassDeclaration(class_declaration) => {
+				return self.cmp_class_decl(
+					class_declaration,
+					definitions,
+				);
+			}
+			Statement::Continue(loop_jump) => {
+				return self.cmp_loop_jump(loop_jump, LoopJumpKind::Continue);
+			}
 			Statement::Expression(expression) => {
 				self.context.open_scope();
 				let result = {
@@ -2848,11 +5744,7 @@ pub struct QuCompiler {
 			Statement::FlowStatement(flow_statement) => {
 				match flow_statement.flow_keyword.slice.as_str() {
 					KEYWORD_IF => {
-						return self.cmp_flow_if(
-							&flow_statement.condition,
-							&flow_statement.body,
-							definitions,
-						);
+						return self.cmp_flow_if(flow_statement, definitions);
 					},
 					KEYWORD_WHILE => {
 						return self.cmp_flow_while(
@@ -2864,6 +5756,9 @@ pub struct QuCompiler {
 					_ => unimplemented!(),
 				}
 			}
+			Statement::ForLoop(for_loop) => {
+				return self.cmp_for_loop(for_loop, definitions);
+			}
 			Statement::FunctionDeclaration(function_declaration) => {
 				// TODO: Compiler fn declaration parameters
 				return self.cmp_fn_decl(
@@ -2875,43 +5770,97 @@ pub struct QuCompiler {
 			Statement::Return(return_statement) => {
 				let code = match &return_statement.value {
 					Some(expression) => {
+						// Values returned from functions are compiled as the
+						// return type, which converts trait-typed values and
+						// types empty collections. Void functions can return
+						// any value.
+						let void_id = definitions.class_id::<Void>()?;
+						let reg_type = match
+							self.context.get_current_context_frame()
+						{
+							ContextFrame::Function(fn_id, _) => Some(
+								definitions.get_function(*fn_id)?
+									.identity
+									.return_type
+							),
+							_ => None,
+						}.filter(|return_type| *return_type != void_id);
 						let mut b = self.cmp_expr(
 							expression,
 							0.into(),
-							None,
+							reg_type,
 							definitions
 						)?;
 						let type_id = b.return_reg.class_id();
 
-						if self.context.frames.len() == 1 {
+						let in_function = matches!(
+							self.context.get_current_context_frame(),
+							ContextFrame::Function(..),
+						);
+						// Returning ends the module's code too, so that
+						// statements after a top level return don't run
+						if !in_function {
 							b.add_op(Return(type_id));
-						} else {
-							b.add_op(End);
 						}
+						b.add_op(End);
+						b
+					},
+					None => {
+						let mut b = QuAsmBuilder::new();
+						b.add_op(End);
 						b
 					},
-					None => QuAsmBuilder::new(),
 				};
 				return Ok(code);
 			}
+			Statement::TraitDeclaration(trait_declaration) => {
+				return self.cmp_trait_decl(
+					trait_declaration,
+					definitions,
+				);
+			}
 			Statement::VarDeclaration(var_declaration) => {
 				return self.cmp_var_decl(
 					&var_declaration,
 					definitions
 				);
 			}
+			Statement::VarDestructure(destructuring) => {
+				return self.cmp_var_destructure(
+					destructuring,
+					definitions,
+				);
+			}
+			Statement::FieldAssign(field_assignment) => {
+				return self.cmp_field_assign(
+					field_assignment,
+					definitions,
+				);
+			}
 			Statement::VarAssign(variable_assignment) => {
 				return  self.cmp_var_assign(
 					variable_assignment,
 					definitions,
 				);
 			}
+			Statement::Impl(impl_block) => {
+				return Err(format!(
+					"The implementation of trait '{}' has to be in the body of a class.",
+					impl_block.name.slice,
+				).into());
+			}
 			Statement::Import(import) => {
 				return  self.cmp_import(
 					import,
 					definitions,
 				);
 			},
+			Statement::IndexAssign(index_assignment) => {
+				return self.cmp_index_assign(
+					index_assignment,
+					definitions,
+				);
+			},
 		};
 	}
 
@@ -2923,8 +5872,22 @@ pub struct QuCompiler {
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
 		let mut b = QuAsmBuilder::new();
-		for statements in &code_block.statements {
-			b.add_builder(self.cmp_statement(statements, definitions)?);
+		let mut after_return = false;
+		for statement in &code_block.statements {
+			if after_return {
+				self.diagnostics.push(
+					QuMsg::unreachable_code().or_span(statement.span())
+				);
+				after_return = false;
+			}
+			if let Statement::Return(_) = statement {
+				after_return = true;
+			}
+
+			let mut statement_b = self.cmp_statement(statement, definitions)
+				.map_err(|msg| msg.or_span(statement.span()))?;
+			statement_b.set_span(statement.span());
+			b.add_builder(statement_b);
 		}
 		return Ok(b);
 	}
@@ -2938,8 +5901,9 @@ pub struct QuCompiler {
 		let code = self.cmp_code_block(
 			code_block,
 			definitions
-		)?.ops;
-		definitions.byte_code_blocks.push(code);
+		)?;
+		definitions.byte_code_spans.push(code.spans);
+		definitions.byte_code_blocks.push(code.ops.into());
 		
 		Ok(QuAsmBuilder::new())
 	}
@@ -2955,6 +5919,13 @@ pub struct QuCompiler {
 			&var_assignment.name.slice,
 			definitions,
 		)?;
+		if let ItemId::StaticVariable(id) = item {
+			return self.cmp_static_var_assign(
+				&var_assignment.new_value,
+				id,
+				definitions,
+			);
+		}
 		let ItemId::Variable(id) = item else { return Err(format!(
 			"'{}' is not a variable.", var_assignment.name
 		).into()) };
@@ -2969,28 +5940,97 @@ pub struct QuCompiler {
 	}
 
 
+	/// Compiles storing the value of an expression in a static variable.
+	fn cmp_static_var_assign(
+		&mut self,
+		expression: &Expression,
+		variable_id: VariableId,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		let class_id = definitions.static_variables[variable_id].class_id;
+
+		self.context.open_scope();
+		let reg = self.context.allocate(class_id, definitions)?.index();
+		let mut b = self.cmp_expr(
+			expression,
+			reg,
+			Some(class_id),
+			definitions,
+		)?;
+		b.add_op(QuOp::StoreStatic(b.return_reg.index(), variable_id));
+		self.context.close_scope();
+
+		Ok(b)
+	}
+
+
 	/// Compiles a variable declaration.
+	/// 
+	/// Variables declared in the outermost scope of a module are compiled as
+	/// static variables of that module, so they can be accessed by functions
+	/// and by later compilations.
 	fn cmp_var_decl(
 		&mut self,
 		var_declaration: &VarDeclaration,
 		definitions: &mut Definitions,
 	) -> Result<QuAsmBuilder, QuMsg> {
 		let ident = &var_declaration.name;
-
-		// Check if the variable is already defined
-		if self.context.has_item(
-			&var_declaration.name.slice,
-			definitions,
-		) {
-			return Err(format!(
-				"An item by '{}' is already defined.", ident,
-			).into());
-		}
+		let module_root = self.context.get_module_root();
+		self.check_var_definable(ident, definitions)?;
 
 		let static_type = self.class_id_from_option_identity(
 			&var_declaration.static_type,
 			definitions
 		)?;
+		if
+			var_declaration.initial_value.is_none()
+			&& definitions.get_class(static_type)?.is_trait
+		{
+			return Err(QuMsg::of_kind(QuErrorKind::InvalidVariableDefinition, format!(
+				"The variable '{}' has to be given a value because its type, '{}', is a trait.",
+				ident,
+				definitions.get_class(static_type)?.common.name,
+			)));
+		}
+		if
+			var_declaration.initial_value.is_none()
+			&& definitions.get_class(static_type)?.function_type.is_some()
+		{
+			return Err(QuMsg::of_kind(QuErrorKind::InvalidVariableDefinition, format!(
+				"The variable '{}' has to be given a value because its type, '{}', is a function type.",
+				ident,
+				definitions.get_class(static_type)?.common.name,
+			)));
+		}
+
+		if let Some(module_id) = module_root {
+			// The variable is defined once its value compiles, so that a
+			// failed compile doesn't leave a variable without storage
+			self.context.open_scope();
+			let reg = self.context.allocate(static_type, definitions)?.index();
+			let b = match &var_declaration.initial_value {
+				Some(expression) => self.cmp_expr(
+					expression,
+					reg,
+					Some(static_type),
+					definitions,
+				),
+				None => Ok(Self::asm_default_value(
+					static_type,
+					reg,
+					definitions,
+				)),
+			};
+			self.context.close_scope();
+			let mut b = b?;
+			let variable_id = definitions.define_static_variable(
+				module_id,
+				ident.slice.clone(),
+				static_type,
+			)?;
+			b.add_op(QuOp::StoreStatic(b.return_reg.index(), variable_id));
+			return Ok(b);
+		}
 		let var_stack_id = self.context.define_variable(
 			ident.slice.clone(),
 			static_type,
@@ -3009,13 +6049,9 @@ pub struct QuCompiler {
 				),
 
 			None => {
-				// No default value, compile fallback to zero
-				// TODO: Attempt to call constructor of object instead of defaulting to 0
-				Ok(QuAsmBuilder::from_const(
-					definitions.get_private_constant(
-						"0".into(),
-						&||{0},
-					),
+				// No default value, compile the class's default value
+				Ok(Self::asm_default_value(
+					static_type,
 					var_stack_id.index(),
 					definitions,
 				))
@@ -3024,6 +6060,113 @@ pub struct QuCompiler {
 	}
 
 
+	/// Returns an error if a variable named `ident` can't be declared
+	/// because an item by that name is already defined. Static variables
+	/// defined by previous compilations can be redefined.
+	fn check_var_definable(
+		&self,
+		ident: &QuToken,
+		definitions: &Definitions,
+	) -> Result<(), QuMsg> {
+		let redefines_static = match self.context.get_module_root() {
+			Some(module_id) => matches!(
+				definitions.get_module(module_id)?
+					.common
+					.static_variables_map
+					.get(&ident.slice),
+				Some(id) if *id < self.first_static_id
+			),
+			None => false,
+		};
+		if !redefines_static && self.context.has_item(
+			&ident.slice,
+			definitions,
+		) {
+			return Err(format!(
+				"An item by '{}' is already defined.", ident,
+			).into());
+		}
+		Ok(())
+	}
+
+
+	/// Compiles a declaration of a variable for each element of a tuple
+	/// (Ex: var (x, y) = pos).
+	/// 
+	/// Each variable takes the type of its element. Like other variable
+	/// declarations, those in the outermost scope of a module are compiled
+	/// as static variables.
+	fn cmp_var_destructure(
+		&mut self,
+		destructuring: &VarDestructuring,
+		definitions: &mut Definitions,
+	) -> Result<QuAsmBuilder, QuMsg> {
+		// The tuple stays allocated while its elements are copied out
+		let mut b = self.cmp_alloc_expression(
+			&destructuring.value,
+			definitions,
+		)?;
+		let tuple = b.return_reg;
+		let tuple_class = definitions.get_class(tuple.class_id())?;
+		if !tuple_class.is_tuple {
+			return Err(QuMsg::not_destructurable(&tuple_class.common.name)
+				.or_span(destructuring.value.span()));
+		}
+		if tuple_class.fields.len() != destructuring.names.len() {
+			return Err(QuMsg::destructure_count_mismatch(
+				&tuple_class.common.name,
+				destructuring.names.len(),
+			).or_span(destructuring.value.span()));
+		}
+
+		// Every name is checked before any variable is defined, so that a
+		// failed compile doesn't leave some of them defined
+		for (i, name) in destructuring.names.iter().enumerate() {
+			self.check_var_definable(name, definitions)
+				.map_err(|msg| msg.or_span(name.span()))?;
+			if destructuring.names[..i].iter().any(|other| other.slice == name.slice) {
+				return Err(QuMsg::from(format!(
+					"An item by '{}' is already defined.", name,
+				)).or_span(name.span()));
+			}
+		}
+
+		let fields = tuple_class.fields.clone();
+		let module_root = self.context.get_module_root();
+		for (name, field) in destructuring.names.iter().zip(fields) {
+			let element = TypedRegId::new(
+				RegId(tuple.index().0 + field.offset),
+				field.class_id,
+			);
+			match module_root {
+				Some(module_id) => {
+					let variable_id = definitions.define_static_variable(
+						module_id,
+						name.slice.clone(),
+						field.class_id,
+					)?;
+					b.add_op(QuOp::StoreStatic(element.index(), variable_id));
+				},
+				None => {
+					let variable = self.context.define_variable(
+						name.slice.clone(),
+						field.class_id,
+						definitions,
+					)?.stack_id;
+					let b_copy = self.cmp_copy_register(
+						element,
+						variable.index(),
+						definitions,
+					)?;
+					b.add_builder(b_copy);
+				},
+			}
+		}
+
+		Ok(b)
+	}
+
+
 	/// Compiles a scope.
 	fn cmp_scope(
 		&mut self,
@@ -3039,11 +6182,18 @@ pub struct QuCompiler {
 	}
 
 
-	/// Compiles Qu code from a [`&str`] into a [`Vec<u8>`].
+	/// Compiles Qu code into the main module and returns the index of the
+	/// code block of its top level code, along with the module's id.
+	/// 
+	/// # Errors
+	/// 
+	/// Returns every error found while parsing, or the first error found
+	/// while compiling along with any warnings found before it.
 	pub(crate) fn compile(
 		&mut self, code:&str, definitions: &mut Definitions,
-	) -> Result<Vec<QuOp>, QuMsg> {
+	) -> Result<(usize, ModuleId), Vec<QuMsg>> {
 		let mut p = QuParser::new();
+		p.set_indentation(self.indentation);
 		let code_block = p.parse(code)?;
 
 		const PRINT_TREE:bool = false;
@@ -3052,27 +6202,51 @@ pub struct QuCompiler {
 		let base_id = *definitions.module_map
 			.get(FUNDAMENTALS_MODULE)
 			.unwrap();
+		let main_id = *definitions.module_map
+			.get(MAIN_MODULE)
+			.unwrap();
+		self.first_static_id = definitions.static_variables.len();
 
 		self.context.open_frame(ContextFrame::module(base_id));
-
-		let compiled = self.compile_code(&code_block, definitions)?;
+		self.context.open_frame(ContextFrame::module(main_id));
+
+		let code_block = match self.compile_code(&code_block, definitions) {
+			Ok(code_block) => code_block,
+			Err(msg) => {
+				let mut diagnostics = self.take_diagnostics();
+				diagnostics.push(msg);
+				return Err(diagnostics);
+			},
+		};
+		self.context.close_frame();
 		self.context.close_frame();
 
-		Ok(compiled)
+		Ok((code_block, main_id))
 	}
 
 
-	/// Compiles Qu code from a [QuLeaf] into a [`Vec<u8>`].
+	/// Compiles Qu code from a [QuLeaf] and returns the index of its code
+	/// block.
 	pub(crate) fn compile_code(
 		&mut self, code_block:&CodeBlock, definitions: &mut Definitions
-	) -> Result<Vec<QuOp>, QuMsg> {
+	) -> Result<usize, QuMsg> {
 		// Main code
-		let code = {
-			let code = self.cmp_module(code_block, definitions)?;
-			Ok::<QuAsmBuilder, QuMsg>(code)
-		}?;
-		
-		Ok(code.ops)
+		self.cmp_module(code_block, definitions)?;
+		// The module's code block is pushed after the blocks of its functions
+		Ok(definitions.byte_code_blocks.len() - 1)
+	}
+
+
+	/// Sets how the indentation of lines is measured.
+	pub fn set_indentation(&mut self, indentation:Indentation) {
+		self.indentation = indentation;
+	}
+
+
+	/// Returns the warnings and notes found while compiling, removing them
+	/// from the compiler.
+	pub(crate) fn take_diagnostics(&mut self) -> Vec<QuMsg> {
+		return std::mem::take(&mut self.diagnostics);
 	}
 
 
@@ -3087,9 +6261,11 @@ pub struct QuCompiler {
 	) -> Result<RegId, QuMsg> {
 		return match expr_leaf {
 			Expression::Operation(_)
+			| Expression::Unary(_)
 			| Expression::Call(_)
 			| Expression::Bool(_)
 			| Expression::Number(_)
+			| Expression::String(_)
 			=> {
 				Ok(self.context.next_reg())
 			},
@@ -3097,7 +6273,7 @@ pub struct QuCompiler {
 				let item = self.context.find_item(
 					&var.name.slice,
 					definitions,
-				)?;
+				).map_err(|msg| msg.or_span(var.span))?;
 				if let ItemId::Variable(id) = item {
 					let var = self.context.get_variable(id)?;
 					Ok(var.stack_id.into())
@@ -3105,28 +6281,49 @@ pub struct QuCompiler {
 					Ok(self.context.next_reg())
 				}
 			}
-			Expression::Tuple(_) => todo!(),
-			Expression::DotIndex(_) => todo!(),
+			Expression::DotIndex(_)
+			| Expression::Index(_)
+			| Expression::Lambda(_)
+			| Expression::List(_)
+			| Expression::Map(_)
+			| Expression::Tuple(_) => Ok(self.context.next_reg()),
 			Expression::As(expr) =>
 				self.get_expr_reg(&expr.left, definitions),
 		};
 	}
 }
 
+/// Where a *break* or *continue* statement jumps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopJumpKind {
+	/// Jumps past the end of the loop.
+	Break,
+	/// Jumps to the start of the loop's next iteration.
+	Continue,
+}
+
+
 #[derive(Debug, Default, Clone)]
 
 struct QuAsmBuilder {
 	ops: Vec<QuOp>,
+	/// The part of the script each op was compiled from.
+	spans: Vec<Option<QuSpan>>,
 	return_reg: TypedRegId,
 	as_type: Option<ClassId>,
 	constant: Option<ConstantId>,
+	/// The indexes of *break* and *continue* jumps that still have to be
+	/// pointed at their loop.
+	loop_jumps: Vec<(usize, LoopJumpKind)>,
 } impl QuAsmBuilder {
 	fn new() -> Self {
 		return Self {
 			ops: vec![],
+			spans: vec![],
 			return_reg: Default::default(),
 			as_type: None,
 			constant: None,
+			loop_jumps: vec![],
 		}
 	}
 
@@ -3146,11 +6343,25 @@ struct QuAsmBuilder {
 	}
 
 	fn add_builder(&mut self, mut builder:QuAsmBuilder) {
+		let offset = self.ops.len();
+		self.loop_jumps.extend(builder.loop_jumps
+			.into_iter()
+			.map(|(index, kind)| (index + offset, kind))
+		);
 		self.ops.append(&mut builder.ops);
+		self.spans.append(&mut builder.spans);
 	}
 
 	fn add_op(&mut self, op:QuOp) {
 		self.ops.push(op);
+		self.spans.push(None);
+	}
+
+	/// Adds a placeholder jump that gets resolved by
+	/// [`QuAsmBuilder::resolve_loop_jumps`].
+	fn add_loop_jump(&mut self, kind:LoopJumpKind) {
+		self.loop_jumps.push((self.ops.len(), kind));
+		self.add_op(JumpBy(0));
 	}
 
 	fn add_return_op(&mut self, op:QuOp, type_id:ClassId) {
@@ -3166,4 +6377,26 @@ struct QuAsmBuilder {
 	fn len(&self) -> usize {
 		return self.ops.len();
 	}
+
+	/// Points every unresolved *continue* jump at the op index
+	/// `continue_to` and every *break* jump at `break_to`.
+	fn resolve_loop_jumps(&mut self, continue_to:usize, break_to:usize) {
+		for (index, kind) in self.loop_jumps.drain(..) {
+			let target = match kind {
+				LoopJumpKind::Break => break_to,
+				LoopJumpKind::Continue => continue_to,
+			};
+			// The program counter moves past the jump after jumping
+			self.ops[index] = JumpBy(target as isize - index as isize - 1);
+		}
+	}
+
+	/// Sets the span of every op that doesn't have one yet.
+	fn set_span(&mut self, span:QuSpan) {
+		for op_span in &mut self.spans {
+			if op_span.is_none() {
+				*op_span = Some(span);
+			}
+		}
+	}
 }
\ No newline at end of file
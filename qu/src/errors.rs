@@ -1,45 +1,173 @@
-
-use crate::tokens::QuCharIndex;
+use crate::Int;
+use crate::tokens::QuSpan;
 use std::fmt::{self, Display, Debug};
 
 
-pub const ERR_TITLE_EMPTY_CODE_BLOCK:&str = "EMPTY CODE BLOCK";
-pub const ERR_TITLE_PARSER_MATCH_FAILED:&str = "PARSER MATCH FAILED";
-pub const ERR_TITLE_GENERAL:&str = "GENERAL";
-pub const ERR_TITLE_INVALID_FLOW_STATEMENT:&str = "INVALID FLOW STATEMENT";
-pub const ERR_TITLE_INVALID_INDENTATION:&str = "INVALID INDENTATION";
-pub const ERR_TITLE_INVALID_SYNTAX:&str = "INVALID SYNTAX";
-pub const ERR_TITLE_INVALID_VARIABLE_ASSIGNMENT:&str = "INVALID VARIABLE ASSIGNMENT";
-pub const ERR_TITLE_INVALID_VARIABLE_DEFINITION:&str = "INVALID VARIABLE DEFINITION";
-pub const ERR_TITLE_MISSING_CODE_BLOCK:&str = "MISSING CODE BLOCK";
-pub const ERR_TITLE_MISSING_TOKEN:&str = "MISSING TOKEN";
-pub const ERR_TITLE_UNDEFINED_TYPE:&str = "UNDEFINED TYPE";
-pub const ERR_TITLE_UNDEFINED_FN:&str = "UNDEFINED FUNCTION";
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+/// The category of a [`QuMsg`].
+/// 
+/// Hosts can match on the kind of an error instead of its text, which may
+/// change between versions.
+pub enum QuErrorKind {
+	/// An error without a more specific category.
+	#[default]
+	General,
+	/// A code block was started, but no code was found.
+	EmptyCodeBlock,
+	/// The parser failed to match anything.
+	ParserMatchFailed,
+	/// A flow statement, like an `if` or `while`, is malformed.
+	InvalidFlowStatement,
+	/// A line has the wrong indentation.
+	InvalidIndentation,
+	/// The script contains syntax that can't be parsed.
+	InvalidSyntax,
+	/// A variable assignment is malformed or assigns the wrong type.
+	InvalidVariableAssignment,
+	/// A variable declaration is malformed or redefines a variable.
+	InvalidVariableDefinition,
+	/// A literal, like a number, can't be converted to a value.
+	InvalidLiteral,
+	/// A code block is required, but none was found.
+	MissingCodeBlock,
+	/// An expected token wasn't found.
+	MissingToken,
+	/// A declaration, like a class or function, lacks its name.
+	MissingName,
+	/// A type was used, but it isn't defined.
+	UndefinedType,
+	/// A function was called, but it isn't defined.
+	UndefinedFunction,
+	/// A value's type didn't match the expected type.
+	TypeMismatch,
+	/// Registering a module, class or function failed.
+	Registration,
+	/// An error occurred while running a script.
+	Runtime,
+	/// Code that can never run, like statements after a `return`.
+	UnreachableCode,
+	/// A compiled script can't be saved, or its bytes can't be loaded.
+	InvalidBytecode,
+} impl QuErrorKind {
+	/// Returns the title shown in messages of this kind.
+	pub fn title(&self) -> &'static str {
+		match self {
+			QuErrorKind::General => "GENERAL",
+			QuErrorKind::EmptyCodeBlock => "EMPTY CODE BLOCK",
+			QuErrorKind::ParserMatchFailed => "PARSER MATCH FAILED",
+			QuErrorKind::InvalidFlowStatement => "INVALID FLOW STATEMENT",
+			QuErrorKind::InvalidIndentation => "INVALID INDENTATION",
+			QuErrorKind::InvalidSyntax => "INVALID SYNTAX",
+			QuErrorKind::InvalidVariableAssignment => "INVALID VARIABLE ASSIGNMENT",
+			QuErrorKind::InvalidVariableDefinition => "INVALID VARIABLE DEFINITION",
+			QuErrorKind::InvalidLiteral => "INVALID LITERAL",
+			QuErrorKind::MissingCodeBlock => "MISSING CODE BLOCK",
+			QuErrorKind::MissingToken => "MISSING TOKEN",
+			QuErrorKind::MissingName => "MISSING NAME",
+			QuErrorKind::UndefinedType => "UNDEFINED TYPE",
+			QuErrorKind::UndefinedFunction => "UNDEFINED FUNCTION",
+			QuErrorKind::TypeMismatch => "TYPE MISMATCH",
+			QuErrorKind::Registration => "REGISTRATION",
+			QuErrorKind::Runtime => "RUNTIME",
+			QuErrorKind::UnreachableCode => "UNREACHABLE CODE",
+			QuErrorKind::InvalidBytecode => "INVALID BYTECODE",
+		}
+	}
+} impl Display for QuErrorKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.title())
+	}
+}
+
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// How serious a [`QuMsg`] is.
+pub enum QuSeverity {
+	/// The script can't be compiled or run.
+	#[default]
+	Error,
+	/// The script works, but probably not as intended.
+	Warning,
+	/// Extra information about the script.
+	Note,
+} impl QuSeverity {
+	/// Returns the title shown in messages of this severity.
+	pub fn title(&self) -> &'static str {
+		match self {
+			QuSeverity::Error => "ERROR",
+			QuSeverity::Warning => "WARNING",
+			QuSeverity::Note => "NOTE",
+		}
+	}
+} impl Display for QuSeverity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.title())
+	}
+}
 
 
 #[derive(Clone, Debug)]
-/// A message describing an error.
+/// A message describing an error, or a diagnostic of lesser severity.
 pub struct QuMsg {
-	/// The message title.
-	pub title:String,
+	/// The category of the message.
+	pub kind:QuErrorKind,
+	/// How serious the message is.
+	pub severity:QuSeverity,
 	/// The message description.
 	pub description:String,
-	/// The script position to highlight.
-	pub token:QuCharIndex,
+	/// The part of the script that caused the message, if it's known.
+	pub span:Option<QuSpan>,
+	/// A suggestion on how to fix the problem.
+	pub help:Option<String>,
 
 } impl QuMsg {
 
 	/// Constructs a new [`QuMsg`]
 	pub fn new() -> Self {
 		let msg = Self {
-			title: String::default(),
+			kind: QuErrorKind::default(),
+			severity: QuSeverity::default(),
 			description: String::default(),
-			token: QuCharIndex::default()
+			span: None,
+			help: None,
 		};
 		msg
 	}
 
 
+	/// Sets the help text of the message.
+	pub fn with_help(mut self, help:impl Into<String>) -> Self {
+		self.help = Some(help.into());
+		return self;
+	}
+
+
+	/// Returns true if the message is an error.
+	pub fn is_error(&self) -> bool {
+		return self.severity == QuSeverity::Error;
+	}
+
+
+	/// Sets the span of the message if it doesn't have one yet.
+	/// 
+	/// Errors bubble up through the nodes of a script, so the innermost node
+	/// that sets a span is the one the message points at.
+	pub fn or_span(mut self, span:QuSpan) -> Self {
+		if self.span.is_none() {
+			self.span = Some(span);
+		}
+		return self;
+	}
+
+
+	/// Returns the span of the message, or the start of the script if the
+	/// span is unknown.
+	fn location(&self) -> QuSpan {
+		return self.span.unwrap_or_default();
+	}
+
+
 	/// Panics and displays the QuMsg prettily.
 	pub fn panic(&self, script:&str) {
 		panic!("{}", self.make_pretty_message(script));
@@ -54,22 +182,24 @@ pub struct QuMsg {
 
 	/// Creates a pretty Qu error message as a [String].
 	pub fn make_pretty_message(&self, script:&str) -> String {
+		let location = self.location();
+
 		// Line numbers
-		let line_nm_pre_pre = (self.token.row as usize).saturating_sub(1);
-		let line_nm_pre = (self.token.row as usize).saturating_sub(0);
-		let line_nm = (self.token.row as usize).saturating_add(1);
-		let line_nm_post = (self.token.row as usize).saturating_add(2);
-		let line_nm_post_post = (self.token.row as usize).saturating_add(3);
+		let line_nm_pre_pre = (location.row as usize).saturating_sub(1);
+		let line_nm_pre = (location.row as usize).saturating_sub(0);
+		let line_nm = (location.row as usize).saturating_add(1);
+		let line_nm_post = (location.row as usize).saturating_add(2);
+		let line_nm_post_post = (location.row as usize).saturating_add(3);
 
 		// Line text
 		let mut script_lines = script.split("\n");
-		let line_pre_pre = if self.token.row > 1 {
+		let line_pre_pre = if location.row > 1 {
 			script_lines.nth(line_nm_pre_pre-1).unwrap_or("")
 			.to_string()
 		} else {
 			"".to_string()
 		};
-		let line_pre = if self.token.row > 0 {
+		let line_pre = if location.row > 0 {
 			script_lines.next().unwrap_or("").to_string()
 		} else {
 			"".to_string()
@@ -80,15 +210,38 @@ pub struct QuMsg {
 		let line_post_post =
 				script_lines.next().unwrap_or("");
 
+		// Underline the span on its first line
+		let underline = match self.span {
+			Some(span) => {
+				let before = (span.column as usize).saturating_sub(1);
+				let mut underline:String = line.chars()
+					.take(before)
+					.map(|c| if c == '\t' {'\t'} else {' '})
+					.collect();
+				let width = script.get(span.start..span.end)
+					.unwrap_or("")
+					.split('\n')
+					.next()
+					.unwrap_or("")
+					.chars()
+					.count()
+					.max(1);
+				underline.push_str(&"^".repeat(width));
+				format!("\n         {underline}")
+			},
+			None => "".to_string(),
+		};
+
 		// Build code view
 		let code_view = format!(
-			"    {:0>4}:{}\n    {:0>4}:{}\n >> {:0>4}:{}\n    {:0>4}:{}\n    {:0>4}:{}\n\n",
+			"    {:0>4}:{}\n    {:0>4}:{}\n >> {:0>4}:{}{}\n    {:0>4}:{}\n    {:0>4}:{}\n\n",
 			line_nm_pre_pre,
 			line_pre_pre,
 			line_nm_pre,
 			line_pre,
 			line_nm,
 			line,
+			underline,
 			line_nm_post,
 			line_post,
 			line_nm_post_post,
@@ -96,11 +249,14 @@ pub struct QuMsg {
 		);
 
 		// Build error message
-		let msg = format!(
-			"ERROR on line {row}, col {col}; {m0}:\"{m1}\"\n{script}",
-			row=self.token.row+1, col=self.token.column, m0=self.title,
-			m1=self.description, script=code_view
+		let mut msg = format!(
+			"{severity} on line {row}, col {col}; {m0}:\"{m1}\"\n{script}",
+			severity=self.severity, row=location.row+1, col=location.column,
+			m0=self.kind, m1=self.description, script=code_view
 		);
+		if let Some(help) = &self.help {
+			msg.push_str(&format!("help: {help}\n"));
+		}
 		return msg;
 		
 	}
@@ -110,7 +266,7 @@ pub struct QuMsg {
 
 	pub fn done() -> Self {
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_GENERAL.into();
+		msg.kind = QuErrorKind::General;
 		msg.description = "Done".into();
 		return msg;
 	}
@@ -118,7 +274,7 @@ pub struct QuMsg {
 
 	pub fn empty_code_block() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_EMPTY_CODE_BLOCK.to_string();
+		msg.kind = QuErrorKind::EmptyCodeBlock;
 		msg.description = "A code block was started, but no code was found.".to_string();
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -128,17 +284,363 @@ pub struct QuMsg {
 	/// Constructs a `flow statement lacks an expression` message.
 	pub fn flow_statement_lacks_expression() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_FLOW_STATEMENT.to_string();
+		msg.kind = QuErrorKind::InvalidFlowStatement;
 		msg.description = format!("Flow statement requires an expression but non was given.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
 
 
+	/// Constructs a `for loop lacks variable` message.
+	pub fn for_loop_lacks_variable() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidFlowStatement;
+		msg.description = "For loop requires a variable name but none was given.".to_string();
+		msg.help = Some("Name the variable that holds each item (Ex: for item in items:).".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `loop jump outside loop` message.
+	pub fn loop_jump_outside_loop(keyword:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidFlowStatement;
+		msg.description = format!("'{keyword}' can only be used inside of a loop.");
+		msg.help = Some("Move it into the body of a 'while' or 'for' loop.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `not iterable` message.
+	pub fn not_iterable(type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Values of type '{type_name}' can't be iterated over.");
+		msg.help = Some("Iterate over a 'range', a list, a map, or a value of a class that implements 'Iterator'.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `iterator lacks current` message.
+	pub fn iterator_lacks_current(type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::UndefinedFunction;
+		msg.description = format!("Iterator of type '{type_name}' has no 'current' function to get its items from.");
+		msg.help = Some("Add a 'current' function that returns the item the iterator is at.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `index lacks value` message.
+	pub fn index_lacks_value() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidSyntax;
+		msg.description = "Expected an index between the brackets, but none was given.".to_string();
+		msg.help = Some("Put the position of the item in the brackets (Ex: items[0]).".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `index assign lacks value` message.
+	pub fn index_assign_lacks_value() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidVariableAssignment;
+		msg.description = "Expected a value to assign to the index, but none was given.".to_string();
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `index out of bounds` message.
+	pub fn index_out_of_bounds(index:Int, len:usize) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = format!("Index {index} is out of bounds for a list of length {len}.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `missing key` message.
+	pub fn missing_key(key:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = format!("Key '{key}' is not in the map.");
+		msg.help = Some("Check for the key first with 'has'.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `static lacks value` message.
+	pub fn static_lacks_value(the_var:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = format!("The variable '{the_var}' was read before it was given a value.");
+		msg.help = Some("Its declaration may be in a script that failed to compile or hasn't run yet.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `call depth exceeded` message.
+	pub fn call_depth_exceeded(max_depth:usize) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = format!("Calls went more than {max_depth} deep.");
+		msg.help = Some("Check for functions that call themselves without end.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `division by zero` message.
+	pub fn division_by_zero() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = "Attempted to divide an int by zero.".to_string();
+		msg.help = Some("Check that the divisor isn't zero first.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `int overflow` message.
+	pub fn int_overflow(operation:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = format!("The result of '{operation}' doesn't fit in an int.");
+		msg.help = Some(format!("Ints range from {} to {}.", Int::MIN, Int::MAX));
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `destructure lacks value` message.
+	pub fn destructure_lacks_value() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidSyntax;
+		msg.description = "Expected a tuple to destructure, but none was given.".to_string();
+		msg.help = Some("Give the variables a tuple with an element for each (Ex: var (x, y) = 1, 2).".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `not destructurable` message.
+	pub fn not_destructurable(type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Values of type '{type_name}' can't be destructured, only tuples can.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `destructure count mismatch` message.
+	pub fn destructure_count_mismatch(
+		type_name:&str,
+		variable_count:usize,
+	) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Can't destructure a '{type_name}' into {variable_count} variables.");
+		msg.help = Some("Declare a variable for each element of the tuple.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs an `ambiguous function value` message.
+	pub fn ambiguous_function_value(name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Can't tell which function '{name}' refers to.");
+		msg.help = Some("Give the value a function type (Ex: var f fn(int) int = ...).".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `callable argument mismatch` message.
+	pub fn callable_args_mismatch(name:&str, type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("'{name}' is a '{type_name}', which can't be called with these arguments.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs an `invalid bytecode` message.
+	pub fn invalid_bytecode(reason:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidBytecode;
+		msg.description = format!("Can't load the compiled script because {reason}.");
+		msg.help = Some("Compile the script again with this version of Qu.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `registered after compiling` message.
+	pub fn registered_after_compiling() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidBytecode;
+		msg.description = "Can't save the script because items were registered after scripts were compiled.".to_string();
+		msg.help = Some("Register everything before compiling scripts that will be saved.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs an `unsaveable constant` message.
+	pub fn unsaveable_constant(name:&str, type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidBytecode;
+		msg.description = format!("Can't save constant '{name}' because values of type '{type_name}' can't be written to bytecode.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs an `assign type mismatch` message.
+	pub fn assign_type_mismatch(value_type:&str, location_type:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Attempted to assign a value of type '{value_type}' to a location of type '{location_type}'.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs an `unregistered item` message.
+	pub fn unregistered_item(
+		kind:QuErrorKind,
+		item:&str,
+		path:&str,
+	) -> Self{
+		let mut msg = Self::new();
+		msg.kind = kind;
+		msg.description = format!("The compiled script needs {item} '{path}', but it isn't registered.");
+		msg.help = Some("Register it before loading the script.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `registered function mismatch` message.
+	pub fn registered_fn_mismatch(path:&str, registered:&[String]) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!(
+			"The compiled script needs function '{path}', but it's registered as {}.",
+			registered.join(", "),
+		);
+		msg.help = Some("Compile the script again against the registered functions.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `registered class mismatch` message.
+	pub fn registered_class_mismatch(
+		path:&str,
+		size:usize,
+		expected_size:usize,
+	) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("The compiled script needs class '{path}' to take {expected_size} bytes, but the registered class takes {size}.");
+		msg.help = Some("Compile the script again against the registered classes.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `map entry lacks value` message.
+	pub fn map_entry_lacks_value() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidSyntax;
+		msg.description = "Expected a value after the key's ':', but none was given.".to_string();
+		msg.help = Some("Give each key a value (Ex: {\"a\": 1}).".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs an `unhashable map key` message.
+	pub fn unhashable_map_key(key_type:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Maps can't have keys of type '{key_type}'.");
+		msg.help = Some(format!("Keys have to implement 'Hash' and 'Equal' with functions that take a '{key_type}' (Ex: fn hash() int, fn equal(other {key_type}) bool)."));
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `pop from empty list` message.
+	pub fn pop_from_empty_list() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::Runtime;
+		msg.description = "Can't pop an item from an empty list.".to_string();
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `not indexable` message.
+	pub fn not_indexable(type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Values of type '{type_name}' can't be indexed.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `invalid index type` message.
+	pub fn invalid_index_type(type_name:&str, index_type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = format!("Values of type '{type_name}' can't be indexed with a '{index_type_name}'.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `untyped empty collection` message.
+	pub fn untyped_empty_collection(example:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::TypeMismatch;
+		msg.description = "The type of the items of an empty collection is unknown.".to_string();
+		msg.help = Some(format!("Give the collection's type where it's declared (Ex: {example})."));
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `flow branch without if` message.
+	pub fn flow_branch_without_if(keyword:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidFlowStatement;
+		msg.description = format!("Found '{keyword}' branch without a preceding 'if' statement.");
+		msg.help = Some("Branches have to follow an 'if' or 'elif' branch at the same indentation.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
 	/// Constructs a `failed parser match` message.
 	pub fn failed_parser_match() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_PARSER_MATCH_FAILED.to_string();
+		msg.kind = QuErrorKind::ParserMatchFailed;
 		msg.description = "Failed to parse something.".to_string();
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -148,27 +650,59 @@ pub struct QuMsg {
 	/// Constructs a general message.
 	pub fn general(description:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_GENERAL.to_string();
+		msg.kind = QuErrorKind::General;
 		msg.description = description.to_string();
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		msg
 	}
 
 
+	/// Constructs a message of the given kind.
+	pub fn of_kind(kind:QuErrorKind, description:impl Into<String>) -> Self{
+		let mut msg = Self::new();
+		msg.kind = kind;
+		msg.description = description.into();
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		msg
+	}
+
+
 	/// Constructs a `missing token` message.
 	pub fn missing_token(expected_token:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_MISSING_TOKEN.to_string();
+		msg.kind = QuErrorKind::MissingToken;
 		msg.description = format!("Epected a '{expected_token}' token, but it was not found.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
 
 
+	/// Constructs a `missing name` message.
+	pub fn missing_name(declaration:&str, example:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::MissingName;
+		msg.description = format!("Expected a name after '{declaration}', but none was given.");
+		msg.help = Some(format!("Name it after the keyword (Ex: {example})."));
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `fn lacks parameters` message.
+	pub fn fn_lacks_parameters(fn_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::MissingToken;
+		msg.description = format!("Expected parameters in parenthesis after function '{fn_name}', but none were given.");
+		msg.help = Some(format!("Functions without parameters still need parenthesis (Ex: fn {fn_name}():)."));
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
 	/// Constructs a `missing code block` message.
 	pub fn missing_code_block() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_MISSING_CODE_BLOCK.to_string();
+		msg.kind = QuErrorKind::MissingCodeBlock;
 		msg.description = "A code block was expected, but none was found.".to_string();
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -178,8 +712,9 @@ pub struct QuMsg {
 	/// Constructs a `missing code block in flow` message.
 	pub fn missing_code_block_in_flow() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_MISSING_CODE_BLOCK.to_string();
+		msg.kind = QuErrorKind::MissingCodeBlock;
 		msg.description = "Flow statement requires a code block, but none was found.".to_string();
+		msg.help = Some("End the statement with ':' and indent its code on the following lines.".into());
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
@@ -188,8 +723,9 @@ pub struct QuMsg {
 	/// Constructs a `invalid indentation` message.
 	pub fn invalid_indent() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_INDENTATION.to_string();
+		msg.kind = QuErrorKind::InvalidIndentation;
 		msg.description = "Encountered invalid indentation.".to_string();
+		msg.help = Some("Code blocks are indented by one tab more than the line that starts them.".into());
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
@@ -198,7 +734,7 @@ pub struct QuMsg {
 	/// Constructs a `invalid syntax` message.
 	pub fn invalid_syntax() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_SYNTAX.to_string();
+		msg.kind = QuErrorKind::InvalidSyntax;
 		msg.description = "A line has an incorrect indentation level.".to_string();
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -208,17 +744,48 @@ pub struct QuMsg {
 	/// Constructs a `invalid token` message.
 	pub fn invalid_token(invalid_tk:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_SYNTAX.to_string();
+		msg.kind = QuErrorKind::InvalidSyntax;
 		msg.description = format!{"Encountered invalid token '{invalid_tk}'."};
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
 
 
+	/// Constructs a `invalid escape sequence` message.
+	pub fn invalid_escape_sequence(sequence:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidSyntax;
+		msg.description = format!("Encountered invalid escape sequence '{sequence}' in string.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `invalid number literal` message.
+	pub fn invalid_number_literal(literal:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidLiteral;
+		msg.description = format!("Number literal '{literal}' has invalid digits.");
+		msg.help = Some("Hex literals use 0-9 and a-f, octal literals 0-7 and binary literals 0-1.".into());
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `number literal overflow` message.
+	pub fn number_literal_overflow(literal:&str, type_name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidLiteral;
+		msg.description = format!("Number literal '{literal}' is too large to fit in a {type_name}.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
 	/// Constructs a `one liner` message.
 	pub fn one_liner() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_INDENTATION.to_string();
+		msg.kind = QuErrorKind::InvalidIndentation;
 		msg.description = "A code block and flow statement are on the same line.".to_string();
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -228,17 +795,37 @@ pub struct QuMsg {
 	/// Constructs a `unclosed parenthesis expression` message.
 	pub fn unclosed_paren_expr() -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_SYNTAX.to_string();
+		msg.kind = QuErrorKind::InvalidSyntax;
 		msg.description = format!("Parenthesy expression remained unclosed.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
 
 
+	/// Constructs a `unclosed string` message.
+	pub fn unclosed_string() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidSyntax;
+		msg.description = "String literal remained unclosed.".to_string();
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `unary lacks operand` message.
+	pub fn unary_lacks_operand(operator:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::InvalidSyntax;
+		msg.description = format!("Expected a value after '{operator}', but found none.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
 	/// Constructs a `undefined function access` message.
 	pub fn undefined_fn_access(the_fn:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_UNDEFINED_FN.to_string();
+		msg.kind = QuErrorKind::UndefinedFunction;
 		msg.description = format!("Can't use '{the_fn}' because it was not previously defined.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -248,17 +835,37 @@ pub struct QuMsg {
 	/// Constructs a `undefined type access` message.
 	pub fn undefined_type_access(the_type:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_UNDEFINED_TYPE.to_string();
+		msg.kind = QuErrorKind::UndefinedType;
 		msg.description = format!("Can't use '{the_type}' because it was not previously defined.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
 	}
 
 
+	/// Constructs a `undefined module import` message.
+	pub fn undefined_module_import(module:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::UndefinedType;
+		msg.description = format!("Can't import '{module}' because no module by that name is registered.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
+	/// Constructs a `unimportable item` message.
+	pub fn unimportable_item(name:&str) -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::UndefinedType;
+		msg.description = format!("Can't import '{name}' because only modules, classes and functions can be imported.");
+		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
+		return msg;
+	}
+
+
 	/// Constructs a `undefined var access` message.
 	pub fn undefined_var_access(the_var:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_VARIABLE_ASSIGNMENT.to_string();
+		msg.kind = QuErrorKind::InvalidVariableAssignment;
 		msg.description = format!
 			("Can't use '{the_var}' because it was not previously defined.",
 		);
@@ -270,7 +877,7 @@ pub struct QuMsg {
 	/// Constructs a `undefined var assign` message.
 	pub fn undefined_var_assign(the_var:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_VARIABLE_ASSIGNMENT.to_string();
+		msg.kind = QuErrorKind::InvalidVariableAssignment;
 		msg.description = format!("Can't assign to '{the_var}' because it was not previously defined.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -281,7 +888,7 @@ pub struct QuMsg {
 	pub fn var_assign_invalid_value(the_var:&str,
 	invalid_value:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_VARIABLE_ASSIGNMENT.to_string();
+		msg.kind = QuErrorKind::InvalidVariableAssignment;
 		msg.description = format!("Can't assign to variable '{the_var}' with '{invalid_value}' because it is not a valid value.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -291,17 +898,28 @@ pub struct QuMsg {
 	/// Constructs a `var assign lacks value` message.
 	pub fn var_assign_lacks_value(the_var:&str) -> Self{
 			let mut msg = Self::new();
-			msg.title = ERR_TITLE_INVALID_VARIABLE_ASSIGNMENT.to_string();
+			msg.kind = QuErrorKind::InvalidVariableAssignment;
 			msg.description = format!("Variable assignment for '{the_var}' lacks an expression.");
 			#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 			return msg;
 		}
 
 
+	/// Constructs an `unreachable code` warning.
+	pub fn unreachable_code() -> Self{
+		let mut msg = Self::new();
+		msg.kind = QuErrorKind::UnreachableCode;
+		msg.severity = QuSeverity::Warning;
+		msg.description = "This code will never run because it comes after a 'return'.".to_string();
+		msg.help = Some("Remove the code or move it before the 'return'.".into());
+		return msg;
+	}
+
+
 	/// Constructs a `var redefined` message.
 	pub fn var_redefined(the_var:&str) -> Self{
 		let mut msg = Self::new();
-		msg.title = ERR_TITLE_INVALID_VARIABLE_DEFINITION.to_string();
+		msg.kind = QuErrorKind::InvalidVariableDefinition;
 		msg.description = format!("Can't define the variable '{the_var}' because it was already defined previously.");
 		#[cfg(feature = "qu_panic_upon_error")] panic!("{}", msg);
 		return msg;
@@ -310,10 +928,11 @@ pub struct QuMsg {
 } impl Display for QuMsg {
 
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		return write!(f, "ERROR on line {row}, col {col}; {title}:\"{descr}",
-			row = self.token.row+1,
-			col = self.token.column,
-			title = self.title,
+		return write!(f, "{severity} on line {row}, col {col}; {title}:\"{descr}",
+			severity = self.severity,
+			row = self.location().row+1,
+			col = self.location().column,
+			title = self.kind,
 			descr = self.description,
 		);
 	}
@@ -326,6 +945,18 @@ pub struct QuMsg {
 		msg
     }
 
+} impl From<Vec<QuMsg>> for QuMsg {
+
+	/// Returns the first error of a list of diagnostics.
+	fn from(diagnostics:Vec<QuMsg>) -> Self {
+		let first = diagnostics.iter().position(|msg| msg.is_error())
+			.unwrap_or(0);
+		return match diagnostics.into_iter().nth(first) {
+			Some(msg) => msg,
+			None => QuMsg::general("Failed without any diagnostics."),
+		};
+	}
+
 } impl From<String> for QuMsg {
 
     fn from(msg:String) -> Self {
@@ -1,11 +1,27 @@
 
 use std::alloc::Layout;
+use std::any::TypeId;
 use std::fmt::Debug;
-
+use std::mem::size_of;
+
+use crate::Bool;
+use crate::Callable;
+use crate::Int;
+use crate::List;
+use crate::ListIterator;
+use crate::Map;
+use crate::MapKey;
+use crate::QuEqual;
+use crate::QuHash;
+use crate::QuIterator;
+use crate::QuErrorKind;
 use crate::QuMsg;
 use crate::Register;
+use crate::Void;
+use crate::QuCompiler;
 use crate::QuVm;
 use crate::Uuid;
+use crate::compiler::CONSTRUCTOR_NAME;
 use crate::compiler::CommonItem;
 use crate::compiler::ConstantId;
 use crate::compiler::Definitions;
@@ -16,7 +32,9 @@ use crate::compiler::FunctionReference;
 use crate::compiler::ItemId;
 use crate::compiler::ModuleId;
 use crate::compiler::ModuleMetadata;
+use crate::objects::Capture;
 use crate::vm::RegId;
+use crate::vm::TypedRegId;
 
 
 pub struct ArgsAPI<'a> {
@@ -26,40 +44,165 @@ pub struct ArgsAPI<'a> {
 	pub(crate) out_id: RegId,
 } impl<'a> ArgsAPI<'a> {
 	/// Gets a reference to the value of the function argument at `index`.
+	/// 
+	/// Returns an error if the function has no argument at `index` or if the
+	/// argument isn't a `T`.
 	pub fn get<T: Register + 'static>(
 		&self,
 		index:usize,
 	) -> Result<&T, QuMsg> {
 		let fn_data = self.vm.definitions.get_function(self.fn_id)?;
-		let value_type_id = self.vm.definitions.class_id::<T>().unwrap();
-		let fn_param_id = fn_data.identity.parameters[index];
+		let Some(&fn_param_id) = fn_data.identity.parameters.get(index) else {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Function '{}' has no parameter[{}].",
+				fn_data.identity.name,
+				index,
+			)));
+		};
+		// Values of every function type are callables
+		let is_callable = TypeId::of::<T>() == TypeId::of::<Callable>();
+		let param_class = self.vm.definitions.get_class(fn_param_id)?;
+		if is_callable && param_class.function_type.is_some() {
+			return self.vm.read::<T>(self.arg_ids[index]);
+		}
+		let value_type_id = self.vm.definitions.class_id::<T>()?;
 		if fn_param_id != value_type_id {
-			panic!(
-				"Return value of type {} does not match function's parameter[{}] type {}",
-				self.vm.definitions.get_class(value_type_id).unwrap().common.name,
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Argument of type {} does not match function's parameter[{}] type {}",
+				self.vm.definitions.get_class(value_type_id)?.common.name,
 				index,
-				self.vm.definitions.get_class(fn_param_id).unwrap().common.name,
-			)
+				self.vm.definitions.get_class(fn_param_id)?.common.name,
+			)));
 		}
 		self.vm.read::<T>(self.arg_ids[index])
 	}
 
+	/// Gets a mutable reference to the value of the function argument at
+	/// `index`.
+	/// 
+	/// Changes are made to the caller's value in place, which is how
+	/// functions like `Iterator.next` advance their iterator. Returns an error
+	/// if the function has no argument at `index` or if the argument isn't a
+	/// `T`.
+	pub fn get_mut<T: Register + 'static>(
+		&mut self,
+		index:usize,
+	) -> Result<&mut T, QuMsg> {
+		self.get::<T>(index)?;
+		let class_id = self.vm.definitions.class_id::<T>()?;
+		self.vm.reg_get_mut::<T>(TypedRegId::new(self.arg_ids[index], class_id))
+	}
+
 	/// Sets the return value of the function to `value`.
+	/// 
+	/// Returns an error if the function doesn't return a `T`.
 	pub fn set<T: Register + 'static>(
 		&mut self,
 		value:T,
-	) {
-		let fn_data = self.vm.definitions.get_function(self.fn_id).unwrap();
-		let value_type_id = self.vm.definitions.class_id::<T>().unwrap();
+	) -> Result<(), QuMsg> {
+		let fn_data = self.vm.definitions.get_function(self.fn_id)?;
+		let value_type_id = self.vm.definitions.class_id::<T>()?;
 		let fn_return_id = fn_data.identity.return_type;
 		if fn_return_id != value_type_id {
-			panic!(
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
 				"Return value of type {} does not match function's return type {}",
-				self.vm.definitions.get_class(value_type_id).unwrap().common.name,
-				self.vm.definitions.get_class(fn_return_id).unwrap().common.name,
-			)
+				self.vm.definitions.get_class(value_type_id)?.common.name,
+				self.vm.definitions.get_class(fn_return_id)?.common.name,
+			)));
 		}
 		self.vm.write::<T>(self.out_id, value);
+		Ok(())
+	}
+
+	/// Sets the return value of the function to a copy of the argument at
+	/// `index`.
+	pub(crate) fn copy_arg(&mut self, index:usize) -> Result<(), QuMsg> {
+		let fn_data = self.vm.definitions.get_function(self.fn_id)?;
+		let class_id = fn_data.identity.parameters[index];
+		let size = self.vm.definitions.get_class(class_id)?.size;
+		self.vm.copy_reg(self.arg_ids[index], self.out_id, size as usize);
+		// The copy gets its own copies of what the argument points to
+		self.vm.own_value(self.out_id, class_id)
+	}
+
+	/// Returns the class of the function's parameter at `index`.
+	pub(crate) fn parameter_class(&self, index:usize) -> Result<ClassId, QuMsg> {
+		let fn_data = self.vm.definitions.get_function(self.fn_id)?;
+		fn_data.identity.parameters.get(index).copied().ok_or_else(|| {
+			QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Function '{}' has no parameter[{}].",
+				fn_data.identity.name,
+				index,
+			))
+		})
+	}
+
+	/// Gets a reference to the argument at `index` as a `T`, without checking
+	/// its class. Used by classes that share a Rust type, like the lists of a
+	/// [`DynFunction`](crate::objects::DynFunction).
+	pub(crate) fn get_dyn<T:'static>(&self, index:usize) -> Result<&T, QuMsg> {
+		self.vm.read::<T>(self.arg_ids[index])
+	}
+
+	/// Gets a mutable reference to the argument at `index` as a `T`, without
+	/// checking its class. See [`ArgsAPI::get_dyn`].
+	pub(crate) fn get_dyn_mut<T:'static>(
+		&mut self,
+		index:usize,
+	) -> Result<&mut T, QuMsg> {
+		let class_id = self.parameter_class(index)?;
+		self.vm.reg_get_mut::<T>(TypedRegId::new(self.arg_ids[index], class_id))
+	}
+
+	/// Sets the return value of the function to `value`, without checking
+	/// the function's return type. See [`ArgsAPI::get_dyn`].
+	pub(crate) fn set_dyn<T:'static>(&mut self, value:T) {
+		self.vm.write::<T>(self.out_id, value);
+	}
+
+	/// Returns a copy of the argument at `index` that owns what it points to,
+	/// so it can be kept off the stack.
+	pub(crate) fn capture_arg(&mut self, index:usize) -> Result<Capture, QuMsg> {
+		let class_id = self.parameter_class(index)?;
+		self.vm.capture_value(self.arg_ids[index], class_id)
+	}
+
+	/// Sets the return value of the function to a copy of `value`.
+	pub(crate) fn set_captured(&mut self, value:&Capture) -> Result<(), QuMsg> {
+		let fn_data = self.vm.definitions.get_function(self.fn_id)?;
+		let class_id = fn_data.identity.return_type;
+		self.vm.restore_value(value, self.out_id, class_id)
+	}
+
+	/// Returns the hash of the argument at `index`, as given by its class's
+	/// implementation of `Hash`.
+	pub(crate) fn hash_arg(&mut self, index:usize) -> Result<Int, QuMsg> {
+		let class_id = self.parameter_class(index)?;
+		let fn_id = self.vm.definitions.trait_function_of(
+			QuHash::id(),
+			class_id,
+			"hash",
+		)?;
+		let arg = self.vm.value_bytes(self.arg_ids[index], class_id)?.to_vec();
+		self.vm.call_with_bytes::<Int>(fn_id, &[&arg])
+	}
+
+	/// Returns true if the argument at `index` equals `other`, as given by
+	/// its class's implementation of `Equal`. `other` has to be of the same
+	/// class as the argument.
+	pub(crate) fn arg_equals(
+		&mut self,
+		index:usize,
+		other:&Capture,
+	) -> Result<bool, QuMsg> {
+		let class_id = self.parameter_class(index)?;
+		let fn_id = self.vm.definitions.trait_function_of(
+			QuEqual::id(),
+			class_id,
+			"equal",
+		)?;
+		let arg = self.vm.value_bytes(self.arg_ids[index], class_id)?.to_vec();
+		self.vm.call_with_bytes::<Bool>(fn_id, &[&arg, &other.bytes])
 	}
 
 	pub fn set_hold(&mut self, value: bool) {
@@ -131,7 +274,10 @@ pub struct Registerer<'a> {
 	fn add_class<T:Register+'static>(
 		&mut self
 	) -> Result<ClassId, QuMsg> {
-		panic!("Classes can't be added at this level. Add a module first.")
+		Err(QuMsg::of_kind(
+			QuErrorKind::Registration,
+			"Classes can't be added at this level. Add a module first.",
+		))
 	}
 
 	fn add_constant<T: Register + 'static>(
@@ -139,7 +285,10 @@ pub struct Registerer<'a> {
 		_name: impl Into<String>,
 		_value: T,
 	) -> Result<ConstantId, QuMsg> {
-		panic!("Constants can't be added at this level. Add a module first.")
+		Err(QuMsg::of_kind(
+			QuErrorKind::Registration,
+			"Constants can't be added at this level. Add a module first.",
+		))
 	}
 
 	fn add_function(
@@ -149,7 +298,10 @@ pub struct Registerer<'a> {
 		_out: ClassId,
 		_body: &'static ExternalFunctionPointer,
 	) -> Result<(), QuMsg> {
-		panic!("Functions can't be added at this level. Add a module first.")
+		Err(QuMsg::of_kind(
+			QuErrorKind::Registration,
+			"Functions can't be added at this level. Add a module first.",
+		))
 	}
 
 	fn add_class_static_function(
@@ -160,14 +312,19 @@ pub struct Registerer<'a> {
 		_out: ClassId,
 		_body: &'static ExternalFunctionPointer,
 	) -> Result<(), QuMsg> {
-		panic!("Static functions can't be added at this level. Add a module first.")
+		Err(QuMsg::of_kind(
+			QuErrorKind::Registration,
+			"Static functions can't be added at this level. Add a module first.",
+		))
 	}
 }
 
 
 #[derive(Clone, Copy, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+/// An ID for a class.
 pub struct ClassId(pub usize);
 impl ClassId {
+	/// Constructs a new [`ClassId`].
 	pub fn new(index:usize) -> Self {
 		Self(index)
 	}
@@ -214,11 +371,41 @@ impl FunctionId {
 }
 
 
+/// The size of the header of a trait-typed value. The header holds the
+/// [`ClassId`] of the class of the value.
+pub(crate) const DYN_HEADER_SIZE:usize = size_of::<ClassId>();
+/// The size of the largest value that a trait-typed value can hold.
+pub(crate) const DYN_PAYLOAD_SIZE:usize = 16;
+
+
+/// A field of a [`QuStruct`].
+#[derive(Debug, Default, Clone)]
+pub struct ClassField {
+	pub name: String,
+	pub class_id: ClassId,
+	/// The location of the field in bytes from the start of the struct.
+	pub offset: usize,
+}
+
+
 #[derive(Debug, Default, Clone)]
 pub struct QuStruct {
 	/// The size of the struct in bytes.
 	pub size: u8,
 	pub(crate) is_trait: bool,
+	/// Whether the class was declared in a script, rather than registered.
+	pub(crate) from_script: bool,
+	/// Whether the class is a tuple, whose fields are its elements.
+	pub(crate) is_tuple: bool,
+	/// The parameters and return type of a function type, whose values are
+	/// [`Callable`]s.
+	pub(crate) function_type: Option<(Box<[ClassId]>, ClassId)>,
+	/// Whether values of a registered class own resources that have to be
+	/// dropped, like the text of a [`Str`](crate::Str).
+	pub(crate) needs_drop: bool,
+	/// The fields of a class declared in a script, in the order they are laid
+	/// out in memory.
+	pub fields: Vec<ClassField>,
 	pub common: CommonItem,
 
 } impl QuStruct {
@@ -261,6 +448,12 @@ pub struct QuStruct {
 	) -> Result<FunctionGroupId, QuMsg> {
 		self.common.get_function_group_id(function_name)
 	}
+
+
+	/// Returns the field with the given name.
+	pub fn get_field(&self, name: &str) -> Option<&ClassField> {
+		self.fields.iter().find(|field| field.name == name)
+	}
 }
 
 
@@ -292,6 +485,265 @@ pub trait RegistererLayer {
 		self.get_definitions_mut().register_module_struct::<T>(module_id)
 	}
 
+	/// Adds a [`List`] class for items of type `T`, which has to be
+	/// registered already. The class is named after its items' class
+	/// (Ex: List[int]).
+	fn add_list_class<T:Register+Clone+'static>(
+		&mut self
+	) -> Result<ClassId, QuMsg> {
+		let Some(item) = self.get_class_id_of::<T>() else {
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't add a list of '{}' because the class isn't registered.",
+				T::name(),
+			)));
+		};
+		let int = self.get_definitions().class_id::<Int>()?;
+		let void = self.get_definitions().class_id::<Void>()?;
+		let list = self.add_class::<List<T>>()?;
+
+		self.add_class_static_function(list, CONSTRUCTOR_NAME,
+			[],
+			list,
+			&|api| {
+				api.set::<List<T>>(List::new())?;
+				Ok(())
+			}
+		)?;
+		self.add_function("copy",
+			[list],
+			list,
+			&|api| {
+				let value = api.get::<List<T>>(0)?.clone();
+				api.set::<List<T>>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list, "len",
+			[list],
+			int,
+			&|api| {
+				let value = api.get::<List<T>>(0)?.len() as Int;
+				api.set::<Int>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list, "append",
+			[list, item],
+			void,
+			&|api| {
+				let value = api.get::<T>(1)?.clone();
+				api.get::<List<T>>(0)?.push(value);
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list, "pop",
+			[list],
+			item,
+			&|api| {
+				let value = api.get::<List<T>>(0)?
+					.pop()
+					.ok_or_else(QuMsg::pop_from_empty_list)?;
+				api.set::<T>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list, "get",
+			[list, int],
+			item,
+			&|api| {
+				let value = api.get::<List<T>>(0)?
+					.get_item(*api.get::<Int>(1)?)?;
+				api.set::<T>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list, "set",
+			[list, int, item],
+			void,
+			&|api| {
+				let value = api.get::<T>(2)?.clone();
+				api.get::<List<T>>(0)?
+					.set_item(*api.get::<Int>(1)?, value)?;
+				Ok(())
+			}
+		)?;
+
+		// Iterating
+		let iterator = self.get_definitions().class_id::<QuIterator>()?;
+		let bool = self.get_definitions().class_id::<Bool>()?;
+		let list_iterator = self.add_class::<ListIterator<T>>()?;
+		self.add_function("copy",
+			[list_iterator],
+			list_iterator,
+			&|api| {
+				let value = api.get::<ListIterator<T>>(0)?.clone();
+				api.set::<ListIterator<T>>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list, "iter",
+			[list],
+			list_iterator,
+			&|api| {
+				let list = api.get::<List<T>>(0)?.clone();
+				api.set::<ListIterator<T>>(ListIterator::new(list))?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(list_iterator, "current",
+			[list_iterator],
+			item,
+			&|api| {
+				let value = api.get::<ListIterator<T>>(0)?
+					.current()
+					.ok_or_else(|| QuMsg::of_kind(
+						QuErrorKind::Runtime,
+						"The list iterator isn't at an item.",
+					))?;
+				api.set::<T>(value)?;
+				Ok(())
+			}
+		)?;
+		self.implement(iterator, list_iterator)?;
+		self.implement_function(
+			iterator,
+			list_iterator,
+			"next", [list_iterator], bool,
+			&|api| {
+				let value = api.get_mut::<ListIterator<T>>(0)?.advance();
+				api.set::<Bool>(value)?;
+				Ok(())
+			},
+		)?;
+		Ok(list)
+	}
+
+	/// Adds a [`Map`] class with keys of type `K` and values of type `V`.
+	/// Both have to be registered already, and lists of `K` have to be added
+	/// with [`RegistererLayer::add_list_class`] for the map's keys. The class
+	/// is named after its keys' and values' classes (Ex: Map[str, int]).
+	fn add_map_class<K:MapKey, V:Register+Clone>(
+		&mut self
+	) -> Result<ClassId, QuMsg> {
+		let d = self.get_definitions();
+		let (Some(key), Some(value)) = (
+			self.get_class_id_of::<K>(),
+			self.get_class_id_of::<V>(),
+		) else {
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't add a map of '{}' to '{}' because the classes aren't registered.",
+				K::name(),
+				V::name(),
+			)));
+		};
+		if !key.is(d.class_id::<QuHash>()?, d) {
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't add a map with '{}' keys because the class doesn't implement 'Hash'.",
+				K::name(),
+			)));
+		}
+		let (Some(keys), Some(keys_iterator)) = (
+			self.get_class_id_of::<List<K>>(),
+			self.get_class_id_of::<ListIterator<K>>(),
+		) else {
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't add a map with '{}' keys because lists of the keys aren't added.",
+				K::name(),
+			)));
+		};
+		let int = d.class_id::<Int>()?;
+		let bool = d.class_id::<Bool>()?;
+		let void = d.class_id::<Void>()?;
+		let map = self.add_class::<Map<K, V>>()?;
+
+		self.add_class_static_function(map, CONSTRUCTOR_NAME,
+			[],
+			map,
+			&|api| {
+				api.set::<Map<K, V>>(Map::new())?;
+				Ok(())
+			}
+		)?;
+		self.add_function("copy",
+			[map],
+			map,
+			&|api| {
+				let value = api.get::<Map<K, V>>(0)?.clone();
+				api.set::<Map<K, V>>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "len",
+			[map],
+			int,
+			&|api| {
+				let value = api.get::<Map<K, V>>(0)?.len() as Int;
+				api.set::<Int>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "has",
+			[map, key],
+			bool,
+			&|api| {
+				let value = api.get::<Map<K, V>>(0)?
+					.contains_key(api.get::<K>(1)?);
+				api.set::<Bool>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "remove",
+			[map, key],
+			bool,
+			&|api| {
+				let value = api.get::<Map<K, V>>(0)?
+					.remove(api.get::<K>(1)?)
+					.is_some();
+				api.set::<Bool>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "keys",
+			[map],
+			keys,
+			&|api| {
+				let value = api.get::<Map<K, V>>(0)?.keys();
+				api.set::<List<K>>(value.into())?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "iter",
+			[map],
+			keys_iterator,
+			&|api| {
+				let keys = api.get::<Map<K, V>>(0)?.keys();
+				api.set::<ListIterator<K>>(ListIterator::new(keys.into()))?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "get",
+			[map, key],
+			value,
+			&|api| {
+				let value = api.get::<Map<K, V>>(0)?
+					.get_item(api.get::<K>(1)?)?;
+				api.set::<V>(value)?;
+				Ok(())
+			}
+		)?;
+		self.add_function_to_class(map, "set",
+			[map, key, value],
+			void,
+			&|api| {
+				let key = api.get::<K>(1)?.clone();
+				let value = api.get::<V>(2)?.clone();
+				api.get::<Map<K, V>>(0)?.insert(key, value);
+				Ok(())
+			}
+		)?;
+		Ok(map)
+	}
+
 	/// Adds a constant to the current layer.
 	fn add_constant<T: Register + 'static>(
 		&mut self,
@@ -355,22 +807,28 @@ pub trait RegistererLayer {
 
 		let args = args.into();
 
+		let name = name.into();
 		if args.len() == 0 {
-			panic!("Can't add function to class without binding a 'self' argument. TODO: better msg")
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't add function '{}' to class '{}' because it has no first argument to bind to the class.",
+				name,
+				self.get_definitions().get_class(class)?.common.name,
+			)));
 		}
 		if !args[0].is(class, self.get_definitions()) {
-			panic!(
-				"First argument's type, {}, does not match class's type, {}.",
-				self.get_definitions().get_class(args[0])?.common.name,
+			return Err(QuMsg::of_kind(QuErrorKind::Registration, format!(
+				"Can't add function '{}' to class '{}' because its first argument's type, {}, does not match the class.",
+				name,
 				self.get_definitions().get_class(class)?.common.name,
-			)
+				self.get_definitions().get_class(args[0])?.common.name,
+			)));
 		}
 
 		self.get_definitions_mut().define_function_in_item(
 			ItemId::Module(module_id),
 			FunctionMetadata {
 				identity: FunctionIdentity {
-					name: name.into(),
+					name,
 					parameters: args,
 					return_type: out,
 				},
@@ -384,6 +842,37 @@ pub trait RegistererLayer {
 		Ok(())
 	}
 
+	/// Adds a function without a body to a trait. Classes that implement the
+	/// trait have to implement the function.
+	fn add_trait_function(
+		&mut self,
+		for_trait: ClassId,
+		name: impl Into<String>,
+		args: impl Into<Box<[ClassId]>>,
+		out: ClassId,
+	) -> Result<(), QuMsg> {
+		let module_id = match self.get_layer_item_id() {
+			ItemId::Module(id) => {id},
+			_ => todo!("Support adding functions to more types items"),
+		};
+
+		self.get_definitions_mut().define_function_in_item(
+			ItemId::Module(module_id),
+			FunctionMetadata {
+				identity: FunctionIdentity {
+					name: name.into(),
+					parameters: args.into(),
+					return_type: out,
+				},
+				code_block: FunctionReference::Abstract,
+				..Default::default()
+			},
+			Some(for_trait),
+			true,
+		)?;
+		Ok(())
+	}
+
 	/// Adds a static function to a class.
 	fn add_class_static_function(
 		&mut self,
@@ -436,11 +925,37 @@ pub trait RegistererLayer {
 
 		let id = self.get_definitions_mut()
 			.register_module_struct::<T>(module_id)?;
-		self.get_definitions_mut().get_class_mut(id)?.is_trait = true;
+		let class = self.get_definitions_mut().get_class_mut(id)?;
+		class.is_trait = true;
+		class.size = (DYN_HEADER_SIZE + DYN_PAYLOAD_SIZE) as u8;
+
+		self.add_class_static_function(id, "copy",
+			[id],
+			id,
+			&|api| api.copy_arg(0)
+		)?;
 		Ok(id)
 	}
 
 
+	/// Returns the function type of functions with `parameters` that return
+	/// `return_type` (Ex: fn(int) int).
+	/// 
+	/// Registered functions that take a function type get the function
+	/// values passed to them as [`Callable`]s.
+	fn function_type(
+		&mut self,
+		parameters:&[ClassId],
+		return_type:ClassId,
+	) -> Result<ClassId, QuMsg> {
+		QuCompiler::function_type_class(
+			parameters,
+			return_type,
+			self.get_definitions_mut(),
+		)
+	}
+
+
 	/// Returns the [`ClassId`] of the given struct.
 	fn get_class_id_of<T: Register + 'static>(
 		&self
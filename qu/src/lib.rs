@@ -28,27 +28,39 @@ SOFTWARE.
 #![warn(missing_docs)]
 #![warn(rustdoc::broken_intra_doc_links)]
 
+mod bytecode;
 mod compiler;
 mod errors;
 mod import;
 pub mod objects;
 mod parser;
+mod script;
 mod tokens;
 mod vm;
 
+use std::any::TypeId;
 use std::any::type_name;
 use std::marker::PhantomData;
 
+use compiler::Definitions;
 use compiler::RegistrationMethod;
-use tokens::TOKEN_TYPE_NAME;
-use tokens::QuToken;
+use compiler::FunctionIdentity;
+use vm::MAIN_MODULE;
+use vm::RegId;
+pub use errors::QuErrorKind;
+pub use import::ClassId;
 pub use errors::QuMsg;
+pub use errors::QuSeverity;
+pub use tokens::Indentation;
+pub use tokens::QuSpan;
 pub use compiler::QuCompiler;
 pub use objects::*;
 pub use parser::QuParser;
 pub use vm::QuVm;
 pub use vm::TypedRegId;
 pub use import::RegistererLayer;
+pub use script::Script;
+pub use bytecode::QUC_VERSION;
 
 type Uuid = uuid::Uuid;
 
@@ -76,6 +88,12 @@ type Uuid = uuid::Uuid;
 #[derive(Default)]
 pub struct Qu<'a> {
 	vm: QuVm,
+	/// How the indentation of lines in scripts is measured.
+	indentation: Indentation,
+	/// The registered items alone, which loaded scripts are added to.
+	/// [`None`] until a script is compiled or loaded, as the Vm holds only
+	/// registered items until then.
+	registered: Option<Definitions>,
 	ph: PhantomData<&'a ()>,
 } impl<'a> Qu<'a> {
 
@@ -91,18 +109,56 @@ pub struct Qu<'a> {
 	pub fn new() -> Self {
 		Qu {
 			vm: QuVm::new(Uuid::new_v4()),
+			indentation: Indentation::default(),
+			registered: None,
 			ph: PhantomData {},
 		}
 	}
 
 
+	/// Sets how the indentation of lines is measured in scripts compiled
+	/// after this call. Scripts are indented with tabs by default.
+	/// 
+	/// # Examples
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Indentation;
+	/// use qu::Qu;
+	/// 
+	/// let mut qu = Qu::new();
+	/// qu.set_indentation(Indentation::Spaces(4));
+	/// 
+	/// let value:i32 = *qu.run_and_get("
+	/// var value int = 1
+	/// if value < 2:
+	///     value = 2
+	/// return value
+	/// ")?;
+	/// assert_eq!(value, 2);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn set_indentation(&mut self, indentation:Indentation) {
+		self.indentation = indentation;
+	}
+
+
 	/// Compiles Qu script without running it.
 	/// 
-	/// Does not return the compiled bytecode.
+	/// Returns a [`Script`] that can be run any number of times with
+	/// [`Qu::run_script`], which also holds the warnings and notes found in
+	/// `code`. Items declared by the script, such as functions, can be used by
+	/// scripts compiled after it.
 	///
 	/// # Errors
 	/// 
-	/// If `code` contains improper Qu syntax then an [`Err`] is returned.
+	/// If `code` contains improper Qu syntax then an [`Err`] is returned with
+	/// every diagnostic found, of which at least one is an error. A list of
+	/// diagnostics converts into its first error, so `?` can be used in
+	/// functions that return a [`QuMsg`].
 	/// 
 	/// # Examples
 	/// 
@@ -112,20 +168,43 @@ pub struct Qu<'a> {
 	/// # fn main(){example().unwrap()}
 	/// # fn example() -> Result<(), qu::QuMsg> {
 	/// let mut qu = Qu::new();
-	/// qu.compile("
+	/// let script = qu.compile("
 	/// 	fn foo() int:
 	/// 		return 3
 	/// ")?;
+	/// assert!(script.diagnostics().is_empty());
 	/// let result:i32 = *qu.run_and_get("return foo()")?;
 	/// assert_eq!(result, 3);
 	/// # return Ok(());
 	/// # }
 	/// ```
-	pub fn compile(&mut self, code:&str) -> Result<(), QuMsg> {
+	/// 
+	/// Every error in a script is reported, not only the first one.
+	/// 
+	/// ```
+	/// use qu::Qu;
+	/// 
+	/// let mut qu = Qu::new();
+	/// let errors = qu.compile("
+	/// 	var a int = )
+	/// 	var b int = 2
+	/// 	var c int = (
+	/// ").unwrap_err();
+	/// assert_eq!(errors.len(), 2);
+	/// ```
+	pub fn compile(&mut self, code:&str) -> Result<Script, Vec<QuMsg>> {
+		self.registered.get_or_insert_with(|| self.vm.definitions.clone());
+
 		// Compile
 		let mut c = QuCompiler::new();
-		c.compile(code, &mut self.vm.definitions)?;
-		Ok(())
+		c.set_indentation(self.indentation);
+		let (code_block, module_id) = c.compile(code, &mut self.vm.definitions)?;
+		Ok(Script {
+			uuid: *self.get_uuid(),
+			code_block,
+			module_id,
+			diagnostics: c.take_diagnostics(),
+		})
 	}
 
 
@@ -163,7 +242,7 @@ pub struct Qu<'a> {
 	/// 			[],
 	/// 			my_class_id,
 	/// 			&|api| {
-	/// 				api.set(MyClass(25));
+	/// 				api.set(MyClass(25))?;
 	/// 				Ok(())
 	/// 			}
 	/// 		)?;
@@ -181,7 +260,12 @@ pub struct Qu<'a> {
 	/// # }
 	/// ```
 	pub fn register(&mut self, body: &RegistrationMethod) -> Result<(), QuMsg>{
-		self.vm.definitions.register(body)
+		let result = self.vm.definitions.register(body);
+		// Scripts loaded later are added to the registered items alone
+		if let (Some(registered), Ok(())) = (&mut self.registered, &result) {
+			registered.register(body)?;
+		}
+		result
 	}
 
 
@@ -207,10 +291,230 @@ pub struct Qu<'a> {
 	/// # }
 	/// ```
 	pub fn run(&mut self, script:&str) -> Result<(), QuMsg> {
-		self.compile(script)?;
+		let script = self.compile(script)?;
+		self.run_script(&script)
+	}
+
+
+	/// Runs a [`Script`] compiled by [`Qu::compile`], without compiling it
+	/// again.
+	/// 
+	/// Each run starts from a fresh top level frame, so the script's
+	/// variables are initialized again. The value the script returns can be
+	/// read like with [`Qu::run_and_get`] by [`Qu::get_result`].
+	/// 
+	/// # Errors
+	/// 
+	/// If `script` was compiled by another [`Qu`] instance or a problem
+	/// occurs at runtime then an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Qu;
+	/// use qu::Int;
+	/// 
+	/// let mut qu = Qu::new();
+	/// qu.run("var spawned int = 0")?;
+	/// 
+	/// let script = qu.compile("
+	/// var health int = 100
+	/// spawned = spawned + 1
+	/// return health + spawned
+	/// ")?;
+	/// qu.run_script(&script)?;
+	/// qu.run_script(&script)?;
+	/// let result:Int = *qu.get_result()?;
+	/// assert_eq!(result, 102);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn run_script(&mut self, script:&Script) -> Result<(), QuMsg> {
+		if script.uuid != *self.get_uuid() {
+			return Err(QuMsg::general(
+				"Can't run a script compiled by another Qu instance."
+			));
+		}
+		// Discard the return value of any previous run
+		self.vm.return_value_id_maybe();
 		const PRINT_COMPIELED_BYTECODE:bool = false;
-		if PRINT_COMPIELED_BYTECODE { dbg!(&self.vm.definitions.byte_code_blocks); } // Debug print bytecode
-		self.vm.loop_ops(self.vm.definitions.byte_code_blocks.len()-1)
+		if PRINT_COMPIELED_BYTECODE { println!("{}", self.disassemble()); } // Debug print bytecode
+		self.vm.run_code_block(script.code_block)
+	}
+
+
+	/// Returns the bytecode of everything this [`Qu`] instance has compiled
+	/// or loaded as readable text.
+	/// 
+	/// Every code block is listed along with the functions compiled into
+	/// it. Ops show the names of the functions and constants they use, the
+	/// registers they read and write (Ex: `&8`), and the labels that jumps
+	/// go to.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Qu;
+	/// use qu::Indentation;
+	/// 
+	/// let mut qu = Qu::new();
+	/// qu.set_indentation(Indentation::Spaces(4));
+	/// qu.compile("
+	/// var count int = 0
+	/// while count < 3:
+	///     count = count + 1
+	/// ")?;
+	/// let text = qu.disassemble();
+	/// assert!(text.contains("static count = "));
+	/// assert!(text.contains("jump L1 if not held"));
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn disassemble(&self) -> String {
+		vm::disassemble(&self.vm.definitions)
+	}
+
+
+	/// Saves a compiled script as the bytes of a `.quc` file, which
+	/// [`Qu::load_script`] can load without the script's source.
+	/// 
+	/// The file holds everything this [`Qu`] instance has compiled, not
+	/// just `script`, so the functions and classes of scripts that were run
+	/// before keep working. Registered items aren't saved. The registered
+	/// functions, classes, modules and constants that the scripts use are
+	/// saved by name, and have to be registered again before the file is
+	/// loaded.
+	/// 
+	/// # Errors
+	/// 
+	/// If `script` was compiled by another [`Qu`] instance, a registered
+	/// constant is of a type that can't be saved, such as one that isn't in
+	/// a module, or items were registered after a script was compiled, then
+	/// an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Qu;
+	/// use qu::Int;
+	/// use qu::Indentation;
+	/// 
+	/// let mut qu = Qu::new();
+	/// qu.set_indentation(Indentation::Spaces(4));
+	/// let script = qu.compile("
+	/// fn area(width int, height int) int:
+	///     return width * height
+	/// return area(3, 4)
+	/// ")?;
+	/// let bytes = qu.save_script(&script)?;
+	/// 
+	/// // The bytes would usually be written to and read from a file
+	/// let mut game = Qu::new();
+	/// let script = game.load_script(&bytes)?;
+	/// game.run_script(&script)?;
+	/// let result:Int = *game.get_result()?;
+	/// assert_eq!(result, 12);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn save_script(&self, script:&Script) -> Result<Vec<u8>, QuMsg> {
+		if script.uuid != *self.get_uuid() {
+			return Err(QuMsg::general(
+				"Can't save a script compiled by another Qu instance."
+			));
+		}
+		let Some(registered) = &self.registered else {
+			return Err(QuMsg::general("Can't save a script before compiling it."));
+		};
+		bytecode::save(
+			&self.vm.definitions,
+			registered,
+			script.code_block,
+			script.module_id,
+		)
+	}
+
+
+	/// Loads a script saved by [`Qu::save_script`].
+	/// 
+	/// Loading replaces everything this [`Qu`] instance has compiled or
+	/// loaded before, so scripts compiled before can't be run afterwards.
+	/// The registered functions, classes, modules and constants that the
+	/// script uses are bound to the ones registered in this instance, by
+	/// name. Everything registered in this instance is kept, whether the
+	/// file uses it or not.
+	/// 
+	/// # Errors
+	/// 
+	/// If `bytes` aren't a `.quc` file of [`QUC_VERSION`], are corrupted,
+	/// refer to items or registers they don't have, or the script needs a
+	/// registered item that isn't registered, or was registered with another
+	/// signature, then an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Qu;
+	/// use qu::Int;
+	/// use qu::QuErrorKind;
+	/// use qu::RegistererLayer;
+	/// 
+	/// fn register_game(qu:&mut Qu) -> Result<(), QuMsg> {
+	///     qu.register(&|r| {
+	///         r.add_module("game", &|m| {
+	///             let int = m.get_class_id_of::<Int>().unwrap();
+	///             m.add_function("level", [], int, &|api| api.set::<Int>(3))
+	///         })?;
+	///         Ok(())
+	///     })
+	/// }
+	/// 
+	/// let mut qu = Qu::new();
+	/// register_game(&mut qu)?;
+	/// let script = qu.compile("
+	/// import game.level
+	/// return level() * 10
+	/// ")?;
+	/// let bytes = qu.save_script(&script)?;
+	/// 
+	/// let mut game = Qu::new();
+	/// let error = game.load_script(&bytes).unwrap_err();
+	/// assert_eq!(error.kind, QuErrorKind::Registration);
+	/// 
+	/// register_game(&mut game)?;
+	/// let script = game.load_script(&bytes)?;
+	/// game.run_script(&script)?;
+	/// let result:Int = *game.get_result()?;
+	/// assert_eq!(result, 30);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn load_script(&mut self, bytes:&[u8]) -> Result<Script, QuMsg> {
+		let registered = self.registered
+			.get_or_insert_with(|| self.vm.definitions.clone());
+		let (definitions, code_block, module_id) = bytecode::load(
+			bytes,
+			registered,
+		)?;
+		self.vm.replace_definitions(definitions);
+		Ok(Script {
+			uuid: *self.get_uuid(),
+			code_block,
+			module_id,
+			diagnostics: vec![],
+		})
 	}
 
 
@@ -239,30 +543,346 @@ pub struct Qu<'a> {
 	/// # return Ok(());
 	/// # }
 	/// ```
+	/// 
+	/// Instances of classes declared in the script can be read as a
+	/// `#[repr(C)]` struct with the same name, which declares its fields with
+	/// [`Register::fields`].
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use std::mem::offset_of;
+	/// use qu::ClassId;
+	/// use qu::Int;
+	/// use qu::Qu;
+	/// use qu::Register;
+	/// 
+	/// #[repr(C)]
+	/// struct Point {x: Int, y: Int}
+	/// impl Register for Point {
+	///     fn fields() -> Option<Vec<(ClassId, usize)>> {
+	///         Some(vec![
+	///             (Int::id(), offset_of!(Point, x)),
+	///             (Int::id(), offset_of!(Point, y)),
+	///         ])
+	///     }
+	/// }
+	/// 
+	/// let mut qu = Qu::new();
+	/// 
+	/// let point:&Point = qu.run_and_get("
+	/// 	class Point:
+	/// 		var x int
+	/// 		var y int
+	/// 
+	/// 		fn init(x int, y int):
+	/// 			self.x = x
+	/// 			self.y = y
+	/// 
+	/// 	return Point(3, 4)
+	/// ")?;
+	/// assert_eq!((point.x, point.y), (3, 4));
+	/// # return Ok(());
+	/// # }
+	/// ```
 	pub fn run_and_get<T: Register + 'static> (
 		&mut self,
 		script:&str,
 	) -> Result<&T, QuMsg> {
 		self.run(script)?;
+		self.get_result()
+	}
+
+
+	/// Gets the value returned by the last run script, such as one run by
+	/// [`Qu::run_script`]. The value can only be gotten once per run.
+	/// 
+	/// # Errors
+	/// 
+	/// If the script didn't return a value of the requested type, or it
+	/// returned an instance of a script class whose fields don't match the
+	/// ones declared by `T`, then an [`Err`] is returned.
+	pub fn get_result<T: Register + 'static>(&mut self) -> Result<&T, QuMsg> {
 		let return_id = self.vm.return_value_id();
 
-		if T::id() != return_id {
-			return Err(format!(
+		// Instances of classes declared in scripts can be read as Rust
+		// structs with the same name, size and fields
+		let return_class = self.vm.definitions.get_class(return_id)?;
+		let is_script_class = return_class.from_script
+			&& return_class.common.name == T::name();
+		let fields:Vec<(ClassId, usize)> = return_class.fields.iter()
+			.map(|field| (field.class_id, field.offset))
+			.collect();
+		if
+			is_script_class
+			&& (return_class.size != T::size() || T::fields() != Some(fields))
+		{
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"The fields of the returned class, {}, don't match the fields declared by {}.",
+				return_class.common.name,
+				type_name::<T>(),
+			)));
+		}
+		// Values of every function type are callables
+		let is_callable = TypeId::of::<T>() == TypeId::of::<Callable>()
+			&& return_class.function_type.is_some();
+
+		if T::id() != return_id && !is_script_class && !is_callable {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
 				"The returned value's type, {}, does not match the requested value's type, {}",
 				self.vm.definitions.get_class(return_id)?.common.name,
 				type_name::<T>(),
-			).into())
+			)))
 		}
 
 		self.vm.read::<T>(0.into())
 	}
 
+
+	/// Run a [`&str`] as Qu script and get the tuple it returns as a Rust
+	/// tuple.
+	/// 
+	/// # Errors
+	/// 
+	/// If `code` contains improper syntax, a problem occurs at runtime, or
+	/// the script doesn't return a tuple with elements of the requested
+	/// types then an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Qu;
+	/// use qu::Int;
+	/// use qu::Float;
+	/// 
+	/// let mut qu = Qu::new();
+	/// 
+	/// let (count, ratio) = qu.run_and_get_tuple::<(Int, Float)>(
+	///     "return 5, 0.5",
+	/// )?;
+	/// assert_eq!(count, 5);
+	/// assert_eq!(ratio, 0.5);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn run_and_get_tuple<T: QuTuple>(
+		&mut self,
+		script:&str,
+	) -> Result<T, QuMsg> {
+		self.run(script)?;
+		let return_id = self.vm.return_value_id();
+
+		let return_class = self.vm.definitions.get_class(return_id)?;
+		let element_ids: Vec<ClassId> = return_class.fields
+			.iter()
+			.map(|field| field.class_id)
+			.collect();
+		if !return_class.is_tuple || element_ids != T::class_ids() {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"The returned value's type, {}, does not match the requested value's type, {}",
+				return_class.common.name,
+				type_name::<T>(),
+			)))
+		}
+
+		T::read(&self.vm, 0.into())
+	}
+
+
+	/// Calls a function value that a script gave to Rust, such as a
+	/// callback passed to a registered function, and returns its result.
+	/// 
+	/// `args` is a tuple of the arguments, and `()` passes none. Functions
+	/// that return nothing return a [`Void`].
+	/// 
+	/// # Errors
+	/// 
+	/// If the arguments or `R` don't match the function's signature,
+	/// `callable` was made by another [`Qu`] instance, or a problem occurs at
+	/// runtime, then an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Callable;
+	/// use qu::Indentation;
+	/// use qu::Int;
+	/// use qu::Qu;
+	/// 
+	/// let mut qu = Qu::new();
+	/// qu.set_indentation(Indentation::Spaces(4));
+	/// 
+	/// let add:Callable = qu.run_and_get::<Callable>("
+	///     fn adder(step int) fn(int) int:
+	///         return fn(x int) int:
+	///             return x + step
+	///     return adder(2)
+	/// ")?.clone();
+	/// let result:Int = qu.invoke(&add, (5,))?;
+	/// assert_eq!(result, 7);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn invoke<A: QuTuple, R: Register + Clone>(
+		&mut self,
+		callable:&Callable,
+		args:A,
+	) -> Result<R, QuMsg> {
+		if callable.uuid != *self.get_uuid() {
+			return Err(QuMsg::general(
+				"Can't invoke a callable made by another Qu instance."
+			));
+		}
+		let d = &self.vm.definitions;
+		let identity = &d.get_function(callable.fn_id)?.identity;
+		// Captured values are passed after the arguments
+		let parameter_count = identity.parameters.len() - callable.captures.len();
+		let parameters = &identity.parameters[..parameter_count];
+		if *parameters != A::class_ids() || identity.return_type != R::id() {
+			return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+				"Function '{}' can't be called with arguments {} to return {}.",
+				identity.display_pretty(d),
+				type_name::<A>(),
+				type_name::<R>(),
+			)))
+		}
+		let mut args_size = 0;
+		for class_id in parameters {
+			args_size += d.get_class(*class_id)?.size as usize;
+		}
+
+		// The arguments are written at the bottom of the stack, which is free
+		// between runs
+		let arg_regs = args.write(&mut self.vm, 0.into());
+		let output = RegId::from(args_size);
+		self.vm.call_callable(callable.clone(), &arg_regs, output)?;
+		Ok(self.vm.read::<R>(output)?.clone())
+	}
+
+
+	/// Calls a function declared by a previous compilation and returns its
+	/// result, without compiling anything.
+	/// 
+	/// The function is picked by its name and the classes of `args`, like a
+	/// call in a script would. Functions of registered modules are named with
+	/// their module (Ex: "my_module.my_function"). `args` is a tuple of the
+	/// arguments, and `()` passes none.
+	/// 
+	/// # Errors
+	/// 
+	/// If no function takes the arguments, `R` isn't the function's return
+	/// type, or a problem occurs at runtime, then an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Float;
+	/// use qu::Indentation;
+	/// use qu::Int;
+	/// use qu::Qu;
+	/// 
+	/// let mut qu = Qu::new();
+	/// qu.set_indentation(Indentation::Spaces(4));
+	/// 
+	/// qu.run("
+	///     var frames int = 0
+	///     fn on_update(delta float) int:
+	///         frames = frames + 1
+	///         return frames
+	/// ")?;
+	/// qu.call::<(Float,), Int>("on_update", (0.016,))?;
+	/// let frames = qu.call::<(Float,), Int>("on_update", (0.016,))?;
+	/// assert_eq!(frames, 2);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn call<A: QuTuple, R: Register + Clone>(
+		&mut self,
+		name:&str,
+		args:A,
+	) -> Result<R, QuMsg> {
+		let d = &self.vm.definitions;
+		let (module_name, fn_name) = name.rsplit_once('.')
+			.unwrap_or((MAIN_MODULE, name));
+		let group_id = d.get_module_by_name(module_name)?
+			.common
+			.get_function_group_id(fn_name)
+			.map_err(|_| QuMsg::undefined_fn_access(name))?;
+		let identity = FunctionIdentity {
+			name: fn_name.into(),
+			parameters: A::class_ids().into(),
+			..Default::default()
+		};
+		let fn_id = d.get_function_group(group_id)?.get_fn_id(&identity, d)?;
+
+		let callable = Callable {
+			fn_id,
+			uuid: *self.get_uuid(),
+			captures: Vec::new().into(),
+		};
+		return self.invoke(&callable, args);
+	}
+
+
+	/// Run a [`&str`] as Qu script and get a readable form of the result.
+	/// 
+	/// The result is written as its value followed by the name of its class.
+	/// Returns [`None`] if the script did not return a value.
+	/// 
+	/// # Errors
+	/// 
+	/// If `code` contains improper syntax or a problem occurs at runtime
+	/// then an [`Err`] is returned.
+	/// 
+	/// # Example
+	/// 
+	/// ```
+	/// # use qu::QuMsg;
+	/// # fn main(){example().unwrap()}
+	/// # fn example() -> Result<(), QuMsg> {
+	/// use qu::Qu;
+	/// 
+	/// let mut qu = Qu::new();
+	/// 
+	/// let text = qu.run_and_display("return 5 + 6")?;
+	/// assert_eq!(text, Some("11: int".to_owned()));
+	/// assert_eq!(qu.run_and_display("var x int = 2")?, None);
+	/// # return Ok(());
+	/// # }
+	/// ```
+	pub fn run_and_display(
+		&mut self,
+		script:&str,
+	) -> Result<Option<String>, QuMsg> {
+		self.run(script)?;
+		let Some(return_id) = self.vm.return_value_id_maybe() else {
+			return Ok(None);
+		};
+
+		let value = self.vm.display_value(0.into(), return_id)?;
+		let class_name = &self.vm.definitions.get_class(return_id)?.common.name;
+		Ok(Some(format!("{value}: {class_name}")))
+	}
+
 }
 
 
 #[cfg(test)]
 mod lib {
-    use crate::{Qu, Module, Float, RegistererLayer, Register, Int, Bool};
+    use crate::{ClassId, Qu, QuErrorKind, QuParser, QuSeverity, Module, Float, RegistererLayer, Register, Int, Bool, Str, List, Map, Callable, Void};
+	use crate::parser::parsed::Statement;
+	use std::mem::offset_of;
+	use std::rc::Rc;
 
 	// TODO: Test what happens when a function overrides a class name
 
@@ -339,7 +959,7 @@ mod lib {
 					[],
 					cls,
 					&|api| {
-						api.set::<TestClass>(TestClass{});
+						api.set::<TestClass>(TestClass{})?;
 						Ok(())
 					}
 				)?;
@@ -351,7 +971,7 @@ mod lib {
 					[cls],
 					int,
 					&|api| {
-						api.set::<Int>(1);
+						api.set::<Int>(1)?;
 						Ok(())
 					}
 				)?;
@@ -363,7 +983,7 @@ mod lib {
 					[cls],
 					int,
 					&|api| {
-						api.set::<Int>(2);
+						api.set::<Int>(2)?;
 						Ok(())
 					}
 				)?;
@@ -567,10 +1187,27 @@ mod lib {
 
 
 	#[test]
-	fn scoping1(){
+	fn endless_recursion() {
+		let mut qu = Qu::new();
 		let script = r#"
-			var l int = 1
-			var r int = 2
+			fn forever(val int) int:
+				return forever(val + 1)
+
+			return forever(1)
+		"#;
+		assert_eq!(qu.run(script).unwrap_err().kind, QuErrorKind::Runtime);
+
+		// Reaching the limit doesn't stop later calls from running
+		let value:i32 = *qu.run_and_get("return 5").unwrap();
+		assert_eq!(value, 5);
+	}
+
+
+	#[test]
+	fn scoping1(){
+		let script = r#"
+			var l int = 1
+			var r int = 2
 
 			fn adder(a int, b int) int:
 				return a + b
@@ -660,6 +1297,262 @@ mod lib {
 	}
 
 
+	#[test]
+	fn if_elif_else() {
+		let mut qu = Qu::new();
+		let script = r#"
+			fn classify(n int) int:
+				if n < 0:
+					return 0
+				elif n == 0:
+					return 1
+				elif n < 10:
+					return 2
+				else:
+					return 3
+
+			return classify(0-5)*1000 + classify(0)*100 + classify(5)*10 + classify(50)
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 123);
+	}
+
+
+	#[test]
+	fn if_elif_without_else() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var a int = 0
+			var b int = 0
+			var n int = 7
+			while n > 0:
+				if n == 7:
+					a = a + 1
+				elif n == 3:
+					b = b + 1
+				n = n - 1
+			if a == 5:
+				a = 100
+			else:
+				a = a + 10
+			return a*10 + b
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 111);
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn else_without_if_panic() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var a int = 0
+			else:
+				a = 1
+		"#;
+
+		qu.run(script).unwrap();
+	}
+
+
+	#[test]
+	fn logic_and_or_not() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var alive bool = true
+			var stunned bool = false
+			var count int = 0
+			if alive and not stunned:
+				count = count + 1
+			if alive and stunned:
+				count = count + 10
+			if stunned or alive:
+				count = count + 100
+			if not alive or stunned:
+				count = count + 1000
+			if not (alive and stunned) and not not alive:
+				count = count + 10000
+			stunned = alive and not stunned
+			if stunned:
+				count = count + 100000
+			return count
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 110101);
+	}
+
+
+	#[test]
+	fn logic_short_circuit() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var calls int = 0
+			fn touch(value bool) bool:
+				calls = calls + 1
+				return value
+
+			var a bool = false and touch(true)
+			var b bool = true or touch(true)
+			var c bool = true and touch(false)
+			var d bool = false or touch(true)
+			if a or b and c or not d:
+				calls = calls + 100
+			return calls
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 2);
+	}
+
+
+	#[test]
+	fn logic_bool_variable_condition() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var running bool = true
+			var count int = 0
+			while running:
+				count = count + 1
+				running = count < 5
+			return count
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 5);
+	}
+
+
+	#[test]
+	fn unary_minus() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var a int = -5
+			var b int = 3 * -a
+			var c float = -2.5
+			if -c > 2.0:
+				b = b + 1
+			return b - -a
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 11);
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn logic_non_bool_panic() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var a bool = 1 and true
+		"#;
+
+		qu.run(script).unwrap();
+	}
+
+
+	#[test]
+	fn comparison_or_equal() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var count int = 0
+			if 3 <= 3:
+				count = count + 1
+			if 4 <= 3:
+				count = count + 10
+			if 3 >= 3:
+				count = count + 100
+			if 2.5 >= 3.0:
+				count = count + 1000
+			if 2.5 <= 3.0:
+				count = count + 10000
+			return count
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 10101);
+	}
+
+
+	#[test]
+	fn modulo_power_root() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var a int = 17 % 5
+			var b int = 2 ** 10
+			var c int = 2 ** -1
+			var d int = 1000 // 3
+			var e int = -27 // 3
+			var f int = 15 // 2
+			return a + b + c + d + e + f
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 2 + 1024 + 0 + 10 - 3 + 3);
+
+		let script = r#"
+			return 7.5 % 2.0 + 9.0 ** 0.5 + 8.0 // 3.0
+		"#;
+
+		let res:f32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 1.5 + 3.0 + 2.0);
+
+		// Int operations without a result are runtime errors
+		for script in [
+			"return 0 ** -1",
+			"return 10 ** 20",
+			"return 5 % 0",
+			"return 8 // 0",
+		] {
+			let err = qu.run(script).unwrap_err();
+			assert_eq!(err.kind, QuErrorKind::Runtime, "{}", script);
+		}
+		let res:i32 = *qu.run_and_get("return 10 ** -20").unwrap();
+		assert_eq!(res, 0);
+	}
+
+
+	#[test]
+	fn operator_precedence() {
+		let mut qu = Qu::new();
+		let script = r#"
+			return 2 * 3 ** 2 + 10 % 4 * 2
+		"#;
+
+		let res:i32 = *qu.run_and_get(script).unwrap();
+		assert_eq!(res, 2 * 9 + 2 * 2);
+
+		// Multiplication, division and modulo go from left to right
+		let res:i32 = *qu.run_and_get("return 7 * 3 % 2").unwrap();
+		assert_eq!(res, 1);
+		let res:i32 = *qu.run_and_get("return 8 / 2 * 4 + 20 % 6 / 2").unwrap();
+		assert_eq!(res, 16 + 1);
+
+		let script = r#"
+			return 1 + 2 <= 3 and 4 >= 2 * 2
+		"#;
+
+		let res:bool = *qu.run_and_get(script).unwrap();
+		assert!(res);
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn root_of_negative_panic() {
+		let mut qu = Qu::new();
+		let script = r#"
+			var a int = -4 // 2
+		"#;
+
+		qu.run(script).unwrap();
+	}
+
+
 	#[test]
 	fn static_typing_return_from_run() {
 		let mut qu = Qu::new();
@@ -856,125 +1749,2109 @@ mod lib {
 		").unwrap();
 		assert_eq!(num, 2*3);
 
-		// TODO: Allow accessing global variables across invocations of 'run'.
 		// Test cross accessing variables
-		// let mut qu = Qu::new();
-		// qu.run("
-		// 	var first int = 5
-		// 	var second int = 20
-		// ").unwrap();
-		// let num = *qu.run_and_get::<i32>("
-		// 	return first * second
-		// ").unwrap();
-		// assert_eq!(num, 5*20);
+		let mut qu = Qu::new();
+		qu.run("
+			var first int = 5
+			var second int = 20
+		").unwrap();
+		let num = *qu.run_and_get::<i32>("
+			return first * second
+		").unwrap();
+		assert_eq!(num, 5*20);
+
+		// Test redeclaring variables
+		qu.run("
+			var first float = 1.5
+		").unwrap();
+		let num = *qu.run_and_get::<f32>("
+			return first
+		").unwrap();
+		assert_eq!(num, 1.5);
 	}
 
 
-	// TODO: Prevent functions definitions from having multiple parameters of
-	// 	the same name 
-	// #[test]
-	// #[should_panic]
-	fn _function_multiple_parameters_same_name() {
+	#[test]
+	fn global_variables() {
 		let mut qu = Qu::new();
-		let script = r#"
-			fn some(a int, a int, c int) int:
-				return a + c
-		"#;
-		qu.run(script).unwrap();
+		qu.run("
+			var counter int = 0
+			fn increment(by int):
+				counter = counter + by
+		").unwrap();
+		qu.run("
+			increment(2)
+			increment(3)
+		").unwrap();
+		let num = *qu.run_and_get::<i32>("
+			return counter
+		").unwrap();
+		assert_eq!(num, 5);
 	}
 
 
 	#[test]
-	fn import() {
+	fn global_variables_failed_compile() {
+		// Variables whose value fails to compile aren't defined
 		let mut qu = Qu::new();
-		let result:i32 = *qu.run_and_get("
-			import math
-			return math.foo(3)
+		assert!(qu.run("var a int = nope").is_err());
+		assert!(qu.compile("return a").is_err());
+		let num = *qu.run_and_get::<Int>("
+			var a int = 4
+			return a
 		").unwrap();
-		assert_eq!(result, 3);
+		assert_eq!(num, 4);
+		assert!(qu.run("var (b, b) = 1, 2").is_err());
+		assert!(qu.compile("return b").is_err());
+
+		// Variables of a script that never ran have no value yet
+		qu.compile(r#"var text str = "text""#).unwrap();
+		let msg = qu.run("return text").unwrap_err();
+		assert_eq!(msg.kind, QuErrorKind::Runtime);
+		assert!(qu.run("var c int = 1\nvar d int = nope").is_err());
+		assert_eq!(qu.run("return c").unwrap_err().kind, QuErrorKind::Runtime);
 	}
 
 
 	#[test]
-	fn import_2() {
+	fn functions_failed_compile() {
+		// Functions whose body fails to compile can't be called by later
+		// scripts, and can be declared again
 		let mut qu = Qu::new();
-		let result:i32 = *qu.run_and_get("
-			import math.foo
-			return foo(3)
+		assert!(qu.run("fn g() int:\n\treturn nope").is_err());
+		let errors = qu.compile("return g()").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::UndefinedFunction);
+		let num = *qu.run_and_get::<Int>("
+			fn g() int:
+				return 7
+			return g()
 		").unwrap();
-		assert_eq!(result, 3);
+		assert_eq!(num, 7);
+
+		// Functions of other groups are kept
+		assert!(qu.run("fn g(a int) int:\n\treturn nope").is_err());
+		assert_eq!(*qu.run_and_get::<Int>("return g()").unwrap(), 7);
 	}
 
 
 	#[test]
-	fn import_3() {
+	fn classes_failed_compile() {
+		// Classes whose body fails to compile can be fixed and declared again
 		let mut qu = Qu::new();
-		let result:i32 = *qu.run_and_get("
-			import math
-			import math.foo
-			return math.foo(3) + foo(3)
+		assert!(qu.run("
+			class P:
+				var x int
+
+				fn get() int:
+					return nope
+		").is_err());
+		let num = *qu.run_and_get::<Int>("
+			class P:
+				var x int
+
+				fn get() int:
+					return self.x
+			return P().get()
 		").unwrap();
-		assert_eq!(result, 3+3);
+		assert_eq!(num, 0);
+
+		// Declarations without names say so
+		for script in ["class:\n\tvar x int", "trait:\n\tfn f()", "fn 1(a int):\n\treturn"] {
+			let errors = qu.compile(script).unwrap_err();
+			assert_eq!(errors[0].kind, QuErrorKind::MissingName, "{script}");
+		}
+		let errors = qu.compile("class Q:\n\timpl:\n\t\tfn f()").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::MissingName);
+		let errors = qu.compile("fn f int:\n\treturn 1").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::MissingToken);
 	}
 
 
 	#[test]
 	#[should_panic]
-	fn not_imported_panic() {
+	fn global_variables_nested_scope_panic() {
+		// Variables in nested scopes are not global
 		let mut qu = Qu::new();
-		let result:i32 = *qu.run_and_get("
-			return math.foo(1)
+		qu.run("
+			if true:
+				var inner int = 1
 		").unwrap();
-		dbg!(result);
+		qu.run("return inner").unwrap();
 	}
 
+
 	#[test]
-	#[should_panic]
-	/// Panic if an item from another module is accessed without importing that
-	/// item.
-	fn not_imported_panic_2() {
+	fn strings() {
 		let mut qu = Qu::new();
-		let result = qu.run("
-			return foo(3)
-		").unwrap();
-		dbg!(result);
+
+		let value = qu.run_and_get::<Str>(r#"
+			return "Hello" + ', ' + "world!"
+		"#).unwrap();
+		assert_eq!(value, "Hello, world!");
+
+		// Escape sequences
+		let value = qu.run_and_get::<Str>(r#"
+			return "tab\tquote\"single\'slash\\line\n"
+		"#).unwrap();
+		assert_eq!(value, "tab\tquote\"single'slash\\line\n");
+
+		// Comparisons
+		let value = *qu.run_and_get::<Bool>(r#"
+			var name str = "qu"
+			return name == "q" + "u"
+		"#).unwrap();
+		assert_eq!(value, true);
+		let value = *qu.run_and_get::<Bool>(r#"
+			return "qu" != "qu"
+		"#).unwrap();
+		assert_eq!(value, false);
+
+		// Length is counted in characters
+		let value = *qu.run_and_get::<Int>(r#"
+			return len("héllo") + "abc".len()
+		"#).unwrap();
+		assert_eq!(value, 8);
+
+		// Converting other values
+		let value = qu.run_and_get::<Str>(r#"
+			return str(5) + " " + str(2.5) + " " + str(true)
+		"#).unwrap();
+		assert_eq!(value, "5 2.5 true");
 	}
 
 
 	#[test]
-	#[should_panic]
-	/// Panic if a specific function is imported, but not the module, then that
-	/// function accessed through the module.
-	fn not_imported_panic_3() {
+	fn operator_operand_types() {
+		// Operators only take the operands their implementation was written for
 		let mut qu = Qu::new();
-		let result:i32 = *qu.run_and_get("
-			import math.foo
-			return math.foo(1)
-		").unwrap();
-		dbg!(result);
+		for script in [
+			"return \"a\" + 1",
+			"return 1 + \"a\"",
+			"return 1 + 1.5",
+			"var s str = \"a\"\nvar n int = 123456789\nreturn s + n",
+		] {
+			let errors = qu.compile(script).unwrap_err();
+			assert_eq!(errors[0].kind, QuErrorKind::TypeMismatch, "{script}");
+		}
+		let value = *qu.run_and_get::<Float>("return 1.0 + 1.5").unwrap();
+		assert_eq!(value, 2.5);
 	}
 
 
 	#[test]
-	/// Return an imported module.
-	fn return_module() {
+	fn strings_in_loops() {
 		let mut qu = Qu::new();
-		let result:&Module = qu.run_and_get("
-			import math
-			return math
-		").unwrap();
-		dbg!(result);
+		let value = qu.run_and_get::<Str>(r#"
+			var text str = ""
+			var i int = 0
+			while i < 3:
+				text = text + str(i)
+				i = i + 1
+			return text
+		"#).unwrap().clone();
+		assert_eq!(value, "012");
+
+		// Values read from Rust stay valid after the script is gone
+		drop(qu);
+		assert_eq!(value.as_str(), "012");
+	}
+
+
+	#[test]
+	fn strings_dropped() {
+		let mut qu = Qu::new();
+		qu.run(r#"
+			fn keep(text str) int:
+				var a str = text
+				var b str = a + "!"
+				return b.len()
+		"#).unwrap();
+
+		// Values made by a call are dropped when it returns, and values
+		// passed by Rust are dropped when they're replaced
+		let text = Str::from("text");
+		for _ in 0..10 {
+			qu.call::<(Str,), Int>("keep", (text.clone(),)).unwrap();
+		}
+		assert_eq!(Rc::strong_count(&text.0), 2);
+		qu.run("var x int = 0").unwrap();
+		assert_eq!(Rc::strong_count(&text.0), 1);
+
+		// Copies outlive the values they were copied from
+		let value = qu.run_and_get::<Str>(r#"
+			class Named:
+				var name str = "a" + "b"
+			var first str = "c" + "d"
+			var second str = first
+			first = "e" + "f"
+			var named Named = Named()
+			var other Named = named
+			named.name = "g" + "h"
+			return second + other.name + named.name + first
+		"#).unwrap();
+		assert_eq!(value, "cdabghef");
+
+		// String literals are dropped with the constants that hold them
+		let mut qu = Qu::new();
+		let literal = qu.run_and_get::<Str>(r#"return "literal""#).unwrap().clone();
+		drop(qu);
+		assert_eq!(Rc::strong_count(&literal.0), 1);
 	}
 
 
 	#[test]
 	#[should_panic]
-	/// Panic when a module is referenced without being imported.
-	fn return_module_panic() {
+	fn strings_unclosed_panic() {
 		let mut qu = Qu::new();
-		let result:&Module = qu.run_and_get("
-			return math
-		").unwrap();
-		dbg!(result);
+		qu.run(r#"
+			var text str = "no end
+		"#).unwrap();
 	}
-}
\ No newline at end of file
+
+
+	#[test]
+	#[should_panic]
+	fn strings_invalid_escape_panic() {
+		let mut qu = Qu::new();
+		qu.run(r#"
+			var text str = "\q"
+		"#).unwrap();
+	}
+
+
+	#[test]
+	fn trait_typed_values() {
+		let mut qu = Qu::new();
+		qu.run("
+			var number Add = 5
+			var decimal Add = 2.5
+			fn double(value Add) Add:
+				return value + value
+		").unwrap();
+
+		let value = qu.run_and_display("return number + 4").unwrap();
+		assert_eq!(value, Some("9: Add".into()));
+		let value = qu.run_and_display("return double(decimal)").unwrap();
+		assert_eq!(value, Some("5.0: Add".into()));
+		let value = qu.run_and_display("return double(double(3))").unwrap();
+		assert_eq!(value, Some("12: Add".into()));
+
+		let value = *qu.run_and_get::<Bool>("return number is int").unwrap();
+		assert_eq!(value, true);
+		let value = *qu.run_and_get::<Bool>("return decimal is int").unwrap();
+		assert_eq!(value, false);
+
+		// Values of other classes can be assigned
+		qu.run("number = 1.5").unwrap();
+		let value = qu.run_and_display("return number + 1.0").unwrap();
+		assert_eq!(value, Some("2.5: Add".into()));
+	}
+
+
+	#[test]
+	fn trait_typed_values_comparison() {
+		let mut qu = Qu::new();
+		qu.run(r#"
+			var text Equal = "qu"
+			var number NotEqual = 3
+		"#).unwrap();
+
+		let value = *qu.run_and_get::<Bool>(r#"return text == "qu""#).unwrap();
+		assert_eq!(value, true);
+		let value = *qu.run_and_get::<Bool>("return number != 3").unwrap();
+		assert_eq!(value, false);
+		let value = *qu.run_and_get::<Bool>("return number != 4").unwrap();
+		assert_eq!(value, true);
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn trait_typed_values_mismatched_class_panic() {
+		let mut qu = Qu::new();
+		qu.run("
+			var number Add = 5
+			var other Add = number + 2.5
+		").unwrap();
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn trait_typed_values_not_implemented_panic() {
+		let mut qu = Qu::new();
+		qu.run(r#"
+			var text Sub = "abc"
+		"#).unwrap();
+	}
+
+
+	#[test]
+	fn classes() {
+		let mut qu = Qu::new();
+		qu.run(r#"
+			class Point:
+				var x int = 1
+				var y int
+				var name str = "point"
+
+				fn init(x int, y int):
+					self.x = x
+					self.y = y
+
+				fn init():
+					self.x = 100
+					self.y = 100
+
+				fn sum() int:
+					return self.x + self.y
+
+				fn scaled(by int) Point:
+					return Point(self.x * by, self.y * by)
+
+			var a Point = Point(2, 3)
+			var b Point = Point()
+		"#).unwrap();
+
+		let value = qu.run_and_display("return a").unwrap();
+		assert_eq!(value, Some(r#"(x: 2, y: 3, name: "point"): Point"#.into()));
+		let value = *qu.run_and_get::<Int>("return b.sum()").unwrap();
+		assert_eq!(value, 200);
+		let value = *qu.run_and_get::<Int>("return a.scaled(3).sum()").unwrap();
+		assert_eq!(value, 15);
+
+		// Fields can be assigned
+		qu.run("a.x = a.x + 8").unwrap();
+		let value = *qu.run_and_get::<Int>("return a.x").unwrap();
+		assert_eq!(value, 10);
+		let value = *qu.run_and_get::<Int>("
+			fn swapped(point Point) Point:
+				var y int = point.y
+				point.y = point.x
+				point.x = y
+				return point
+			return swapped(a).x
+		").unwrap();
+		assert_eq!(value, 3);
+	}
+
+
+	#[test]
+	fn classes_default_constructor() {
+		let mut qu = Qu::new();
+		let value = qu.run_and_display("
+			class Counter:
+				var count int
+				var step int = 2
+
+				fn next() int:
+					return self.count + self.step
+
+			var counter Counter
+			return counter.next()
+		").unwrap();
+		assert_eq!(value, Some("2: int".into()));
+	}
+
+
+	#[test]
+	fn classes_nested_fields() {
+		let mut qu = Qu::new();
+		let value = *qu.run_and_get::<Float>("
+			class Size:
+				var width float
+				var height float
+
+				fn init(width float, height float):
+					self.width = width
+					self.height = height
+
+			class Rect:
+				var x float
+				var size Size = Size(2.0, 3.0)
+
+			var rect Rect = Rect()
+			rect.size.height = 4.0
+			return rect.size.width * rect.size.height
+		").unwrap();
+		assert_eq!(value, 8.0);
+	}
+
+
+	#[test]
+	fn classes_mutating_methods() {
+		// Changes a method makes to `self` are kept by the caller's value
+		let mut qu = Qu::new();
+		qu.run("
+			class C:
+				var n int
+
+				fn inc():
+					self.n = self.n + 1
+
+				fn inc_twice() int:
+					self.inc()
+					self.inc()
+					return self.n
+
+			fn local() int:
+				var c C = C()
+				c.inc()
+				c.inc()
+				return c.n
+
+			var top C = C()
+			top.inc()
+			top.inc()
+		").unwrap();
+		assert_eq!(*qu.run_and_get::<Int>("return local()").unwrap(), 2);
+		assert_eq!(*qu.run_and_get::<Int>("return top.n").unwrap(), 2);
+		assert_eq!(*qu.run_and_get::<Int>("return top.inc_twice()").unwrap(), 4);
+		assert_eq!(*qu.run_and_get::<Int>("return top.n").unwrap(), 4);
+	}
+
+
+	#[test]
+	fn classes_get_from_rust() {
+		#[repr(C)]
+		struct Player {
+			health: Int,
+			speed: Float,
+			alive: Bool,
+		}
+		impl Register for Player {
+			fn fields() -> Option<Vec<(ClassId, usize)>> {
+				Some(vec![
+					(Int::id(), offset_of!(Player, health)),
+					(Float::id(), offset_of!(Player, speed)),
+					(Bool::id(), offset_of!(Player, alive)),
+				])
+			}
+		}
+
+		let mut qu = Qu::new();
+		let player:&Player = qu.run_and_get("
+			class Player:
+				var health int = 10
+				var speed float = 1.5
+				var alive bool = true
+
+			var player Player = Player()
+			player.health = 7
+			return player
+		").unwrap();
+		assert_eq!(player.health, 7);
+		assert_eq!(player.speed, 1.5);
+		assert_eq!(player.alive, true);
+
+		// Structs whose fields don't match can't be read
+		#[repr(C)]
+		struct Named {
+			name: Str,
+		}
+		impl Register for Named {
+			fn fields() -> Option<Vec<(ClassId, usize)>> {
+				Some(vec![(Str::id(), offset_of!(Named, name))])
+			}
+		}
+		let msg = qu.run_and_get::<Named>("
+			class Named:
+				var first int = 1
+				var second int = 2
+				var third int = 3
+				var fourth int = 4
+			return Named()
+		").err().unwrap();
+		assert_eq!(msg.kind, QuErrorKind::TypeMismatch);
+
+		#[repr(C)]
+		struct Unlisted {
+			first: Int,
+		}
+		impl Register for Unlisted {}
+		let msg = qu.run_and_get::<Unlisted>("
+			class Unlisted:
+				var first int
+			return Unlisted()
+		").err().unwrap();
+		assert_eq!(msg.kind, QuErrorKind::TypeMismatch);
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn classes_unknown_field_panic() {
+		let mut qu = Qu::new();
+		qu.run("
+			class Point:
+				var x int
+
+			var point Point = Point()
+			point.z = 5
+		").unwrap();
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn classes_no_constructor_panic() {
+		let mut qu = Qu::new();
+		qu.run("
+			class Point:
+				var x int
+
+				fn init(x int):
+					self.x = x
+
+			var point Point = Point(1, 2)
+		").unwrap();
+	}
+
+
+	#[test]
+	fn traits() {
+		let mut qu = Qu::new();
+		qu.run("
+			trait Damageable:
+				fn take_damage(amount int) int
+				fn is_alive() bool
+
+			class Player:
+				var health int
+
+				fn init(health int):
+					self.health = health
+
+				impl Damageable:
+					fn take_damage(amount int) int:
+						return self.health - amount
+
+					fn is_alive() bool:
+						return self.health > 0
+
+			fn hit(target Damageable) int:
+				return target.take_damage(5)
+
+			var target Damageable = Player(10)
+		").unwrap();
+
+		let value = *qu.run_and_get::<Int>("return target.take_damage(3)").unwrap();
+		assert_eq!(value, 7);
+		let value = *qu.run_and_get::<Int>("return Player(10).take_damage(4)").unwrap();
+		assert_eq!(value, 6);
+		let value = *qu.run_and_get::<Int>("return hit(Player(8))").unwrap();
+		assert_eq!(value, 3);
+		let value = *qu.run_and_get::<Bool>("return Player(0).is_alive()").unwrap();
+		assert_eq!(value, false);
+		let value = *qu.run_and_get::<Bool>("return target is Damageable").unwrap();
+		assert_eq!(value, true);
+
+		// Traits without functions don't need a body
+		let value = *qu.run_and_get::<Bool>("
+			trait Sync
+
+			class Message:
+				var id int
+				impl Sync
+
+			return Message() is Sync
+		").unwrap();
+		assert_eq!(value, true);
+	}
+
+
+	#[test]
+	fn traits_operators() {
+		let mut qu = Qu::new();
+		let value = qu.run_and_display("
+			class Vector:
+				var x int
+				var y int
+
+				fn init(x int, y int):
+					self.x = x
+					self.y = y
+
+				impl Add:
+					fn add(other Vector) Vector:
+						return Vector(self.x + other.x, self.y + other.y)
+
+			return Vector(1, 2) + Vector(3, 4)
+		").unwrap();
+		assert_eq!(value, Some("(x: 4, y: 6): Vector".into()));
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn traits_missing_function_panic() {
+		let mut qu = Qu::new();
+		qu.run("
+			trait Named:
+				fn name() str
+				fn id() int
+
+			class Player:
+				var id int
+
+				impl Named:
+					fn id() int:
+						return self.id
+		").unwrap();
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn traits_impl_outside_class_panic() {
+		let mut qu = Qu::new();
+		qu.run("
+			trait Named:
+				fn name() str
+
+			impl Named:
+				fn name() str:
+					return \"name\"
+		").unwrap();
+	}
+
+
+	// TODO: Prevent functions definitions from having multiple parameters of
+	// 	the same name 
+	// #[test]
+	// #[should_panic]
+	fn _function_multiple_parameters_same_name() {
+		let mut qu = Qu::new();
+		let script = r#"
+			fn some(a int, a int, c int) int:
+				return a + c
+		"#;
+		qu.run(script).unwrap();
+	}
+
+
+	#[test]
+	fn import() {
+		let mut qu = Qu::new();
+		let result:i32 = *qu.run_and_get("
+			import math
+			return math.foo(3)
+		").unwrap();
+		assert_eq!(result, 3);
+	}
+
+
+	#[test]
+	fn import_2() {
+		let mut qu = Qu::new();
+		let result:i32 = *qu.run_and_get("
+			import math.foo
+			return foo(3)
+		").unwrap();
+		assert_eq!(result, 3);
+	}
+
+
+	#[test]
+	fn import_3() {
+		let mut qu = Qu::new();
+		let result:i32 = *qu.run_and_get("
+			import math
+			import math.foo
+			return math.foo(3) + foo(3)
+		").unwrap();
+		assert_eq!(result, 3+3);
+	}
+
+
+	#[test]
+	fn import_failed_compile() {
+		let mut qu = Qu::new();
+		let errors = qu.compile("import nope").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::UndefinedType);
+		assert!(qu.compile("import nope.foo").is_err());
+		assert!(qu.compile("import math.nope").is_err());
+		qu.run("var x int = 1").unwrap();
+		assert!(qu.compile("import __main__.x").is_err());
+	}
+
+
+	#[test]
+	#[should_panic]
+	fn not_imported_panic() {
+		let mut qu = Qu::new();
+		let result:i32 = *qu.run_and_get("
+			return math.foo(1)
+		").unwrap();
+		dbg!(result);
+	}
+
+	#[test]
+	#[should_panic]
+	/// Panic if an item from another module is accessed without importing that
+	/// item.
+	fn not_imported_panic_2() {
+		let mut qu = Qu::new();
+		let result = qu.run("
+			return foo(3)
+		").unwrap();
+		dbg!(result);
+	}
+
+
+	#[test]
+	#[should_panic]
+	/// Panic if a specific function is imported, but not the module, then that
+	/// function accessed through the module.
+	fn not_imported_panic_3() {
+		let mut qu = Qu::new();
+		let result:i32 = *qu.run_and_get("
+			import math.foo
+			return math.foo(1)
+		").unwrap();
+		dbg!(result);
+	}
+
+
+	#[test]
+	/// Return an imported module.
+	fn return_module() {
+		let mut qu = Qu::new();
+		let result:&Module = qu.run_and_get("
+			import math
+			return math
+		").unwrap();
+		dbg!(result);
+	}
+
+
+	#[test]
+	#[should_panic]
+	/// Panic when a module is referenced without being imported.
+	fn return_module_panic() {
+		let mut qu = Qu::new();
+		let result:&Module = qu.run_and_get("
+			return math
+		").unwrap();
+		dbg!(result);
+	}
+
+
+	#[test]
+	fn error_kinds() {
+		let mut qu = Qu::new();
+
+		let err = qu.run("var a int = 1 )").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::InvalidSyntax);
+
+		let err = qu.run("var a Nothing = 1").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::UndefinedType);
+
+		let err = qu.run("nothing(1)").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::UndefinedFunction);
+
+		let err = qu.run("if 1:\n\tvar a int = 1").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::TypeMismatch);
+
+		for script in [
+			"var a int = \"one\"",
+			"var a bool = 1 and true",
+			"var a int = 1\na()",
+			"fn f() int:\n\treturn true",
+		] {
+			let err = qu.compile(script).unwrap_err();
+			assert_eq!(err[0].kind, QuErrorKind::TypeMismatch, "{}", script);
+		}
+		let err = qu.run_and_get::<Float>("return 1").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::TypeMismatch);
+
+		// Int arithmetic without a result fails at runtime
+		for script in [
+			"var a int = 5\nvar b int = a / 0",
+			"var a int = 2147483647 + 1",
+			"var a int = -2147483647 - 2",
+			"var a int = 65536 * 65536",
+			"var a int = -(-2147483647 - 1)",
+			"var a int = -9 // 2",
+		] {
+			let err = qu.run(script).unwrap_err();
+			assert_eq!(err.kind, QuErrorKind::Runtime, "{}", script);
+		}
+
+		// The instance is still usable after errors
+		let result:Int = *qu.run_and_get("return 2").unwrap();
+		assert_eq!(result, 2);
+	}
+
+
+	#[test]
+	fn error_kinds_registration() {
+		struct Wrapped; impl Register for Wrapped {}
+
+		let mut qu = Qu::new();
+		let err = qu.register(&|r| {
+			r.add_class::<Wrapped>()?;
+			Ok(())
+		}).unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::Registration);
+
+		let err = qu.register(&|r| {
+			r.add_module("wrong_arg", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				let bool = m.get_class_id_of::<Bool>().unwrap();
+				m.add_function("get_wrong", [int], int, &|api| {
+					api.get::<Bool>(0)?;
+					Ok(())
+				})?;
+				m.add_function("set_wrong", [int], int, &|api| {
+					api.set::<Bool>(true)?;
+					Ok(())
+				})?;
+				m.add_function_to_class(int, "not_bound", [bool], int, &|_| {
+					Ok(())
+				})?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::Registration);
+
+		let err = qu.run("
+			import wrong_arg.get_wrong
+			get_wrong(1)
+		").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::TypeMismatch);
+
+		let err = qu.run("
+			import wrong_arg.set_wrong
+			set_wrong(1)
+		").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::TypeMismatch);
+	}
+
+
+	#[test]
+	fn error_spans() {
+		let mut qu = Qu::new();
+
+		// Compiler errors point at the offending expression
+		let script = "var a int = 1\nif a < 2:\n\tvar b int = a + missing\n";
+		let span = qu.run(script).unwrap_err().span.unwrap();
+		assert_eq!((span.row, span.column), (2, 18));
+		assert_eq!(&script[span.start..span.end], "missing");
+
+		// Columns don't wrap on long lines
+		let script = format!("var c int = {}y", "1 + ".repeat(100));
+		let span = qu.run(&script).unwrap_err().span.unwrap();
+		assert_eq!((span.row, span.column), (0, 413));
+		assert_eq!(&script[span.start..span.end], "y");
+
+		// Runtime errors point at the expression that raised them
+		let script = "fn root(x int) int:\n\treturn x // 0\n\nroot(4)";
+		let span = qu.run(script).unwrap_err().span.unwrap();
+		assert_eq!((span.row, span.column), (1, 9));
+		assert_eq!(&script[span.start..span.end], "x // 0");
+	}
+
+
+	#[test]
+	fn parsed_spans() {
+		let script = "var a int = 1\nvar b int = a + 2";
+		let block = QuParser::new().parse(script).unwrap();
+		let Statement::VarDeclaration(decl) = &block.statements[1] else {
+			panic!("Expected a variable declaration");
+		};
+		assert_eq!(&script[decl.span.start..decl.span.end], "var b int = a + 2");
+		assert_eq!((decl.span.row, decl.span.column), (1, 1));
+
+		let value = decl.initial_value.as_ref().unwrap();
+		assert_eq!(&script[value.span().start..value.span().end], "a + 2");
+		assert_eq!(value.span().column, 13);
+		assert_eq!(block.span.end, script.len());
+	}
+
+
+	#[test]
+	fn compile_diagnostics() {
+		let mut qu = Qu::new();
+
+		// Parsing continues after an error, in nested blocks too
+		let errors = qu.compile("
+			fn broken() int:
+				var a int = )
+				return 1
+			else:
+				var b int = 2
+			var c int = 3 )
+		").unwrap_err();
+		let rows:Vec<u32> = errors.iter()
+			.map(|msg| msg.span.unwrap().row)
+			.collect();
+		assert_eq!(rows, [2, 4, 6]);
+		assert!(errors.iter().all(|msg| msg.is_error()));
+		assert_eq!(errors[1].kind, QuErrorKind::InvalidFlowStatement);
+		assert!(errors[1].help.is_some());
+
+		// Warnings don't stop compilation
+		let warnings = qu.compile("
+			fn early() int:
+				return 1
+				var unused int = 2
+		").unwrap();
+		let warnings = warnings.diagnostics();
+		assert_eq!(warnings.len(), 1);
+		assert_eq!(warnings[0].severity, QuSeverity::Warning);
+		assert_eq!(warnings[0].kind, QuErrorKind::UnreachableCode);
+		assert_eq!(warnings[0].span.unwrap().row, 3);
+
+		// A top level return ends the module's code too
+		let script = qu.compile("
+			return 1
+			var z int = 3
+		").unwrap();
+		assert_eq!(script.diagnostics()[0].kind, QuErrorKind::UnreachableCode);
+		qu.run_script(&script).unwrap();
+		assert_eq!(*qu.get_result::<Int>().unwrap(), 1);
+		assert_eq!(qu.run("return z").unwrap_err().kind, QuErrorKind::Runtime);
+
+		// Running reports the first error
+		let err = qu.run("var d int = )\nvar e int = (").unwrap_err();
+		assert_eq!(err.span.unwrap().row, 0);
+	}
+
+
+	#[test]
+	fn lexer_token_kinds() {
+		use crate::tokens::{tokenize, Indentation, TokenKind};
+
+		let tokens = tokenize("var pi float = 3.12 # Comment\nfoo.0 // 2", Indentation::Tabs);
+		let kinds:Vec<(TokenKind, &str)> = tokens.iter()
+			.map(|tk| (tk.kind, tk.slice.as_str()))
+			.collect();
+		assert_eq!(kinds, [
+			(TokenKind::Keyword, "var"),
+			(TokenKind::Name, "pi"),
+			(TokenKind::Name, "float"),
+			(TokenKind::Symbol, "="),
+			(TokenKind::Float, "3.12"),
+			(TokenKind::Name, "foo"),
+			(TokenKind::Symbol, "."),
+			(TokenKind::Int, "0"),
+			(TokenKind::Symbol, "//"),
+			(TokenKind::Int, "2"),
+		]);
+
+		// Spaces can be used for indentation
+		let tokens = tokenize("if x:\n    y\n\tz\n  w", Indentation::Spaces(4));
+		let indents:Vec<u8> = tokens.iter()
+			.map(|tk| tk.char_index.indent)
+			.collect();
+		assert_eq!(indents, [0, 0, 0, 1, 1, 0]);
+
+		// Deeper indentation than an indent can hold is capped rather than
+		// overflowing
+		let script = format!("x\n{}y", "\t".repeat(300));
+		let tokens = tokenize(&script, Indentation::Tabs);
+		assert_eq!(tokens[1].char_index.indent, u8::MAX);
+		let script = format!("x\n{}y", " ".repeat(300));
+		let tokens = tokenize(&script, Indentation::Spaces(1));
+		assert_eq!(tokens[1].char_index.indent, u8::MAX);
+		let mut qu = Qu::new();
+		let script = format!("var a int = 1\n{}var b int = 2", "\t".repeat(300));
+		assert!(qu.compile(&script).is_err());
+
+		// Keywords can't be used as names
+		for keyword in ["return", "while", "import", "true", "false"] {
+			let tokens = tokenize(keyword, Indentation::Tabs);
+			assert_eq!(tokens[0].kind, TokenKind::Keyword);
+			let mut qu = Qu::new();
+			let script = format!("var {} int = 5", keyword);
+			assert!(qu.compile(&script).is_err(), "{}", keyword);
+		}
+	}
+
+
+	#[test]
+	fn comments() {
+		let mut qu = Qu::new();
+		let result:Float = *qu.run_and_get("
+			# A single line comment
+			var value float = 1.5 # After code
+
+			#/ A multi
+				line comment /#
+			value = value + 2.
+			return value
+		").unwrap();
+		assert_eq!(result, 3.5);
+	}
+
+
+	#[test]
+	fn number_literals() {
+		let mut qu = Qu::new();
+
+		let result:Int = *qu.run_and_get("
+			return 0xFF + 0b1010 + 0o17 + 1_000_000
+		").unwrap();
+		assert_eq!(result, 255 + 10 + 15 + 1_000_000);
+
+		// Hex literals can use every bit
+		let result:Int = *qu.run_and_get("return 0xFFFF_FFFF").unwrap();
+		assert_eq!(result, -1);
+
+		let result:Float = *qu.run_and_get("return 1e-3 + 2.5E2 + 2f").unwrap();
+		assert_eq!(result, 0.001 + 250.0 + 2.0);
+
+		// Ints and floats with the same value don't share a constant
+		let result:Float = *qu.run_and_get("
+			var a int = 5
+			var b float = 5
+			return b / 2.0
+		").unwrap();
+		assert_eq!(result, 2.5);
+
+		// Overflow is reported instead of wrapping
+		let errors = qu.compile("var x int = 2147483648").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidLiteral);
+		assert_eq!(errors[0].span.unwrap().column, 13);
+		let errors = qu.compile("var x int = 0x1_0000_0000").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidLiteral);
+		let errors = qu.compile("var x float = 1e39").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidLiteral);
+
+		// Negative literals are checked with their sign
+		let result:Int = *qu.run_and_get("return -2147483648").unwrap();
+		assert_eq!(result, Int::MIN);
+		let result:Int = *qu.run_and_get("return -5.hash() + - -2").unwrap();
+		assert_eq!(result, -5 + 2);
+		let errors = qu.compile("var x int = -2147483649").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidLiteral);
+
+		// Invalid digits and exponents
+		let errors = qu.compile("var x int = 0b102").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidLiteral);
+		for script in ["return 1e", "return 2.5e+", "return 1e3x"] {
+			let errors = qu.compile(script).unwrap_err();
+			assert_eq!(errors[0].kind, QuErrorKind::InvalidLiteral, "{script}");
+		}
+	}
+
+
+	#[test]
+	fn for_loops() {
+		let mut qu = Qu::new();
+
+		let result:Int = *qu.run_and_get("
+			var total int = 0
+			for i in range(1, 5):
+				total = total + i
+			for i in range(3):
+				total = total + 100
+			for i in range(5, 0):
+				total = total + 1000
+			return total
+		").unwrap();
+		assert_eq!(result, 10 + 300);
+
+		// Iterating doesn't change the iterable
+		let result:Int = *qu.run_and_get("
+			var count int = 0
+			var numbers Range = range(3)
+			for a in numbers:
+				for b in numbers:
+					count = count + 1
+			return count
+		").unwrap();
+		assert_eq!(result, 9);
+
+		// Script classes can implement Iterator
+		let result:Int = *qu.run_and_get("
+			class Countdown:
+				var count int
+
+				fn init(from int):
+					self.count = from + 1
+
+				fn current() int:
+					return self.count
+
+				impl Iterator:
+					fn next() bool:
+						self.count = self.count - 1
+						return self.count > 0
+
+			var digits int = 0
+			for n in Countdown(3):
+				digits = digits * 10 + n
+			return digits
+		").unwrap();
+		assert_eq!(result, 321);
+
+		let errors = qu.compile("for x in 5:\n\tvar y int = x").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(errors[0].span.unwrap().column, 10);
+	}
+
+
+	#[test]
+	fn for_loops_rust_iterator() {
+		use crate::Range;
+
+		#[derive(Clone, Copy)]
+		struct Fibonacci {
+			current: Int,
+			next: Int,
+		}
+		impl Register for Fibonacci {}
+
+		let mut qu = Qu::new();
+		qu.register(&|r| {
+			r.add_module("fib", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				let bool = m.get_class_id_of::<Bool>().unwrap();
+				let iterator = m.get_class_id_of::<crate::QuIterator>().unwrap();
+				let fib = m.add_class::<Fibonacci>()?;
+
+				m.add_function("fibonacci", [], fib, &|api| {
+					api.set(Fibonacci {current: 0, next: 1})
+				})?;
+				m.add_function_to_class(fib, "copy", [fib], fib, &|api| {
+					let value = *api.get::<Fibonacci>(0)?;
+					api.set(value)
+				})?;
+				m.add_function_to_class(fib, "current", [fib], int, &|api| {
+					let value = api.get::<Fibonacci>(0)?.current;
+					api.set(value)
+				})?;
+				m.implement(iterator, fib)?;
+				m.implement_function(iterator, fib, "next", [fib], bool, &|api| {
+					let fib = api.get_mut::<Fibonacci>(0)?;
+					(fib.current, fib.next) = (fib.next, fib.current + fib.next);
+					api.set(true)
+				})?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+
+		let result:Int = *qu.run_and_get("
+			import fib.fibonacci
+
+			fn first_over(limit int) int:
+				for value in fibonacci():
+					if value > limit:
+						return value
+				return 0
+
+			return first_over(50)
+		").unwrap();
+		assert_eq!(result, 55);
+
+		// Ranges can be used from Rust
+		let mut range = Range::new(0, 2);
+		assert!(range.advance());
+		assert_eq!(range.current(), 0);
+		assert!(range.advance());
+		assert!(!range.advance());
+	}
+
+
+	#[test]
+	fn iterators_by_hand() {
+		let mut qu = Qu::new();
+
+		// Top level
+		let result:Int = *qu.run_and_get("
+			var r Range = range(5)
+			r.next()
+			r.next()
+			return r.current()
+		").unwrap();
+		assert_eq!(result, 1);
+
+		let mut qu = Qu::new();
+		let result:Int = *qu.run_and_get("
+			var it Range = range(4)
+			var total int = 0
+			while it.next():
+				total = total + it.current()
+			return total
+		").unwrap();
+		assert_eq!(result, 6);
+
+		// In a function
+		let mut qu = Qu::new();
+		let result:Int = *qu.run_and_get("
+			fn sum(it Range) int:
+				var total int = 0
+				while it.next():
+					total = total + it.current()
+				return total
+			return sum(range(5))
+		").unwrap();
+		assert_eq!(result, 10);
+
+		// Through fields
+		let mut qu = Qu::new();
+		let result:Int = *qu.run_and_get("
+			class Holder:
+				var r Range = range(4)
+
+			fn f() int:
+				var h Holder = Holder()
+				h.r.next()
+				h.r.next()
+				return h.r.current()
+
+			var h Holder = Holder()
+			var total int = 0
+			while h.r.next():
+				total = total + h.r.current()
+			return total + f()
+		").unwrap();
+		assert_eq!(result, 7);
+
+		// Script iterators
+		let mut qu = Qu::new();
+		let result:Int = *qu.run_and_get("
+			class Counter:
+				var n int
+				fn current() int:
+					return self.n
+				impl Iterator:
+					fn next() bool:
+						self.n = self.n + 1
+						return self.n < 3
+
+			fn f() int:
+				var c Counter = Counter()
+				c.next()
+				c.next()
+				return c.current()
+
+			var c Counter = Counter()
+			c.next()
+			return c.current() + f()
+		").unwrap();
+		assert_eq!(result, 3);
+	}
+
+
+	#[test]
+	fn break_and_continue() {
+		let mut qu = Qu::new();
+
+		let result:Int = *qu.run_and_get("
+			var i int = 0
+			var total int = 0
+			while true:
+				i = i + 1
+				if i == 3:
+					continue
+				if i > 5:
+					break
+				total = total + i
+			return total
+		").unwrap();
+		assert_eq!(result, 1 + 2 + 4 + 5);
+
+		let result:Int = *qu.run_and_get("
+			var total int = 0
+			for i in range(10):
+				if i == 2:
+					continue
+				elif i == 4:
+					break
+				total = total + i
+			return total
+		").unwrap();
+		assert_eq!(result, 0 + 1 + 3);
+
+		// Only the innermost loop is left
+		let result:Int = *qu.run_and_get("
+			var count int = 0
+			for a in range(3):
+				var b int = 0
+				while true:
+					b = b + 1
+					if b > 2:
+						break
+					count = count + 1
+				count = count + 100
+			return count
+		").unwrap();
+		assert_eq!(result, 3 * 102);
+
+		let errors = qu.compile("var a int = 1\nbreak").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidFlowStatement);
+		assert_eq!(errors[0].span.unwrap().row, 1);
+
+		// Functions in a loop's body aren't part of the loop
+		let errors = qu.compile(
+			"while true:\n\tfn skip():\n\t\tcontinue\n\tbreak"
+		).unwrap_err();
+		assert_eq!(errors.len(), 1);
+		assert_eq!(errors[0].kind, QuErrorKind::InvalidFlowStatement);
+	}
+
+
+	#[test]
+	fn lists() {
+		let mut qu = Qu::new();
+
+		let result:Int = *qu.run_and_get("
+			var numbers List[int] = [1, 2, 3,]
+			numbers.append(4)
+			numbers[0] = 10
+			var last int = numbers.pop()
+			return numbers[0] + numbers[2] * last + numbers.len()
+		").unwrap();
+		assert_eq!(result, 10 + 3 * 4 + 3);
+
+		// Empty lists get their type from where they're declared
+		let result:Float = *qu.run_and_get("
+			var total float = 0.0
+			var halves List[float] = []
+			for i in range(4):
+				halves.append(float(i) / 2.0)
+			for i in range(halves.len()):
+				total = total + halves[i]
+			return total
+		").unwrap();
+		assert_eq!(result, 3.0);
+
+		// Lists can be given to functions and returned from them
+		let result:Str = qu.run_and_get::<Str>("
+			fn words(count int) List[str]:
+				var list List[str] = []
+				while list.len() < count:
+					list.append(\"word\")
+				return list
+			fn first(list List[str]) str:
+				return list[0]
+			return first(words(2)) + words(3)[2]
+		").unwrap().clone();
+		assert_eq!(result, "wordword");
+
+		// Lists are read from Rust as a List
+		let list = qu.run_and_get::<List<Int>>("
+			var list List[int] = [1, 2]
+			list.append(3)
+			return list
+		").unwrap();
+		assert_eq!(list.to_vec(), vec![1, 2, 3]);
+
+		// List literals can be returned and indexed directly
+		let list = qu.run_and_get::<List<Int>>("return [1, 2, 3]").unwrap();
+		assert_eq!(list.to_vec(), vec![1, 2, 3]);
+		let result:Int = *qu.run_and_get("
+			fn empty() List[int]:
+				return []
+			return empty().len()
+		").unwrap();
+		assert_eq!(result, 0);
+		let result:Int = *qu.run_and_get("return [5, 6][1]").unwrap();
+		assert_eq!(result, 6);
+
+		let script = "var list List[int] = [1, 2]\nvar a int = list[2]";
+		let err = qu.run(script).unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::Runtime);
+		assert_eq!(&script[err.span.unwrap().start..err.span.unwrap().end], "list[2]");
+		assert_eq!(qu.run("var l List[int] = []\nl.pop()").unwrap_err().kind, QuErrorKind::Runtime);
+
+		assert_eq!(qu.compile("var l List[int] = [1]\nvar m = []").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(qu.compile("var l List[int] = [1, true]").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(qu.compile("var l List[int] = [1]\nl[1.0] = 2").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(qu.compile("var a int = 5\nvar b int = a[0]").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+	}
+
+
+	#[test]
+	fn lists_from_rust() {
+		let mut qu = Qu::new();
+		qu.register(&|r| {
+			r.add_module("stats", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				let ints = m.get_class_id_of::<List<Int>>().unwrap();
+				m.add_function("sum", [ints], int, &|api| {
+					let sum = api.get::<List<Int>>(0)?.to_vec().iter().sum();
+					api.set::<Int>(sum)
+				})?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+
+		let result:Int = *qu.run_and_get("
+			import stats.sum
+			return sum([1, 2, 3]) + [4, 5].sum()
+		").unwrap();
+		assert_eq!(result, 15);
+	}
+
+
+	#[test]
+	fn lists_of_any_class() {
+		let mut qu = Qu::new();
+
+		// Lists of script classes are defined the first time they're named
+		let result:Str = qu.run_and_get::<Str>("
+			class Named:
+				var name str
+
+				fn init(name str):
+					self.name = name
+
+			var people List[Named] = [Named(\"ann\"), Named(\"bob\")]
+			people.append(Named(\"cid\"))
+			people[0] = Named(\"dan\")
+			var text str = people.pop().name
+			for person in people:
+				text = text + person.name
+			return text
+		").unwrap().clone();
+		assert_eq!(result, "ciddanbob");
+
+		// Lists of lists and of tuples
+		let result:Int = *qu.run_and_get("
+			var grid List[List[int]] = [[1, 2], [3]]
+			grid[1].append(4)
+			var total int = 0
+			for row in grid:
+				for n in row:
+					total = total + n
+			return total + grid.len() * 100
+		").unwrap();
+		assert_eq!(result, 10 + 200);
+
+		let result:Int = *qu.run_and_get("
+			fn products(pairs List[(int, int)]) List[int]:
+				var result List[int] = []
+				for pair in pairs:
+					var (a, b) = pair
+					result.append(a * b)
+				return result
+			var pairs List[(int, int)] = [(1, 2), (3, 4)]
+			var (a, b) = pairs[0]
+			return products(pairs)[1] + b
+		").unwrap();
+		assert_eq!(result, 12 + 2);
+
+		// Maps have values of any class
+		let result:Str = qu.run_and_get::<Str>("
+			var by_id Map[int, Named] = {1: Named(\"eve\")}
+			by_id[2] = Named(\"fay\")
+			var groups Map[str, List[int]] = {\"a\": [1]}
+			groups[\"a\"].append(2)
+			return by_id[1].name + by_id[2].name + str(groups[\"a\"].len())
+		").unwrap().clone();
+		assert_eq!(result, "evefay2");
+
+		assert_eq!(
+			qu.compile("var l List[Nope] = []").unwrap_err()[0].kind,
+			QuErrorKind::UndefinedType,
+		);
+	}
+
+
+	#[test]
+	fn maps() {
+		let mut qu = Qu::new();
+
+		let result:Int = *qu.run_and_get("
+			var ages Map[str, int] = {\"ann\": 30, \"bob\": 25,}
+			ages[\"cid\"] = 40
+			ages[\"ann\"] = ages[\"ann\"] + 1
+			var removed bool = ages.remove(\"bob\")
+			if ages.has(\"bob\") or not removed:
+				return 0
+			return ages[\"ann\"] + ages[\"cid\"] + ages.len() + ages.keys().len()
+		").unwrap();
+		assert_eq!(result, 31 + 40 + 2 + 2);
+
+		// Maps and lists can be looped over. Maps loop over their keys in
+		// the order they were inserted.
+		let result:Str = qu.run_and_get::<Str>("
+			var names Map[int, str] = {}
+			names[3] = \"c\"
+			names[1] = \"a\"
+			names[3] = \"C\"
+			var text str = \"\"
+			for id in names:
+				text = text + names[id]
+			for word in [\"x\", \"y\"]:
+				text = text + word
+			return text
+		").unwrap().clone();
+		assert_eq!(result, "Caxy");
+
+		let result:Bool = *qu.run_and_get("
+			var text str = \"ab\" + \"c\"
+			return \"abc\".hash() == text.hash() and 5.hash() == 5
+		").unwrap();
+		assert!(result);
+
+		// Maps are read from Rust as a Map
+		let map = qu.run_and_get::<Map<Str, Int>>("
+			var map Map[str, int] = {\"a\": 1}
+			map[\"b\"] = 2
+			return map
+		").unwrap();
+		assert_eq!(map.get(&"b".into()), Some(2));
+		assert_eq!(map.to_vec(), vec![("a".into(), 1), ("b".into(), 2)]);
+
+		let script = "var map Map[str, int] = {\"a\": 1}\nvar a int = map[\"b\"]";
+		let err = qu.run(script).unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::Runtime);
+		assert_eq!(&script[err.span.unwrap().start..err.span.unwrap().end], "map[\"b\"]");
+
+		assert_eq!(qu.compile("var m Map[str, int] = {}\nvar n = {}").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(qu.compile("var m Map[str, int] = {\"a\": 1}\nm[1] = 2").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert!(qu.compile("var m Map[float, int] = {}").is_err());
+
+		// Removing an entry keeps the others findable and in order
+		let map = qu.run_and_get::<Map<Int, Int>>("
+			var map Map[int, int] = {1: 10, 2: 20, 3: 30, 4: 40}
+			map.remove(2)
+			map.remove(4)
+			map[5] = 50
+			return map
+		").unwrap();
+		assert_eq!(map.to_vec(), vec![(1, 10), (3, 30), (5, 50)]);
+		assert_eq!(map.get(&3), Some(30));
+		assert!(!map.contains_key(&2));
+
+		// Classes declared in scripts are keys if they implement `Hash` and
+		// `Equal`, which maps hash and compare the keys with
+		let result:Str = qu.run_and_get::<Str>("
+			class Tile:
+				var x int
+				var y int
+
+				fn init(x int, y int):
+					self.x = x
+					self.y = y
+
+				impl Hash:
+					fn hash() int:
+						return self.x
+
+				impl Equal:
+					fn equal(other Tile) bool:
+						return self.x == other.x and self.y == other.y
+
+			var tiles Map[Tile, str] = {Tile(1, 2): \"grass\"}
+			tiles[Tile(1, 3)] = \"sand\"
+			tiles[Tile(4, 2)] = \"rock\"
+			tiles[Tile(1, 2)] = \"water\"
+			tiles.remove(Tile(4, 2))
+			if tiles.has(Tile(4, 2)) or tiles.len() != 2:
+				return \"\"
+			var text str = \"\"
+			for tile in tiles:
+				text = text + tiles[tile]
+			return text + tiles[Tile(1, 3)]
+		").unwrap().clone();
+		assert_eq!(result, "watersandsand");
+
+		let err = qu.run("
+			class Cell:
+				var x int
+
+				impl Hash:
+					fn hash() int:
+						return self.x
+
+				impl Equal:
+					fn equal(other Cell) bool:
+						return self.x == other.x
+
+			var cells Map[Cell, int] = {}
+			var a int = cells[Cell()]
+		").unwrap_err();
+		assert_eq!(err.kind, QuErrorKind::Runtime);
+
+		// Keys have to be hashed and compared as their own class
+		let err = qu.compile("
+			class Point:
+				var x int
+
+				impl Hash:
+					fn hash() int:
+						return self.x
+
+			var points Map[Point, int] = {}
+		").unwrap_err();
+		assert_eq!(err[0].kind, QuErrorKind::TypeMismatch);
+		assert!(err[0].description.contains("'Point'"), "{}", err[0].description);
+	}
+
+
+	#[test]
+	fn tuples() {
+		let mut qu = Qu::new();
+
+		// Functions can return tuples, which are destructured into variables
+		let result:Int = *qu.run_and_get("
+			fn divide(a int, b int) (int, int):
+				return a / b, a % b
+			var (quotient, remainder) = divide(17, 5)
+			var pair (int, int) = 1, 2
+			var (first, second) = pair
+			return quotient * 100 + remainder * 10 + first + second
+		").unwrap();
+		assert_eq!(result, 300 + 20 + 3);
+
+		// Destructuring in a function, and tuples given to functions
+		let result:Str = qu.run_and_get::<Str>("
+			fn swap(pair (str, str)) (str, str):
+				var (a, b) = pair
+				return b, a
+			fn join() str:
+				var (a, b) = swap((\"x\", \"y\"))
+				return a + b
+			return join()
+		").unwrap().clone();
+		assert_eq!(result, "yx");
+
+		// Tuples are read from Rust as Rust tuples
+		let result = qu.run_and_get_tuple::<(Int, Float, Str)>("
+			fn stats() (int, float, str):
+				return 2, 0.5, \"ok\"
+			return stats()
+		").unwrap();
+		assert_eq!(result, (2, 0.5, "ok".into()));
+		assert!(qu.run_and_get_tuple::<(Int, Int)>("return 1, 2.0").is_err());
+
+		assert_eq!(
+			qu.run_and_display("return 1, (true, 2.5)").unwrap(),
+			Some("(1, (true, 2.5)): (int, (bool, float))".into()),
+		);
+
+		assert_eq!(qu.compile("var (a, b) = 1, 2, 3").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(qu.compile("var (a, b) = 5").unwrap_err()[0].kind, QuErrorKind::TypeMismatch);
+		assert_eq!(qu.compile("var (a, b)").unwrap_err()[0].kind, QuErrorKind::InvalidSyntax);
+	}
+
+
+	#[test]
+	fn functions_as_values() {
+		let mut qu = Qu::new();
+
+		// Lambdas and named functions can be stored and passed as callbacks.
+		// Lambdas capture locals by value when they're made.
+		let result:Int = *qu.run_and_get("
+			fn apply(f fn(int) int, x int) int:
+				return f(x)
+			fn double(x int) int:
+				return x * 2
+			fn run() int:
+				var offset int = 3
+				var add fn(int) int = fn(x int) int:
+					return x + offset
+				offset = 100
+				return apply(add, 1) * 100 + apply(double, 5) + add(10)
+			return run()
+		").unwrap();
+		assert_eq!(result, 400 + 10 + 13);
+
+		// Static variables can hold function values, and aren't captured
+		let result:Int = *qu.run_and_get("
+			var scale int = 2
+			var scaled fn(int) int = fn(x int) int:
+				return x * scale
+			scale = 3
+			var do_nothing fn() = fn():
+				var unused int = 0
+			do_nothing()
+			return scaled(5)
+		").unwrap();
+		assert_eq!(result, 15);
+
+		assert_eq!(
+			qu.run_and_display("return fn(a str, b float) bool:\n\treturn true").unwrap(),
+			Some("fn(str, float) bool: fn(str, float) bool".into()),
+		);
+
+		// Rust hosts can keep callables and invoke them later
+		thread_local! {
+			static HANDLER:std::cell::RefCell<Option<Callable>>
+				= Default::default();
+		}
+		qu.register(&|r| {
+			r.add_module("events", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				let void = m.get_class_id_of::<Void>().unwrap();
+				let handler = m.function_type(&[int], int)?;
+				m.add_function("on_event", [handler], void, &|api| {
+					let handler = api.get::<Callable>(0)?.clone();
+					HANDLER.with(|cell| *cell.borrow_mut() = Some(handler));
+					Ok(())
+				})?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+		qu.run("
+			import events.on_event
+			fn setup():
+				var bonus int = 7
+				var handler fn(int) int = fn(x int) int:
+					return x + bonus
+				on_event(handler)
+			setup()
+		").unwrap();
+		let handler = HANDLER.with(|cell| cell.borrow().clone()).unwrap();
+		assert_eq!(qu.invoke::<(Int,), Int>(&handler, (1,)).unwrap(), 8);
+		assert!(qu.invoke::<(Float,), Int>(&handler, (1.0,)).is_err());
+		assert!(qu.invoke::<(), Int>(&handler, ()).is_err());
+
+		// Captured values are owned by the callable
+		let mut qu = Qu::new();
+		let greet = qu.run_and_get::<Callable>("
+			fn greeter(name str) fn() str:
+				var greeting str = \"Hello, \" + name
+				return fn() str:
+					return greeting
+			return greeter(\"Qu\")
+		").unwrap().clone();
+		qu.run("var other str = \"a\" + \"b\"").unwrap();
+		let text = qu.invoke::<(), Str>(&greet, ()).unwrap();
+		assert_eq!(text.as_str(), "Hello, Qu");
+		let msg = Qu::new().invoke::<(), Str>(&greet, ()).unwrap_err();
+		assert_eq!(
+			msg.description,
+			"Can't invoke a callable made by another Qu instance.",
+		);
+
+		let errors = qu.compile("
+			fn twice(f fn(int) int) int:
+				return f(f(1))
+			twice(2)
+		").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::UndefinedFunction);
+		let errors = qu.compile("
+			var f fn(int) int = fn(x int) int:
+				return x
+			f(1, 2)
+		").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::TypeMismatch);
+		let errors = qu.compile("
+			fn g(x int) int:
+				return x
+			fn g(x float) float:
+				return x
+			var h = g
+		").unwrap_err();
+		assert_eq!(errors[0].kind, QuErrorKind::TypeMismatch);
+	}
+
+
+	#[test]
+	fn calling_functions_from_rust() {
+		let mut qu = Qu::new();
+		qu.run("
+			var calls int = 0
+			fn count() int:
+				calls = calls + 1
+				return calls
+			fn scale(x int) int:
+				return x * 2
+			fn scale(x float) float:
+				return x * 0.5
+			fn greet(name str, times int) str:
+				var text str = \"\"
+				for i in range(times):
+					text = text + \"hi \" + name + \" \"
+				return text
+			fn element(index int) int:
+				var numbers List[int] = [10, 20]
+				return numbers[index]
+		").unwrap();
+
+		// Calls keep the state of static variables between them
+		assert_eq!(qu.call::<(), Int>("count", ()).unwrap(), 1);
+		assert_eq!(qu.call::<(), Int>("count", ()).unwrap(), 2);
+
+		// Overloads are picked by the classes of the arguments
+		assert_eq!(qu.call::<(Int,), Int>("scale", (3,)).unwrap(), 6);
+		assert_eq!(qu.call::<(Float,), Float>("scale", (3.0,)).unwrap(), 1.5);
+		assert_eq!(
+			qu.call::<(Str, Int), Str>("greet", ("qu".into(), 2)).unwrap(),
+			"hi qu hi qu ",
+		);
+		assert_eq!(qu.call::<(Int,), Int>("math.foo", (7,)).unwrap(), 7);
+
+		assert_eq!(
+			qu.call::<(), Int>("missing", ()).unwrap_err().kind,
+			QuErrorKind::UndefinedFunction,
+		);
+		assert_eq!(
+			qu.call::<(Bool,), Int>("scale", (true,)).unwrap_err().kind,
+			QuErrorKind::UndefinedFunction,
+		);
+		assert!(qu.call::<(Int,), Float>("scale", (3,)).is_err());
+		assert_eq!(
+			qu.call::<(Int,), Int>("element", (5,)).unwrap_err().kind,
+			QuErrorKind::Runtime,
+		);
+		assert_eq!(qu.call::<(Int,), Int>("element", (1,)).unwrap(), 20);
+	}
+
+
+	#[test]
+	fn compiled_scripts() {
+		let mut qu = Qu::new();
+		qu.run("var total int = 0").unwrap();
+
+		// A script runs many times without being compiled again, and its
+		// variables start over each run
+		let blocks = qu.vm.definitions.byte_code_blocks.len();
+		let script = qu.compile("
+			var step int = 2
+			step = step * 5
+			total = total + step
+			return total
+		").unwrap();
+		for expected in [10, 20, 30] {
+			qu.run_script(&script).unwrap();
+			assert_eq!(*qu.get_result::<Int>().unwrap(), expected);
+		}
+		assert_eq!(qu.vm.definitions.byte_code_blocks.len(), blocks + 1);
+
+		// Scripts compiled later can use the items of earlier scripts
+		let script = qu.compile("
+			fn bonus() int:
+				return 5
+		").unwrap();
+		qu.run_script(&script).unwrap();
+		assert_eq!(*qu.run_and_get::<Int>("return total + bonus()").unwrap(), 35);
+
+		// A runtime error doesn't stop a script from running again
+		let script = qu.compile("
+			var numbers List[int] = [1, 2]
+			return numbers[total - 29]
+		").unwrap();
+		qu.run_script(&script).unwrap();
+		assert_eq!(*qu.get_result::<Int>().unwrap(), 2);
+		qu.run("total = 100").unwrap();
+		assert_eq!(qu.run_script(&script).unwrap_err().kind, QuErrorKind::Runtime);
+		qu.run("total = 29").unwrap();
+		qu.run_script(&script).unwrap();
+		assert_eq!(*qu.get_result::<Int>().unwrap(), 1);
+
+		// Scripts only run in the Qu instance that compiled them
+		assert!(Qu::new().run_script(&script).is_err());
+	}
+
+
+	#[test]
+	fn saved_scripts() {
+		let mut qu = Qu::new();
+		let script = qu.compile(r#"
+			class Point:
+				var x int
+				var y int
+
+				fn init(x int, y int):
+					self.x = x
+					self.y = y
+
+				fn sum() int:
+					return self.x + self.y
+
+			fn scaled(point Point, by int) Point:
+				return Point(point.x * by, point.y * by)
+			fn describe() str:
+				return "saved"
+			var offset int = 10
+			var add fn(int) int = fn(x int) int:
+				return x + offset
+			var (first, second) = 2, 0.5
+			var numbers List[int] = [1, 2, 3]
+			var points List[Point] = [Point(0, 0)]
+			return add(scaled(Point(1, 2), numbers[2]).sum()) + first + points[0].x
+		"#).unwrap();
+		let bytes = qu.save_script(&script).unwrap();
+
+		let mut loaded = Qu::new();
+		let script = loaded.load_script(&bytes).unwrap();
+		loaded.run_script(&script).unwrap();
+		assert_eq!(*loaded.get_result::<Int>().unwrap(), 9 + 10 + 2);
+
+		// Everything the script defined can be used after loading
+		assert_eq!(loaded.call::<(), Str>("describe", ()).unwrap(), "saved");
+		let result = *loaded.run_and_get::<Int>("return Point(4, 5).sum() + add(1)").unwrap();
+		assert_eq!(result, 9 + 11);
+		let result = *loaded.run_and_get::<Int>("
+			points.append(Point(7, 8))
+			for point in points:
+				offset = offset + point.y
+			return offset
+		").unwrap();
+		assert_eq!(result, 10 + 8);
+
+		// Loaded scripts can be saved and loaded again
+		let bytes = loaded.save_script(&script).unwrap();
+		let script = qu.load_script(&bytes).unwrap();
+		qu.run_script(&script).unwrap();
+		assert_eq!(*qu.get_result::<Int>().unwrap(), 9 + 10 + 2);
+
+		// Broken files are refused
+		let mut corrupted = bytes.clone();
+		*corrupted.last_mut().unwrap() ^= 1;
+		assert_eq!(qu.load_script(&corrupted).unwrap_err().kind, QuErrorKind::InvalidBytecode);
+		let mut newer = bytes.clone();
+		newer[4] += 1;
+		assert_eq!(qu.load_script(&newer).unwrap_err().kind, QuErrorKind::InvalidBytecode);
+		assert_eq!(qu.load_script(b"return 5").unwrap_err().kind, QuErrorKind::InvalidBytecode);
+		assert_eq!(qu.load_script(&bytes[..bytes.len() / 2]).unwrap_err().kind, QuErrorKind::InvalidBytecode);
+
+		// Only the items scripts add are saved
+		let mut qu = Qu::new();
+		let script = qu.compile("
+			var flag bool = true
+			return 1
+		").unwrap();
+		assert!(qu.save_script(&script).unwrap().len() < 4096);
+
+		// Edited files are refused rather than run, even with a valid checksum
+		let mut qu = Qu::new();
+		let script = qu.compile("
+			class Pair:
+				var a int
+				var b int
+
+				fn init(a int, b int):
+					self.a = a
+					self.b = b
+
+			fn total(pair Pair, by int) int:
+				if by > 0:
+					return pair.a + pair.b + by
+				return pair.a * pair.b
+
+			var pair Pair = Pair(2, 3)
+			return total(pair, 4) - total(pair, 0)
+		").unwrap();
+		let bytes = qu.save_script(&script).unwrap();
+		let mut loaded = Qu::new();
+		for i in 16..bytes.len() {
+			let mut edited = bytes.clone();
+			edited[i] ^= 0x41;
+			let checksum = crate::bytecode::checksum(&edited[16..]);
+			edited[8..16].copy_from_slice(&checksum.to_le_bytes());
+			if let Ok(script) = loaded.load_script(&edited) {
+				let _ = loaded.run_script(&script);
+			}
+		}
+
+		// Registered items are bound by name when loading
+		struct Wrapped; impl Register for Wrapped {}
+		let mut qu = Qu::new();
+		qu.register(&|r| {
+			r.add_module("game", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				m.add_class::<Wrapped>()?;
+				m.add_function("level", [], int, &|api| api.set::<Int>(3))?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+		let script = qu.compile("
+			import game.level
+			import game.Wrapped
+			fn unwrap(wrapped Wrapped) int:
+				return level()
+			return level()
+		").unwrap();
+		let bytes = qu.save_script(&script).unwrap();
+
+		let mut loaded = Qu::new();
+		loaded.register(&|r| {
+			r.add_module("game", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				m.add_function("level", [], int, &|api| api.set::<Int>(3))?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+		assert_eq!(loaded.load_script(&bytes).unwrap_err().kind, QuErrorKind::UndefinedType);
+
+		let mut loaded = Qu::new();
+		loaded.register(&|r| {
+			r.add_module("game", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				m.add_class::<Wrapped>()?;
+				m.add_function("level", [int], int, &|api| api.set::<Int>(3))?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+		assert_eq!(loaded.load_script(&bytes).unwrap_err().kind, QuErrorKind::TypeMismatch);
+
+		let mut loaded = Qu::new();
+		loaded.register(&|r| {
+			r.add_module("game", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				m.add_class::<Wrapped>()?;
+				m.add_function("score", [], int, &|api| api.set::<Int>(3))?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+		assert_eq!(loaded.load_script(&bytes).unwrap_err().kind, QuErrorKind::UndefinedFunction);
+
+		let mut loaded = Qu::new();
+		loaded.register(&|r| {
+			r.add_module("game", &|m| {
+				let int = m.get_class_id_of::<Int>().unwrap();
+				m.add_class::<Wrapped>()?;
+				m.add_function("level", [], int, &|api| api.set::<Int>(7))?;
+				Ok(())
+			})?;
+			Ok(())
+		}).unwrap();
+		let script = loaded.load_script(&bytes).unwrap();
+		loaded.run_script(&script).unwrap();
+		assert_eq!(*loaded.get_result::<Int>().unwrap(), 7);
+	}
+
+
+	#[test]
+	fn disassembly() {
+		let mut qu = Qu::new();
+		let script = qu.compile("
+			fn area(width int, height int) int:
+				return width * height
+			var total int = 0
+			for i in range(3):
+				if i > 0:
+					total = total + area(i, 2)
+			return total
+		").unwrap();
+		let text = qu.disassemble();
+		assert!(text.contains("block 0: fn area(int, int) -> int\n"));
+		assert!(text.contains(": top level\n"));
+		assert!(text.contains("= arg 1\n"));
+		assert!(text.contains("static total = &"));
+		assert!(text.contains("return int\n"));
+
+		// Every jump goes to a label
+		let labels:Vec<&str> = text.lines()
+			.filter(|line| line.starts_with('L'))
+			.collect();
+		assert!(!labels.is_empty());
+		for line in text.lines().filter(|line| line.contains("jump ")) {
+			let label = line.split_whitespace().nth(2).unwrap();
+			assert!(labels.contains(&format!("{label}:").as_str()), "{line}");
+		}
+
+		// Loaded bytecode reads the same as the bytecode it was saved from
+		let bytes = qu.save_script(&script).unwrap();
+		let mut loaded = Qu::new();
+		loaded.load_script(&bytes).unwrap();
+		assert_eq!(loaded.disassemble(), text);
+	}
+}
@@ -4,7 +4,10 @@
 use duplicate::duplicate;
 use once_cell::sync::Lazy;
 
+use crate::QuCompiler;
+use crate::QuErrorKind;
 use crate::QuMsg;
+use crate::QuVm;
 use crate::Uuid;
 use crate::compiler::CONSTRUCTOR_NAME;
 use crate::compiler::ModuleId;
@@ -13,12 +16,21 @@ use crate::import::ClassId;
 use crate::import::FunctionId;
 use crate::import::Registerer;
 use crate::import::RegistererLayer;
+use crate::vm::RegId;
 use std::alloc::Layout;
+use std::any::Any;
 use std::any::TypeId;
 use std::any::type_name;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::mem::size_of;
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::rc::Rc;
 use std::sync::RwLock;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
@@ -52,10 +64,6 @@ macro_rules! qufn {
 	};
 }
 
-const EMPTY_FN:&'static dyn Fn(&mut ArgsAPI) -> Result<(), QuMsg> = &|_: &mut ArgsAPI|{
-	Ok::<(), QuMsg>(())
-};
-
 /// A method for registering the __fundamentals__ module in Qu.
 /// 
 /// # Examples
@@ -82,6 +90,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 			let float = m.add_class::<Float>()?;
 			let int = m.add_class::<Int>()?;
 			let module = m.add_class::<Module>()?;
+			let string = m.add_class::<Str>()?;
 
 			
 
@@ -93,27 +102,33 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[ident[mul] name["mul"] Type [QuMul]]
 					[ident[div] name["div"] Type [QuDiv]]
 					[ident[pow] name["pow"] Type [QuPow]]
-					[ident[modulous] name["modulous"] Type [QuMod]]
+					[ident[modulo] name["mod"] Type [QuMod]]
+					[ident[sqrt] name["sqrt"] Type [QuSqrt]]
 				]
 				let ident = m.add_trait::<Type>()?;
-				m.add_function_to_class(ident, name,
-					[ident, ident], ident,
-					&|api| { (EMPTY_FN)(api) }
-				)?;
+				m.add_trait_function(ident, name, [ident, ident], ident)?;
 			);
 			duplicate!(
 				[
 					[ident[lesser] name["lesser"] Type [QuLesser]]
+					[ident[lessereq] name["lessereq"] Type [QuLesserEq]]
 					[ident[greater] name["greater"] Type [QuGreater]]
+					[ident[greatereq] name["greatereq"] Type [QuGreaterEq]]
 					[ident[equal] name["equal"] Type [QuEqual]]
 					[ident[not_equal] name["not_equal"] Type [QuNotEqual]]
 				]
 				let ident = m.add_trait::<Type>()?;
-				m.add_function_to_class(ident, name,
-					[ident, ident], bool,
-					&|api| { (EMPTY_FN)(api) }
-				)?;
+				m.add_trait_function(ident, name, [ident, ident], bool)?;
 			);
+			let neg = m.add_trait::<QuNeg>()?;
+			m.add_trait_function(neg, "neg", [neg], neg)?;
+			let not = m.add_trait::<QuNot>()?;
+			m.add_trait_function(not, "not", [not], bool)?;
+			let iterator = m.add_trait::<QuIterator>()?;
+			m.add_trait_function(iterator, "next", [iterator], bool)?;
+			m.get_definitions_mut().set_mutable_self(iterator, "next")?;
+			let hash = m.add_trait::<QuHash>()?;
+			m.add_trait_function(hash, "hash", [hash], int)?;
 
 			// Constants
 			m.add_constant("PI", 3)?;
@@ -130,7 +145,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[],
 					float,
 					&|api| {
-						api.set::<Float>(0f32);
+						api.set::<Float>(0f32)?;
 						Ok(())
 					}
 				)?;
@@ -138,7 +153,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[float],
 					float,
 					&|api| {
-						api.set::<Float>(*api.get::<Float>(0)?);
+						api.set::<Float>(*api.get::<Float>(0)?)?;
 						Ok(())
 					}
 				)?;
@@ -146,7 +161,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[int],
 					float,
 					&|api| {
-						api.set::<Float>(*api.get::<Int>(0)? as Float);
+						api.set::<Float>(*api.get::<Int>(0)? as Float)?;
 						Ok(())
 					}
 				)?;
@@ -154,14 +169,38 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[bool],
 					float,
 					&|api| {
-						api.set::<Float>(*api.get::<Bool>(0)? as Int as Float);
+						api.set::<Float>(*api.get::<Bool>(0)? as Int as Float)?;
 						Ok(())
 					}
 				)?;
 				qufn!(m, api, copy(float) float {
-					api.set::<Float>(*api.get::<Float>(0)?);
+					api.set::<Float>(*api.get::<Float>(0)?)?;
 					Ok(())
 				});
+				m.implement(pow, float)?;
+				m.implement_function(
+					pow,
+					float,
+					"pow", [float, float], float,
+					&|api| {
+						let value = api.get::<Float>(0)?
+							.powf(*api.get::<Float>(1)?);
+						api.set::<Float>(value)?;
+						Ok(())
+					},
+				)?;
+				m.implement(sqrt, float)?;
+				m.implement_function(
+					sqrt,
+					float,
+					"sqrt", [float, float], float,
+					&|api| {
+						let value = api.get::<Float>(0)?
+							.powf(1.0 / *api.get::<Float>(1)?);
+						api.set::<Float>(value)?;
+						Ok(())
+					},
+				)?;
 			}
 
 			{ // int
@@ -169,7 +208,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[],
 					int,
 					&|api| {
-						api.set::<Int>(0);
+						api.set::<Int>(0)?;
 						Ok(())
 					}
 				)?;
@@ -177,7 +216,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[int],
 					int,
 					&|api| {
-						api.set::<Int>(*api.get::<Int>(0)?);
+						api.set::<Int>(*api.get::<Int>(0)?)?;
 						Ok(())
 					}
 				)?;
@@ -185,7 +224,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[bool],
 					int,
 					&|api| {
-						api.set::<Int>(*api.get::<Bool>(0)? as Int);
+						api.set::<Int>(*api.get::<Bool>(0)? as Int)?;
 						Ok(())
 					}
 				)?;
@@ -193,14 +232,52 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[float],
 					int,
 					&|api| {
-						api.set::<Int>(*api.get::<f32>(0)? as Int);
+						api.set::<Int>(*api.get::<f32>(0)? as Int)?;
 						Ok(())
 					}
 				)?;
 				qufn!(m, api, copy(int) int {
-					api.set::<Int>(*api.get::<Int>(0)?);
+					api.set::<Int>(*api.get::<Int>(0)?)?;
 					Ok(())
 				});
+				m.implement(pow, int)?;
+				m.implement_function(
+					pow,
+					int,
+					"pow", [int, int], int,
+					&|api| {
+						let base = *api.get::<Int>(0)?;
+						let exponent = *api.get::<Int>(1)?;
+						let value = if exponent >= 0 {
+							base.checked_pow(exponent as u32).ok_or_else(|| {
+								int_op_error(base, "**", exponent)
+							})?
+						} else if base == 0 {
+							return Err(QuMsg::division_by_zero());
+						} else {
+							// Integer division truncates the fraction, and
+							// powers too big for an int truncate to zero
+							base.checked_pow(exponent.unsigned_abs())
+								.map_or(0, |power| 1 / power)
+						};
+						api.set::<Int>(value)?;
+						Ok(())
+					},
+				)?;
+				m.implement(sqrt, int)?;
+				m.implement_function(
+					sqrt,
+					int,
+					"sqrt", [int, int], int,
+					&|api| {
+						let value = int_root(
+							*api.get::<Int>(0)?,
+							*api.get::<Int>(1)?,
+						)?;
+						api.set::<Int>(value)?;
+						Ok(())
+					},
+				)?;
 			}
 			
 			{ // bool
@@ -208,7 +285,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[],
 					bool,
 					&|api| {
-						api.set::<Bool>(false);
+						api.set::<Bool>(false)?;
 						Ok(())
 					}
 				)?;
@@ -216,7 +293,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[bool],
 					bool,
 					&|api| {
-						api.set::<Bool>(*api.get::<Bool>(0)?);
+						api.set::<Bool>(*api.get::<Bool>(0)?)?;
 						Ok(())
 					}
 				)?;
@@ -224,7 +301,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[int],
 					bool,
 					&|api| {
-						api.set::<Bool>(*api.get::<Int>(0)? != 0);
+						api.set::<Bool>(*api.get::<Int>(0)? != 0)?;
 						Ok(())
 					}
 				)?;
@@ -232,19 +309,31 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 					[float],
 					bool,
 					&|api| {
-						api.set::<Bool>(*api.get::<Float>(0)? != 0f32);
+						api.set::<Bool>(*api.get::<Float>(0)? != 0f32)?;
 						Ok(())
 					}
 				)?;
 				qufn!(m, api, copy(bool) bool {
-					api.set::<Bool>(*api.get::<Bool>(0)?);
+					api.set::<Bool>(*api.get::<Bool>(0)?)?;
 					Ok(())
 				});
+				m.implement(not, bool)?;
+				m.implement_function(
+					not,
+					bool,
+					"not", [bool], bool,
+					&|api| {
+						let value = !*api.get::<Bool>(0)?;
+						api.set_hold(value);
+						api.set::<Bool>(value)?;
+						Ok(())
+					},
+				)?;
 			}
 
 			{ // class
 				qufn!(m, api, copy(class) class {
-					api.set::<Class>(*api.get::<Class>(0)?);
+					api.set::<Class>(*api.get::<Class>(0)?)?;
 					Ok(())
 				});
 				m.implement(equal, class)?;
@@ -260,7 +349,7 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 							&api.vm.definitions,
 						);
 						api.set_hold(value);
-						api.set::<Bool>(value);
+						api.set::<Bool>(value)?;
 						Ok(())
 					},
 				)?;
@@ -268,37 +357,232 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 
 			{ // module
 				qufn!(m, api, copy(module) module {
-					api.set::<Module>(*api.get::<Module>(0)?);
+					api.set::<Module>(*api.get::<Module>(0)?)?;
 					Ok(())
 				});
 			}
 
-			// Implement trait functions traits in classes
-			duplicate!(
-				[
-					[class_id [float] OpType [Float]]
-					[class_id [int] OpType [Int]]
-				]
+			{ // str
+				m.add_class_static_function(string, CONSTRUCTOR_NAME,
+					[],
+					string,
+					&|api| {
+						api.set::<Str>(Str::default())?;
+						Ok(())
+					}
+				)?;
+				m.add_class_static_function(string, CONSTRUCTOR_NAME,
+					[string],
+					string,
+					&|api| {
+						let value = api.get::<Str>(0)?.clone();
+						api.set::<Str>(value)?;
+						Ok(())
+					}
+				)?;
+				m.add_class_static_function(string, CONSTRUCTOR_NAME,
+					[int],
+					string,
+					&|api| {
+						let value = api.get::<Int>(0)?.to_string();
+						api.set::<Str>(value.into())?;
+						Ok(())
+					}
+				)?;
+				m.add_class_static_function(string, CONSTRUCTOR_NAME,
+					[float],
+					string,
+					&|api| {
+						let value = format!("{:?}", api.get::<Float>(0)?);
+						api.set::<Str>(value.into())?;
+						Ok(())
+					}
+				)?;
+				m.add_class_static_function(string, CONSTRUCTOR_NAME,
+					[bool],
+					string,
+					&|api| {
+						let value = api.get::<Bool>(0)?.to_string();
+						api.set::<Str>(value.into())?;
+						Ok(())
+					}
+				)?;
+				qufn!(m, api, copy(string) string {
+					let value = api.get::<Str>(0)?.clone();
+					api.set::<Str>(value)?;
+					Ok(())
+				});
+				qufn!(m, api, len(string) int {
+					let value = api.get::<Str>(0)?.chars().count() as Int;
+					api.set::<Int>(value)?;
+					Ok(())
+				});
+				m.implement(add, string)?;
+				m.implement_function(
+					add,
+					string,
+					"add", [string, string], string,
+					&|api| {
+						let value = format!(
+							"{}{}",
+							api.get::<Str>(0)?,
+							api.get::<Str>(1)?,
+						);
+						api.set::<Str>(value.into())?;
+						Ok(())
+					},
+				)?;
 				duplicate!(
 					[
-						[trait_id [add] fn_name ["add"] op [+] Ret [OpType]]
-						[trait_id [sub] fn_name ["sub"] op [-] Ret [OpType]]
-						[trait_id [mul] fn_name ["mul"] op [*] Ret [OpType]]
-						[trait_id [div] fn_name ["div"] op [/] Ret [OpType]]
+						[trait_id [equal] fn_name ["equal"] op [==]]
+						[trait_id [not_equal] fn_name ["not_equal"] op [!=]]
 					]
-					m.implement(trait_id, class_id)?;
+					m.implement(trait_id, string)?;
 					m.implement_function(
 						trait_id,
-						class_id,
-						fn_name, [class_id, class_id], class_id,
+						string,
+						fn_name, [string, string], bool,
 						&|api| {
-							api.set::<Ret>(
-								*api.get::<OpType>(0)? op *api.get::<OpType>(1)?
-							);
+							let value = api.get::<Str>(0)?
+								op api.get::<Str>(1)?;
+							api.set_hold(value);
+							api.set::<Bool>(value)?;
 							Ok(())
 						},
 					)?;
-				)
+				);
+			}
+
+			{ // list
+				m.add_list_class::<Int>()?;
+				m.add_list_class::<Float>()?;
+				m.add_list_class::<Bool>()?;
+				m.add_list_class::<Str>()?;
+			}
+
+			// Hashing
+			duplicate!(
+				[
+					[class_id [int] Type [Int]]
+					[class_id [bool] Type [Bool]]
+					[class_id [string] Type [Str]]
+				]
+				m.implement(hash, class_id)?;
+				m.implement_function(
+					hash,
+					class_id,
+					"hash", [class_id], int,
+					&|api| {
+						let value = api.get::<Type>(0)?.hash_key();
+						api.set::<Int>(value)?;
+						Ok(())
+					},
+				)?;
+			);
+
+			{ // map
+				duplicate!(
+					[
+						[Key [Int]]
+						[Key [Bool]]
+						[Key [Str]]
+					]
+					duplicate!{
+						[
+							[Value [Int]]
+							[Value [Float]]
+							[Value [Bool]]
+							[Value [Str]]
+						]
+						m.add_map_class::<Key, Value>()?;
+					}
+				);
+			}
+
+			{ // range
+				let range = m.add_class::<Range>()?;
+				qufn!(m, api, range(int) range {
+					api.set::<Range>(Range::new(0, *api.get::<Int>(0)?))?;
+					Ok(())
+				});
+				qufn!(m, api, range(int, int) range {
+					let start = *api.get::<Int>(0)?;
+					let end = *api.get::<Int>(1)?;
+					api.set::<Range>(Range::new(start, end))?;
+					Ok(())
+				});
+				qufn!(m, api, copy(range) range {
+					api.set::<Range>(*api.get::<Range>(0)?)?;
+					Ok(())
+				});
+				m.add_function_to_class(range, "current",
+					[range],
+					int,
+					&|api| {
+						api.set::<Int>(api.get::<Range>(0)?.current())?;
+						Ok(())
+					}
+				)?;
+				m.implement(iterator, range)?;
+				m.implement_function(
+					iterator,
+					range,
+					"next", [range], bool,
+					&|api| {
+						let value = api.get_mut::<Range>(0)?.advance();
+						api.set::<Bool>(value)?;
+						Ok(())
+					},
+				)?;
+			}
+
+			// Implement trait functions traits in classes
+			duplicate!(
+				[
+					[trait_id [add] fn_name ["add"] op [+]]
+					[trait_id [sub] fn_name ["sub"] op [-]]
+					[trait_id [mul] fn_name ["mul"] op [*]]
+					[trait_id [div] fn_name ["div"] op [/]]
+					[trait_id [modulo] fn_name ["mod"] op [%]]
+				]
+				m.implement(trait_id, float)?;
+				m.implement_function(
+					trait_id,
+					float,
+					fn_name, [float, float], float,
+					&|api| {
+						api.set::<Float>(
+							*api.get::<Float>(0)? op *api.get::<Float>(1)?
+						)?;
+						Ok(())
+					},
+				)?;
+			);
+			// Int operations that can fail return an error instead of
+			// panicking
+			duplicate!(
+				[
+					[trait_id [add] fn_name ["add"] op [+] method [checked_add]]
+					[trait_id [sub] fn_name ["sub"] op [-] method [checked_sub]]
+					[trait_id [mul] fn_name ["mul"] op [*] method [checked_mul]]
+					[trait_id [div] fn_name ["div"] op [/] method [checked_div]]
+					[trait_id [modulo] fn_name ["mod"] op [%] method [checked_rem]]
+				]
+				m.implement(trait_id, int)?;
+				m.implement_function(
+					trait_id,
+					int,
+					fn_name, [int, int], int,
+					&|api| {
+						let left = *api.get::<Int>(0)?;
+						let right = *api.get::<Int>(1)?;
+						let value = left.method(right).ok_or_else(|| {
+							int_op_error(left, stringify!(op), right)
+						})?;
+						api.set::<Int>(value)?;
+						Ok(())
+					},
+				)?;
 			);
 			duplicate!(
 				[
@@ -310,7 +594,9 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 						[trait_id [equal] fn_name ["equal"] op [==] Ret [Bool]]
 						[trait_id [not_equal] fn_name ["not_equal"] op [!=] Ret [Bool]]
 						[trait_id [greater] fn_name ["greater"] op [>] Ret [Bool]]
+						[trait_id [greatereq] fn_name ["greatereq"] op [>=] Ret [Bool]]
 						[trait_id [lesser] fn_name ["lesser"] op [<] Ret [Bool]]
+						[trait_id [lessereq] fn_name ["lessereq"] op [<=] Ret [Bool]]
 					]
 					m.implement(trait_id, class_id)?;
 					m.implement_function(
@@ -321,18 +607,81 @@ pub fn fundamentals_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 							let value = *api.get::<OpType>(0)?
 								op *api.get::<OpType>(1)?;
 							api.set_hold(value);
-							api.set::<Ret>(value);
+							api.set::<Ret>(value)?;
 							Ok(())
 						},
 					)?;
 				)
 			);
+			m.implement(neg, float)?;
+			m.implement_function(
+				neg,
+				float,
+				"neg", [float], float,
+				&|api| {
+					api.set::<Float>(-*api.get::<Float>(0)?)?;
+					Ok(())
+				},
+			)?;
+			m.implement(neg, int)?;
+			m.implement_function(
+				neg,
+				int,
+				"neg", [int], int,
+				&|api| {
+					let value = *api.get::<Int>(0)?;
+					let negated = value.checked_neg().ok_or_else(|| {
+						QuMsg::int_overflow(&format!("-({value})"))
+					})?;
+					api.set::<Int>(negated)?;
+					Ok(())
+				},
+			)?;
 			Ok(())
 		}
 	)?;
 	Ok(())
 }
 
+/// Returns the error of an int operation that has no result, which is either
+/// a division by zero or an overflow.
+fn int_op_error(left:Int, operator:&str, right:Int) -> QuMsg {
+	if right == 0 && (operator == "/" || operator == "%") {
+		return QuMsg::division_by_zero();
+	}
+	return QuMsg::int_overflow(&format!("{left} {operator} {right}"));
+}
+
+
+/// Returns the `degree`-th root of `value`, rounded toward zero.
+fn int_root(value:Int, degree:Int) -> Result<Int, QuMsg> {
+	if degree <= 0 {
+		return Err(QuMsg::of_kind(QuErrorKind::Runtime, format!(
+			"Can't take the root of degree {degree} of an int."
+		)));
+	}
+	if value < 0 && degree % 2 == 0 {
+		return Err(QuMsg::of_kind(QuErrorKind::Runtime, format!(
+			"Can't take an even root of the negative int {value}."
+		)));
+	}
+
+	// Estimate with floats, then correct the estimate's rounding errors
+	let magnitude = (value as i64).abs();
+	let fits = |root:i64| {
+		root.checked_pow(degree as u32).is_some_and(|x| x <= magnitude)
+	};
+	let mut root = (magnitude as f64).powf(1.0 / degree as f64).round() as i64;
+	while root > 0 && !fits(root) {
+		root -= 1;
+	}
+	while fits(root + 1) {
+		root += 1;
+	}
+	return Ok((root * (value as i64).signum()) as Int);
+}
+
+
 /// A method for registering the math module in Qu.
 pub fn math_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 	registerer.add_module(
@@ -349,7 +698,7 @@ pub fn math_module(registerer: &mut Registerer) -> Result<(), QuMsg> {
 				int,
 				&|api:&mut ArgsAPI| {
 					let first:Int = *api.get(0)?;
-					api.set::<>(first);
+					api.set::<>(first)?;
 					Ok(())
 				}
 			)?;
@@ -368,10 +717,17 @@ duplicate!(
 		[ClassName ["Div"] InternalName [QuDiv]]
 		[ClassName ["Pow"] InternalName [QuPow]]
 		[ClassName ["Mod"] InternalName [QuMod]]
+		[ClassName ["Sqrt"] InternalName [QuSqrt]]
 		[ClassName ["Greater"] InternalName [QuGreater]]
+		[ClassName ["GreaterEq"] InternalName [QuGreaterEq]]
 		[ClassName ["Lesser"] InternalName [QuLesser]]
+		[ClassName ["LesserEq"] InternalName [QuLesserEq]]
 		[ClassName ["Equal"] InternalName [QuEqual]]
 		[ClassName ["NotEqual"] InternalName [QuNotEqual]]
+		[ClassName ["Neg"] InternalName [QuNeg]]
+		[ClassName ["Not"] InternalName [QuNot]]
+		[ClassName ["Iterator"] InternalName [QuIterator]]
+		[ClassName ["Hash"] InternalName [QuHash]]
 	]
 	/// The ClassName trait for Qu
 	pub struct InternalName {}
@@ -408,6 +764,744 @@ pub struct Function { // TODO: Lock modules to the vm they came from.
 }
 
 
+/// A function held as a value, along with the values it captured.
+/// 
+/// Scripts make callables from lambdas and from named functions. A host can
+/// keep the callables it's given and call them later with
+/// [`Qu::invoke`](crate::Qu::invoke).
+#[derive(Debug, Clone)]
+pub struct Callable {
+	pub(crate) fn_id: FunctionId,
+	/// The [`Uuid`] of the Qu instance whose function this is.
+	pub(crate) uuid: Uuid,
+	/// The captured values. They're passed to the function after the
+	/// arguments of each call.
+	pub(crate) captures: Rc<[Capture]>,
+} impl Register for Callable {
+	fn name() -> &'static str {"__Callable__"}
+}
+
+
+/// A value captured by a [`Callable`], or held by a collection of a class
+/// that has no Rust type (See [`DynFunction`]).
+/// 
+/// The capture owns a copy of the value, so it lives on after the variable
+/// it was captured from is gone.
+#[derive(Debug)]
+pub(crate) struct Capture {
+	/// The bytes of the value, as they're passed to the function.
+	pub(crate) bytes: Box<[u8]>,
+	/// Keeps the resources that `bytes` point to alive, such as the text of
+	/// a [`Str`].
+	pub(crate) _values: Vec<Box<dyn Any>>,
+}
+
+
+/// Qu's string type.
+/// 
+/// The text is reference counted and never changes, so copies of a [`Str`]
+/// share the same text.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Str(pub(crate) Rc<str>);
+impl Str {
+	/// Returns the text of the string.
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+} impl Register for Str {
+	fn name() -> &'static str {"str"}
+} impl Deref for Str {
+	type Target = str;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+} impl Display for Str {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", &*self.0)
+	}
+} impl From<&str> for Str {
+	fn from(value: &str) -> Self {
+		Self(value.into())
+	}
+} impl From<String> for Str {
+	fn from(value: String) -> Self {
+		Self(value.into())
+	}
+} impl PartialEq<str> for Str {
+	fn eq(&self, other: &str) -> bool {
+		&*self.0 == other
+	}
+} impl PartialEq<&str> for Str {
+	fn eq(&self, other: &&str) -> bool {
+		&*self.0 == *other
+	}
+}
+
+
+/// An iterator over the ints from a start up to, but not including, an end.
+/// 
+/// Made in Qu with `range(end)` or `range(start, end)`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Range {
+	/// The next int to iterate over.
+	start: Int,
+	end: Int,
+	/// The int the range is at.
+	current: Int,
+} impl Range {
+	/// Constructs a new [`Range`] from `start` up to `end`.
+	pub fn new(start:Int, end:Int) -> Self {
+		Self { start, end, current: start }
+	}
+
+	/// Moves the range to its next int. Returns false if there are none left.
+	pub fn advance(&mut self) -> bool {
+		if self.start >= self.end {
+			return false;
+		}
+		self.current = self.start;
+		self.start += 1;
+		return true;
+	}
+
+	/// Returns the int the range is at.
+	pub fn current(&self) -> Int {
+		self.current
+	}
+} impl Register for Range {
+	fn name() -> &'static str {"Range"}
+}
+
+
+/// Qu's list type, made in Qu with a list literal (Ex: `[1, 2, 3]`).
+/// 
+/// Lists hold items of a single type, and each type of item has its own list
+/// class (Ex: `List[int]`). Copies of a [`List`] share the same items, so
+/// changes made through one copy are seen by all of them.
+#[derive(Debug)]
+pub struct List<T>(Rc<RefCell<Vec<T>>>);
+impl<T:Clone> List<T> {
+	/// Constructs a new empty [`List`].
+	pub fn new() -> Self {
+		Self(Default::default())
+	}
+
+	/// Returns the number of items in the list.
+	pub fn len(&self) -> usize {
+		self.0.borrow().len()
+	}
+
+	/// Returns true if the list has no items.
+	pub fn is_empty(&self) -> bool {
+		self.0.borrow().is_empty()
+	}
+
+	/// Returns a copy of the item at `index`, if there is one.
+	pub fn get(&self, index:usize) -> Option<T> {
+		self.0.borrow().get(index).cloned()
+	}
+
+	/// Adds an item to the end of the list.
+	pub fn push(&self, item:T) {
+		self.0.borrow_mut().push(item);
+	}
+
+	/// Removes the last item of the list and returns it.
+	pub fn pop(&self) -> Option<T> {
+		self.0.borrow_mut().pop()
+	}
+
+	/// Returns a copy of the items in the list.
+	pub fn to_vec(&self) -> Vec<T> {
+		self.0.borrow().clone()
+	}
+
+	/// Returns the item at a Qu index, or an error if it's out of bounds.
+	pub(crate) fn get_item(&self, index:Int) -> Result<T, QuMsg> {
+		usize::try_from(index).ok()
+			.and_then(|i| self.get(i))
+			.ok_or_else(|| QuMsg::index_out_of_bounds(index, self.len()))
+	}
+
+	/// Replaces the item at a Qu index, or returns an error if it's out of
+	/// bounds.
+	pub(crate) fn set_item(&self, index:Int, item:T) -> Result<(), QuMsg> {
+		let mut items = self.0.borrow_mut();
+		let len = items.len();
+		let slot = usize::try_from(index).ok()
+			.and_then(|i| items.get_mut(i))
+			.ok_or_else(|| QuMsg::index_out_of_bounds(index, len))?;
+		*slot = item;
+		Ok(())
+	}
+} impl<T> Clone for List<T> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+} impl<T:Clone> Default for List<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+} impl<T:Clone> From<Vec<T>> for List<T> {
+	fn from(value: Vec<T>) -> Self {
+		Self(Rc::new(RefCell::new(value)))
+	}
+} impl<T:Register+Clone> Register for List<T> {
+	fn name() -> &'static str {
+		generic_name::<Self>(|| format!("List[{}]", T::name()))
+	}
+}
+
+
+/// An iterator over the items of a [`List`], made in Qu by iterating over a
+/// list (Ex: `for item in items:`).
+#[derive(Debug, Clone)]
+pub struct ListIterator<T> {
+	list: List<T>,
+	/// The index of the next item.
+	next: usize,
+} impl<T:Clone> ListIterator<T> {
+	/// Constructs a new [`ListIterator`] at the start of `list`.
+	pub fn new(list:List<T>) -> Self {
+		Self { list, next: 0 }
+	}
+
+	/// Moves the iterator to its next item. Returns false if there are none
+	/// left.
+	pub fn advance(&mut self) -> bool {
+		if self.next >= self.list.len() {
+			return false;
+		}
+		self.next += 1;
+		return true;
+	}
+
+	/// Returns the item the iterator is at.
+	pub fn current(&self) -> Option<T> {
+		self.list.get(self.next.checked_sub(1)?)
+	}
+} impl<T:Register+Clone> Register for ListIterator<T> {
+	fn name() -> &'static str {
+		generic_name::<Self>(|| format!("ListIterator[{}]", T::name()))
+	}
+}
+
+
+/// A value that can be used as the key of a [`Map`].
+/// 
+/// This is the Rust side of Qu's `Hash` trait, whose `hash` function returns
+/// the same value as [`MapKey::hash_key`]. Maps with keys of other classes,
+/// such as classes declared in scripts, call the class's `Hash` and `Equal`
+/// implementations instead (See [`DynFunction`]).
+pub trait MapKey: Register + Clone + PartialEq + Display {
+	/// Returns the hash of the key. Keys that are equal have equal hashes.
+	fn hash_key(&self) -> Int;
+} impl MapKey for Int {
+	fn hash_key(&self) -> Int {
+		*self
+	}
+} impl MapKey for Bool {
+	fn hash_key(&self) -> Int {
+		*self as Int
+	}
+} impl MapKey for Str {
+	fn hash_key(&self) -> Int {
+		let mut hasher = DefaultHasher::new();
+		self.as_str().hash(&mut hasher);
+		hasher.finish() as Int
+	}
+}
+
+
+/// Qu's map type, made in Qu with a map literal (Ex: `{"a": 1, "b": 2}`).
+/// 
+/// Maps hold keys of a single type and values of a single type, and each pair
+/// of types has its own map class (Ex: `Map[str, int]`). Keys are kept in the
+/// order they were first inserted. Copies of a [`Map`] share the same
+/// entries, like copies of a [`List`].
+#[derive(Debug)]
+pub struct Map<K, V>(Rc<RefCell<MapEntries<K, V>>>);
+impl<K:MapKey, V:Clone> Map<K, V> {
+	/// Constructs a new empty [`Map`].
+	pub fn new() -> Self {
+		Self(Rc::new(RefCell::new(MapEntries::new())))
+	}
+
+	/// Returns the number of entries in the map.
+	pub fn len(&self) -> usize {
+		self.0.borrow().entries.len()
+	}
+
+	/// Returns true if the map has no entries.
+	pub fn is_empty(&self) -> bool {
+		self.0.borrow().entries.is_empty()
+	}
+
+	/// Returns true if the map has an entry for `key`.
+	pub fn contains_key(&self, key:&K) -> bool {
+		self.0.borrow().find(key).is_some()
+	}
+
+	/// Returns a copy of the value of `key`, if there is one.
+	pub fn get(&self, key:&K) -> Option<V> {
+		let entries = self.0.borrow();
+		let index = entries.find(key)?;
+		Some(entries.entries[index].1.clone())
+	}
+
+	/// Sets the value of `key`, returning its old value if it had one.
+	pub fn insert(&self, key:K, value:V) -> Option<V> {
+		let mut entries = self.0.borrow_mut();
+		if let Some(index) = entries.find(&key) {
+			return Some(std::mem::replace(&mut entries.entries[index].1, value));
+		}
+		entries.push(key.hash_key(), key, value);
+		None
+	}
+
+	/// Removes the entry of `key`, returning its value if it had one.
+	pub fn remove(&self, key:&K) -> Option<V> {
+		let mut entries = self.0.borrow_mut();
+		let index = entries.find(key)?;
+		Some(entries.remove(key.hash_key(), index).1)
+	}
+
+	/// Returns a copy of the keys of the map.
+	pub fn keys(&self) -> Vec<K> {
+		self.0.borrow().entries.iter().map(|(key, _)| key.clone()).collect()
+	}
+
+	/// Returns a copy of the entries of the map.
+	pub fn to_vec(&self) -> Vec<(K, V)> {
+		self.0.borrow().entries.clone()
+	}
+
+	/// Returns the value of `key`, or an error if it isn't in the map.
+	pub(crate) fn get_item(&self, key:&K) -> Result<V, QuMsg> {
+		self.get(key).ok_or_else(|| QuMsg::missing_key(&key.to_string()))
+	}
+} impl<K, V> Clone for Map<K, V> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+} impl<K:MapKey, V:Clone> Default for Map<K, V> {
+	fn default() -> Self {
+		Self::new()
+	}
+} impl<K:MapKey, V:Clone> From<Vec<(K, V)>> for Map<K, V> {
+	fn from(value: Vec<(K, V)>) -> Self {
+		let map = Self::new();
+		for (key, value) in value {
+			map.insert(key, value);
+		}
+		map
+	}
+} impl<K:MapKey, V:Register+Clone> Register for Map<K, V> {
+	fn name() -> &'static str {
+		generic_name::<Self>(|| format!("Map[{}, {}]", K::name(), V::name()))
+	}
+}
+
+
+/// The entries of a [`Map`], with the indexes of the entries grouped by the
+/// hash of their keys.
+#[derive(Debug)]
+struct MapEntries<K, V> {
+	entries: Vec<(K, V)>,
+	buckets: HashMap<Int, Vec<usize>>,
+} impl<K, V> MapEntries<K, V> {
+	fn new() -> Self {
+		Self {
+			entries: vec![],
+			buckets: HashMap::new(),
+		}
+	}
+
+	/// Returns the indexes of the entries whose keys have the hash `hash`.
+	fn bucket(&self, hash:Int) -> &[usize] {
+		self.buckets.get(&hash).map_or(&[], |bucket| bucket)
+	}
+
+	/// Adds an entry whose key has the hash `hash`.
+	fn push(&mut self, hash:Int, key:K, value:V) {
+		self.buckets.entry(hash).or_default().push(self.entries.len());
+		self.entries.push((key, value));
+	}
+
+	/// Removes the entry at `removed`, whose key has the hash `hash`, and
+	/// moves the indexes of the entries after it back by one.
+	fn remove(&mut self, hash:Int, removed:usize) -> (K, V) {
+		if let Some(bucket) = self.buckets.get_mut(&hash) {
+			bucket.retain(|index| *index != removed);
+			if bucket.is_empty() {
+				self.buckets.remove(&hash);
+			}
+		}
+		for bucket in self.buckets.values_mut() {
+			for index in bucket.iter_mut() {
+				if *index > removed {
+					*index -= 1;
+				}
+			}
+		}
+		self.entries.remove(removed)
+	}
+} impl<K:MapKey, V> MapEntries<K, V> {
+	/// Returns the index of the entry of `key`.
+	fn find(&self, key:&K) -> Option<usize> {
+		self.bucket(key.hash_key())
+			.iter()
+			.copied()
+			.find(|index| self.entries[*index].0 == *key)
+	}
+}
+
+
+/// The Rust type of lists of classes that have no Rust type. See
+/// [`DynFunction`].
+pub(crate) type DynList = List<Rc<Capture>>;
+/// The Rust type of iterators over a [`DynList`].
+pub(crate) type DynListIterator = ListIterator<Rc<Capture>>;
+/// The Rust type of maps whose keys or values are of classes that have no
+/// Rust type. See [`DynFunction`].
+pub(crate) type DynMap = Map<Rc<Capture>, Rc<Capture>>;
+
+
+/// The functions of the list, list iterator and map classes that are defined
+/// for classes without a Rust type, such as classes declared in scripts and
+/// tuples (Ex: `List[(int, int)]`, `Map[Tile, str]`).
+/// 
+/// Their items, keys and values are kept off the stack as [`Capture`]s. Keys
+/// are hashed and compared by calling their class's implementations of
+/// `Hash` and `Equal`, so any class that implements both can be a key.
+/// Unlike other external functions, these are saved to `.quc` files, by
+/// their position in [`DynFunction::ALL`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DynFunction {
+	ListNew,
+	ListCopy,
+	ListLen,
+	ListAppend,
+	ListPop,
+	ListGet,
+	ListSet,
+	ListIter,
+	IteratorCopy,
+	IteratorCurrent,
+	IteratorNext,
+	MapNew,
+	MapCopy,
+	MapLen,
+	MapHas,
+	MapRemove,
+	MapKeys,
+	MapIter,
+	MapGet,
+	MapSet,
+} impl DynFunction {
+	/// Every function, in the order they're saved in.
+	pub(crate) const ALL:[Self; 20] = [
+		Self::ListNew,
+		Self::ListCopy,
+		Self::ListLen,
+		Self::ListAppend,
+		Self::ListPop,
+		Self::ListGet,
+		Self::ListSet,
+		Self::ListIter,
+		Self::IteratorCopy,
+		Self::IteratorCurrent,
+		Self::IteratorNext,
+		Self::MapNew,
+		Self::MapCopy,
+		Self::MapLen,
+		Self::MapHas,
+		Self::MapRemove,
+		Self::MapKeys,
+		Self::MapIter,
+		Self::MapGet,
+		Self::MapSet,
+	];
+
+
+	/// Calls the function with the arguments and output of `api`.
+	pub(crate) fn call(self, api:&mut ArgsAPI) -> Result<(), QuMsg> {
+		match self {
+			Self::ListNew => api.set_dyn(DynList::new()),
+			Self::ListCopy => {
+				let list = api.get_dyn::<DynList>(0)?.clone();
+				api.set_dyn(list);
+			},
+			Self::ListLen => {
+				let len = api.get_dyn::<DynList>(0)?.len() as Int;
+				api.set::<Int>(len)?;
+			},
+			Self::ListAppend => {
+				let item = Rc::new(api.capture_arg(1)?);
+				api.get_dyn::<DynList>(0)?.push(item);
+			},
+			Self::ListPop => {
+				let item = api.get_dyn::<DynList>(0)?
+					.pop()
+					.ok_or_else(QuMsg::pop_from_empty_list)?;
+				api.set_captured(&item)?;
+			},
+			Self::ListGet => {
+				let index = *api.get::<Int>(1)?;
+				let item = api.get_dyn::<DynList>(0)?.get_item(index)?;
+				api.set_captured(&item)?;
+			},
+			Self::ListSet => {
+				let item = Rc::new(api.capture_arg(2)?);
+				let index = *api.get::<Int>(1)?;
+				api.get_dyn::<DynList>(0)?.set_item(index, item)?;
+			},
+			Self::ListIter => {
+				let list = api.get_dyn::<DynList>(0)?.clone();
+				api.set_dyn(DynListIterator::new(list));
+			},
+			Self::IteratorCopy => {
+				let iterator = api.get_dyn::<DynListIterator>(0)?.clone();
+				api.set_dyn(iterator);
+			},
+			Self::IteratorCurrent => {
+				let item = api.get_dyn::<DynListIterator>(0)?
+					.current()
+					.ok_or_else(|| QuMsg::of_kind(
+						QuErrorKind::Runtime,
+						"The list iterator isn't at an item.",
+					))?;
+				api.set_captured(&item)?;
+			},
+			Self::IteratorNext => {
+				let value = api.get_dyn_mut::<DynListIterator>(0)?.advance();
+				api.set::<Bool>(value)?;
+			},
+			Self::MapNew => {
+				api.set_dyn::<DynMap>(Map(Rc::new(RefCell::new(MapEntries::new()))));
+			},
+			Self::MapCopy => {
+				let map = api.get_dyn::<DynMap>(0)?.clone();
+				api.set_dyn(map);
+			},
+			Self::MapLen => {
+				let len = api.get_dyn::<DynMap>(0)?.0.borrow().entries.len();
+				api.set::<Int>(len as Int)?;
+			},
+			Self::MapHas => {
+				let (_, index) = Self::find_key(api)?;
+				api.set::<Bool>(index.is_some())?;
+			},
+			Self::MapRemove => {
+				let (hash, index) = Self::find_key(api)?;
+				if let Some(index) = index {
+					api.get_dyn::<DynMap>(0)?.0.borrow_mut().remove(hash, index);
+				}
+				api.set::<Bool>(index.is_some())?;
+			},
+			Self::MapKeys => Self::set_keys(api)?,
+			Self::MapIter => {
+				Self::set_keys(api)?;
+				let d = &api.vm.definitions;
+				let map_class = d.get_function(api.fn_id)?.identity.parameters[0];
+				let keys_class = d.get_function(
+					QuCompiler::find_method(map_class, "keys", &[map_class], d)?
+				)?.identity.return_type;
+				let iter_id = QuCompiler::find_method(
+					keys_class,
+					"iter",
+					&[keys_class],
+					d,
+				)?;
+				api.vm.call_function(iter_id, &[api.out_id], api.out_id)?;
+			},
+			Self::MapGet => {
+				let (_, index) = Self::find_key(api)?;
+				let Some(index) = index else {
+					let key_class = api.parameter_class(1)?;
+					let key = api.vm.display_value(api.arg_ids[1], key_class)?;
+					return Err(QuMsg::missing_key(&key));
+				};
+				let value = api.get_dyn::<DynMap>(0)?.0.borrow().entries[index].1.clone();
+				api.set_captured(&value)?;
+			},
+			Self::MapSet => {
+				let key = Rc::new(api.capture_arg(1)?);
+				let value = Rc::new(api.capture_arg(2)?);
+				let (hash, index) = Self::find_key(api)?;
+				let mut entries = api.get_dyn::<DynMap>(0)?.0.borrow_mut();
+				match index {
+					Some(index) => entries.entries[index].1 = value,
+					None => entries.push(hash, key, value),
+				}
+			},
+		}
+		Ok(())
+	}
+
+
+	/// Returns the hash of the key given as the second argument, and the
+	/// index of its entry in the map given as the first, if it has one.
+	fn find_key(api:&mut ArgsAPI) -> Result<(Int, Option<usize>), QuMsg> {
+		let map = api.get_dyn::<DynMap>(0)?.clone();
+		let hash = api.hash_arg(1)?;
+		// The entries aren't borrowed while the keys are compared, because
+		// comparing them can run scripts
+		let candidates:Vec<(usize, Rc<Capture>)> = {
+			let entries = map.0.borrow();
+			entries.bucket(hash)
+				.iter()
+				.map(|index| (*index, entries.entries[*index].0.clone()))
+				.collect()
+		};
+		for (index, key) in candidates {
+			if !api.arg_equals(1, &key)? {
+				continue;
+			}
+			let is_entry = map.0.borrow()
+				.entries
+				.get(index)
+				.is_some_and(|(other, _)| Rc::ptr_eq(other, &key));
+			if !is_entry {
+				return Err(QuMsg::of_kind(
+					QuErrorKind::Runtime,
+					"The map was changed while its keys were compared.",
+				));
+			}
+			return Ok((hash, Some(index)));
+		}
+		Ok((hash, None))
+	}
+
+
+	/// Sets the output to a list of the keys of the map given as the first
+	/// argument. The list is made through the functions of the class that
+	/// the map's `keys` function returns.
+	fn set_keys(api:&mut ArgsAPI) -> Result<(), QuMsg> {
+		let keys:Vec<Rc<Capture>> = api.get_dyn::<DynMap>(0)?
+			.0
+			.borrow()
+			.entries
+			.iter()
+			.map(|(key, _)| key.clone())
+			.collect();
+		let d = &api.vm.definitions;
+		let map_class = d.get_function(api.fn_id)?.identity.parameters[0];
+		let keys_class = d.get_function(
+			QuCompiler::find_method(map_class, "keys", &[map_class], d)?
+		)?.identity.return_type;
+		let key_class = d.get_function(
+			QuCompiler::find_method(keys_class, "pop", &[keys_class], d)?
+		)?.identity.return_type;
+		let constructor_id = QuCompiler::find_method(
+			keys_class,
+			CONSTRUCTOR_NAME,
+			&[],
+			d,
+		)?;
+		let append_id = QuCompiler::find_method(
+			keys_class,
+			"append",
+			&[keys_class, key_class],
+			d,
+		)?;
+
+		api.vm.call_function(constructor_id, &[], api.out_id)?;
+		// The list is passed to `append` without being copied, so that the
+		// keys are added to the output's list
+		let list = api.vm.value_bytes(api.out_id, keys_class)?.to_vec();
+		for key in keys {
+			api.vm.call_with_bytes::<()>(append_id, &[&list, &key.bytes])?;
+		}
+		Ok(())
+	}
+}
+
+
+/// A Rust tuple that a Qu tuple can be read as (Ex: `(Int, Float)` for a Qu
+/// `(int, float)`).
+/// 
+/// Implemented for the empty tuple and for tuples of up to 8 registered
+/// classes. The empty tuple stands for a list of no arguments.
+pub trait QuTuple: Sized {
+	/// Returns the classes of the tuple's elements, in order.
+	fn class_ids() -> Vec<ClassId>;
+
+	/// Reads the tuple from the registers starting at `reg`.
+	fn read(vm:&QuVm, reg:RegId) -> Result<Self, QuMsg>;
+
+	/// Writes the tuple's elements one after another starting at `reg`, and
+	/// returns the register of each.
+	fn write(self, vm:&mut QuVm, reg:RegId) -> Vec<RegId>;
+}
+impl QuTuple for () {
+	fn class_ids() -> Vec<ClassId> {
+		vec![]
+	}
+
+	fn read(_vm:&QuVm, _reg:RegId) -> Result<Self, QuMsg> {
+		Ok(())
+	}
+
+	fn write(self, _vm:&mut QuVm, _reg:RegId) -> Vec<RegId> {
+		vec![]
+	}
+}
+macro_rules! impl_qu_tuple {
+	($($T:ident $i:tt),+) => {
+		impl<$($T:Register+Clone),+> QuTuple for ($($T,)+) {
+			fn class_ids() -> Vec<ClassId> {
+				vec![$($T::id()),+]
+			}
+
+			fn read(vm:&QuVm, reg:RegId) -> Result<Self, QuMsg> {
+				// Elements are laid out one after another
+				let sizes = [$($T::size() as usize),+];
+				Ok(($(
+					vm.read::<$T>(
+						RegId(reg.0 + sizes[..$i].iter().sum::<usize>())
+					)?.clone(),
+				)+))
+			}
+
+			fn write(self, vm:&mut QuVm, reg:RegId) -> Vec<RegId> {
+				let sizes = [$($T::size() as usize),+];
+				let regs = vec![$(
+					RegId(reg.0 + sizes[..$i].iter().sum::<usize>())
+				),+];
+				$(vm.write(regs[$i], self.$i);)+
+				regs
+			}
+		}
+	};
+}
+impl_qu_tuple!(A 0);
+impl_qu_tuple!(A 0, B 1);
+impl_qu_tuple!(A 0, B 1, C 2);
+impl_qu_tuple!(A 0, B 1, C 2, D 3);
+impl_qu_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_qu_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_qu_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_qu_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+
+/// Returns the name of the generic class `T`, making it with `make` the
+/// first time.
+/// 
+/// Names are leaked, but each class only ever has one.
+fn generic_name<T:'static>(make:impl FnOnce() -> String) -> &'static str {
+	static NAMES:Lazy<RwLock<HashMap<TypeId, &'static str>>>
+		= Lazy::new(Default::default);
+	if let Some(name) = NAMES.read().unwrap().get(&TypeId::of::<T>()) {
+		return name;
+	}
+	let name = Box::leak(make().into_boxed_str());
+	NAMES.write().unwrap().insert(TypeId::of::<T>(), name);
+	name
+}
+
+
 impl Register for Bool {
 	fn name() -> &'static str {"bool"}
 }
@@ -433,6 +1527,13 @@ static ID_MAP:Lazy<RwLock<HashMap<TypeId, ClassId>>> = Lazy::new(||{
 });
 
 
+/// Returns a new [`ClassId`] that no other class uses, such as for classes
+/// declared in scripts.
+pub(crate) fn new_class_id() -> ClassId {
+	ClassId::new(ID_COUNTER.fetch_add(1, Ordering::SeqCst))
+}
+
+
 /// A trait for registering structs into the Qu programming language.
 pub trait Register: 'static {
 	/// The name of the object inferred from the source type.
@@ -460,9 +1561,7 @@ pub trait Register: 'static {
 		match id_option {
 			Some(id) => id,
 			None => {
-				let id = ClassId::new(
-					ID_COUNTER.fetch_add(1, Ordering::SeqCst)
-				);
+				let id = new_class_id();
 				ID_MAP.write().unwrap().insert(TypeId::of::<Self>(), id);
 				id
 			},
@@ -478,6 +1577,15 @@ pub trait Register: 'static {
 		let layout = Layout::from_size_align(size, 4).unwrap();
 		layout.pad_to_align().size() as u8
 	}
+
+	/// Returns the class and offset in bytes of each field of a `#[repr(C)]`
+	/// struct that mirrors a class declared in a script, in order.
+	/// 
+	/// Instances of the script class can only be read as this struct if the
+	/// fields match. Returns [`None`] by default.
+	fn fields() -> Option<Vec<(ClassId, usize)>> {
+		None
+	}
 }
 
 #[cfg(test)]
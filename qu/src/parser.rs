@@ -1,12 +1,15 @@
 
+use std::num::IntErrorKind;
 use std::vec;
 
-use crate::errors;
-use crate::TOKEN_TYPE_NAME;
-use crate::tokens::RULES;
-use crate::tokens::TOKEN_TYPE_NUMBER;
+use crate::errors::QuErrorKind;
 use crate::tokens::tokenize;
-use crate::QuToken;
+use crate::tokens::Indentation;
+use crate::tokens::QuSpan;
+use crate::tokens::QuToken;
+use crate::tokens::TokenKind;
+use crate::Float;
+use crate::Int;
 use crate::QuMsg;
 
 
@@ -15,13 +18,19 @@ pub const FLOW_TYPE_WHILE:u8 = 1;
 
 pub const KEYWORD_BOOL_TRUE:&str = "true";
 pub const KEYWORD_BOOL_FALSE:&str = "false";
+pub const KEYWORD_BREAK:&str = "break";
 pub const KEYWORD_CLASS:&str = "class";
+pub const KEYWORD_CONTINUE:&str = "continue";
 pub const KEYWORD_ELSE:&str = "else";
 pub const KEYWORD_ELIF:&str = "elif";
 pub const KEYWORD_FN:&str = "fn";
+pub const KEYWORD_FOR:&str = "for";
 pub const KEYWORD_IF:&str = "if";
+pub const KEYWORD_IMPL:&str = "impl";
 pub const KEYWORD_IMPORT:&str = "import";
+pub const KEYWORD_IN:&str = "in";
 pub const KEYWORD_RETURN:&str = "return";
+pub const KEYWORD_TRAIT:&str = "trait";
 pub const KEYWORD_VAR:&str = "var";
 pub const KEYWORD_WHILE:&str = "while";
 pub const KEYWORD_IS:&str = "is";
@@ -29,6 +38,10 @@ pub const KEYWORD_IS:&str = "is";
 pub const OP_ASSIGN_SYMBOL:&str = "=";
 pub const OP_BLOCK_START:&str = ":";
 pub const OP_DOT_INDEX:&str = ".";
+pub const OP_INDEX_OPEN:&str = "[";
+pub const OP_INDEX_CLOSE:&str = "]";
+pub const OP_MAP_OPEN:&str = "{";
+pub const OP_MAP_CLOSE:&str = "}";
 pub const OP_EXPR_ADD:&str = "+";
 pub const OP_EXPR_AND:&str = "and";
 pub const OP_EXPR_DIV:&str = "/";
@@ -40,6 +53,7 @@ pub const OP_EXPR_LSE:&str = "<=";
 pub const OP_EXPR_MOD:&str = "%";
 pub const OP_EXPR_MUL:&str = "*";
 pub const OP_EXPR_NEQ:&str = "!=";
+pub const OP_EXPR_NOT:&str = "not";
 pub const OP_EXPR_OR:&str = "or";
 pub const OP_EXPR_POW:&str = "**";
 pub const OP_EXPR_SUB:&str = "-";
@@ -50,8 +64,11 @@ pub type QuParamNode = (QuToken, Option<QuToken>);
 
 pub mod parsed {
 	use std::fmt::Display;
+	use crate::tokens::QuSpan;
 	use crate::tokens::QuToken;
 	use super::QuOperator;
+	use crate::Float;
+	use crate::Int;
 
 	#[derive(Debug, Clone, PartialEq)]
 	/// Defines an expression in a Qu program tree.
@@ -60,17 +77,138 @@ pub mod parsed {
 		/// Call function branch.
 		Call(Box<CallExpression>),
 		DotIndex(Box<DotIndex>),
+		/// An index into a value (Ex: list[0]).
+		Index(Box<IndexExpression>),
+		/// A literal list (Ex: [1, 2, 3]).
+		List(Box<ListLiteral>),
+		/// A function made as a value (Ex: fn(x int) int: ...).
+		Lambda(Box<Lambda>),
+		/// A literal map (Ex: {"a": 1, "b": 2}).
+		Map(Box<MapLiteral>),
 		/// A calculable expression. Contains an operator and two [`QuLeafExpr`]s.
 		Operation(Box<OperationExpression>),
 		/// A literal int or boolean value.
 		Bool(Box<BoolLiteral>),
 		/// A literal float value.
 		Number(Box<NumberLiteral>),
+		/// A literal string value.
+		String(Box<StringLiteral>),
 		/// A tuple.
 		Tuple(Box<TupleExpression>),
+		/// An expression with a single operand. Contains an operator and an
+		/// [`Expression`] (Ex: not x, -x).
+		Unary(Box<UnaryExpression>),
 		/// A variable name.
 		Var(Box<VarExpression>),
 	} impl Expression {
+		/// Adds the names this expression uses to `used`.
+		fn collect_names(&self, used:&mut Vec<String>) {
+			match self {
+				Expression::As(a) => a.left.collect_names(used),
+				Expression::Call(a) => {
+					match &a.caller {
+						Some(caller) => caller.collect_names(used),
+						None => push_name(used, &a.name.slice),
+					}
+					for parameter in &a.parameters.elements {
+						parameter.collect_names(used);
+					}
+				},
+				Expression::DotIndex(a) => a.left.collect_names(used),
+				Expression::Index(a) => {
+					a.left.collect_names(used);
+					a.index.collect_names(used);
+				},
+				Expression::Lambda(a) => {
+					for name in a.body.code_block.free_names() {
+						let is_parameter = a.identity.parameters.iter()
+							.any(|parameter| parameter.name() == name);
+						if !is_parameter {
+							push_name(used, &name);
+						}
+					}
+				},
+				Expression::List(a) => {
+					for element in &a.elements {
+						element.collect_names(used);
+					}
+				},
+				Expression::Map(a) => {
+					for (key, value) in &a.entries {
+						key.collect_names(used);
+						value.collect_names(used);
+					}
+				},
+				Expression::Operation(a) => {
+					a.left.collect_names(used);
+					a.right.collect_names(used);
+				},
+				Expression::Tuple(a) => {
+					for element in &a.elements {
+						element.collect_names(used);
+					}
+				},
+				Expression::Unary(a) => a.operand.collect_names(used),
+				Expression::Var(a) => push_name(used, &a.name.slice),
+				Expression::Bool(_)
+				| Expression::Number(_)
+				| Expression::String(_) => {},
+			}
+		}
+
+
+		/// Returns *true* if this expression may change the variable `name`,
+		/// which is the case for calls of its methods.
+		fn changes_var(&self, name:&str) -> bool {
+			match self {
+				Expression::As(a) => a.left.changes_var(name),
+				Expression::Call(a) => {
+					let changes_caller = a.caller.as_ref().is_some_and(|caller| {
+						caller.root_name() == Some(name)
+							|| caller.changes_var(name)
+					});
+					changes_caller || a.parameters.elements.iter()
+						.any(|parameter| parameter.changes_var(name))
+				},
+				Expression::DotIndex(a) => a.left.changes_var(name),
+				Expression::Index(a) => {
+					a.left.changes_var(name) || a.index.changes_var(name)
+				},
+				Expression::List(a) => {
+					a.elements.iter().any(|element| element.changes_var(name))
+				},
+				Expression::Map(a) => a.entries.iter().any(|(key, value)| {
+					key.changes_var(name) || value.changes_var(name)
+				}),
+				Expression::Operation(a) => {
+					a.left.changes_var(name) || a.right.changes_var(name)
+				},
+				Expression::Tuple(a) => {
+					a.elements.iter().any(|element| element.changes_var(name))
+				},
+				Expression::Unary(a) => a.operand.changes_var(name),
+				// Lambdas change copies of the values they capture
+				Expression::Lambda(_)
+				| Expression::Var(_)
+				| Expression::Bool(_)
+				| Expression::Number(_)
+				| Expression::String(_) => false,
+			}
+		}
+
+
+		/// Returns the name of the variable this expression indexes into, if
+		/// it only indexes into a variable (Ex: `a` for `a.b[0]`).
+		fn root_name(&self) -> Option<&str> {
+			match self {
+				Expression::DotIndex(a) => a.left.root_name(),
+				Expression::Index(a) => a.left.root_name(),
+				Expression::Var(a) => Some(&a.name.slice),
+				_ => None,
+			}
+		}
+
+
 		/// Attempts to convert an expression into an identity, and panics if
 		/// it can't.
 		pub fn into_identity(&self) -> &str {
@@ -80,11 +218,38 @@ pub mod parsed {
 				Expression::DotIndex(dot_index) => {
 					&dot_index.right.slice
 				},
+				Expression::Index(_) => todo!(),
+				Expression::Lambda(_) => todo!(),
+				Expression::List(_) => todo!(),
+				Expression::Map(_) => todo!(),
 				Expression::Operation(_) => todo!(),
 				Expression::Bool(_) => todo!(),
 				Expression::Tuple(_) => todo!(),
+				Expression::Unary(_) => todo!(),
 				Expression::Var(var) => &var.name.slice,
     			Expression::Number(_) => todo!(),
+				Expression::String(_) => todo!(),
+			}
+		}
+
+
+		/// Returns the part of the script this expression was parsed from.
+		pub fn span(&self) -> QuSpan {
+			match self {
+				Expression::As(a) => a.span,
+				Expression::Call(a) => a.span,
+				Expression::DotIndex(a) => a.span,
+				Expression::Index(a) => a.span,
+				Expression::Lambda(a) => a.span,
+				Expression::List(a) => a.span,
+				Expression::Map(a) => a.span,
+				Expression::Operation(a) => a.span,
+				Expression::Bool(a) => a.span,
+				Expression::Number(a) => a.span,
+				Expression::String(a) => a.span,
+				Expression::Tuple(a) => a.span,
+				Expression::Unary(a) => a.span,
+				Expression::Var(a) => a.span,
 			}
 		}
 	} impl Default for Expression {
@@ -97,11 +262,17 @@ pub mod parsed {
 				Expression::As(a) => write!(f, "{:?}", **a),
 				Expression::Call(a) => write!(f, "{:?}", **a),
 				Expression::DotIndex(a) => write!(f, "{:?}", **a),
+				Expression::Index(a) => write!(f, "{:?}", **a),
+				Expression::Lambda(a) => write!(f, "{:?}", **a),
+				Expression::List(a) => write!(f, "{:?}", **a),
+				Expression::Map(a) => write!(f, "{:?}", **a),
 				Expression::Operation(a) => write!(f, "{:?}", **a),
 				Expression::Bool(a) => write!(f, "{:?}", **a),
 				Expression::Tuple(a) => write!(f, "{:?}", **a),
+				Expression::Unary(a) => write!(f, "{:?}", **a),
 				Expression::Var(a) => write!(f, "{:?}", **a),
     			Expression::Number(a) => write!(f, "{:?}", **a),
+				Expression::String(a) => write!(f, "{:?}", **a),
 			}
 		}
 	}
@@ -110,23 +281,187 @@ pub mod parsed {
 	#[derive(Debug, Clone, PartialEq)]
 	/// Defines an expression in a Qu program tree.
 	pub enum Statement {
+		/// A break statement. Leaves the innermost loop.
+		Break(Box<LoopJump>),
+		/// A class declaration. Contains the class name, fields, and
+		/// functions.
+		ClassDeclaration(Box<ClassDeclaration>),
+		/// A continue statement. Skips to the next iteration of the innermost
+		/// loop.
+		Continue(Box<LoopJump>),
 		/// A floating expression
 		Expression(Box<Expression>),
+		/// An assignment to the field of a value. Contains a dot index and a
+		/// [`Expression`].
+		FieldAssign(Box<FieldAssignment>),
 		/// An if statement. Contains an assertion statement and a [`Vec`] of
 		/// instructions.
 		FlowStatement(Box<FlowStatement>),
+		/// A for loop. Contains the loop variable, the value iterated over,
+		/// and the loop's body.
+		ForLoop(Box<ForLoop>),
 		/// A function declaration branch. Contains the function name,
 		/// parameters, and instructions.
 		FunctionDeclaration(Box<FunctionDeclaration>),
+		/// An implementation of a trait in a class. Contains the trait name and
+		/// the functions implemented.
+		Impl(Box<ImplBlock>),
 		/// An import statement.
 		Import(Box<Import>),
+		/// An assignment to an index of a value (Ex: list[0] = 5).
+		IndexAssign(Box<IndexAssignment>),
 		/// A return statement for a function
 		Return(Box<ReturnStatement>),
+		/// A trait declaration. Contains the trait name and the signatures of
+		/// its functions.
+		TraitDeclaration(Box<TraitDeclaration>),
 		/// A variable assignment. Contains a var name and a [`QuLeafExpr`].
 		VarAssign(Box<VarAssignment>),
 		/// A variable declaration. Contains a var name, type(TODO), and
 		/// [`QuLeafExpr`].
 		VarDeclaration(Box<VarDeclaration>),
+		/// A declaration of a variable for each element of a tuple (Ex:
+		/// var (x, y) = pos).
+		VarDestructure(Box<VarDestructuring>),
+	} impl Statement {
+		/// Adds the names this statement uses to `used` and the names it
+		/// declares to `declared`.
+		fn collect_names(&self, used:&mut Vec<String>, declared:&mut Vec<String>) {
+			match self {
+				Statement::Break(_)
+				| Statement::ClassDeclaration(_)
+				| Statement::Continue(_)
+				| Statement::FunctionDeclaration(_)
+				| Statement::Impl(_)
+				| Statement::Import(_)
+				| Statement::TraitDeclaration(_) => {},
+				Statement::Expression(a) => a.collect_names(used),
+				Statement::FieldAssign(a) => {
+					a.field.left.collect_names(used);
+					a.new_value.collect_names(used);
+				},
+				Statement::FlowStatement(a) => {
+					let branches = a.elif_branches.iter();
+					for branch in std::iter::once(&**a).chain(branches) {
+						branch.condition.collect_names(used);
+						for statement in &branch.body.code_block.statements {
+							statement.collect_names(used, declared);
+						}
+					}
+					if let Some(else_branch) = &a.else_branch {
+						for statement in &else_branch.body.code_block.statements {
+							statement.collect_names(used, declared);
+						}
+					}
+				},
+				Statement::ForLoop(a) => {
+					a.iterable.collect_names(used);
+					push_name(declared, &a.name.slice);
+					for statement in &a.body.code_block.statements {
+						statement.collect_names(used, declared);
+					}
+				},
+				Statement::IndexAssign(a) => {
+					a.index.left.collect_names(used);
+					a.index.index.collect_names(used);
+					a.new_value.collect_names(used);
+				},
+				Statement::Return(a) => if let Some(value) = &a.value {
+					value.collect_names(used);
+				},
+				Statement::VarAssign(a) => {
+					push_name(used, &a.name.slice);
+					a.new_value.collect_names(used);
+				},
+				Statement::VarDeclaration(a) => {
+					if let Some(value) = &a.initial_value {
+						value.collect_names(used);
+					}
+					push_name(declared, &a.name.slice);
+				},
+				Statement::VarDestructure(a) => {
+					a.value.collect_names(used);
+					for name in &a.names {
+						push_name(declared, &name.slice);
+					}
+				},
+			}
+		}
+
+
+		/// Returns *true* if this statement may change the variable `name`,
+		/// either by assigning to it or to one of its fields or indexes, or by
+		/// calling one of its methods.
+		fn changes_var(&self, name:&str) -> bool {
+			match self {
+				Statement::Break(_)
+				| Statement::ClassDeclaration(_)
+				| Statement::Continue(_)
+				| Statement::FunctionDeclaration(_)
+				| Statement::Impl(_)
+				| Statement::Import(_)
+				| Statement::TraitDeclaration(_) => false,
+				Statement::Expression(a) => a.changes_var(name),
+				Statement::FieldAssign(a) => {
+					a.field.left.root_name() == Some(name)
+						|| a.field.left.changes_var(name)
+						|| a.new_value.changes_var(name)
+				},
+				Statement::FlowStatement(a) => {
+					let branches = a.elif_branches.iter();
+					let changes_branch = std::iter::once(&**a)
+						.chain(branches)
+						.any(|branch| {
+							branch.condition.changes_var(name)
+								|| branch.body.code_block.changes_var(name)
+						});
+					changes_branch || a.else_branch.as_ref().is_some_and(
+						|else_branch| else_branch.body.code_block.changes_var(name)
+					)
+				},
+				Statement::ForLoop(a) => {
+					a.iterable.changes_var(name)
+						|| a.body.code_block.changes_var(name)
+				},
+				Statement::IndexAssign(a) => {
+					a.index.left.root_name() == Some(name)
+						|| a.index.left.changes_var(name)
+						|| a.index.index.changes_var(name)
+						|| a.new_value.changes_var(name)
+				},
+				Statement::Return(a) => a.value.as_ref()
+					.is_some_and(|value| value.changes_var(name)),
+				Statement::VarAssign(a) => {
+					a.name.slice == name || a.new_value.changes_var(name)
+				},
+				Statement::VarDeclaration(a) => a.initial_value.as_ref()
+					.is_some_and(|value| value.changes_var(name)),
+				Statement::VarDestructure(a) => a.value.changes_var(name),
+			}
+		}
+
+
+		/// Returns the part of the script this statement was parsed from.
+		pub fn span(&self) -> QuSpan {
+			match self {
+				Statement::Break(a) => a.span,
+				Statement::ClassDeclaration(a) => a.span,
+				Statement::Continue(a) => a.span,
+				Statement::Expression(a) => a.span(),
+				Statement::FieldAssign(a) => a.span,
+				Statement::FlowStatement(a) => a.span,
+				Statement::ForLoop(a) => a.span,
+				Statement::FunctionDeclaration(a) => a.span,
+				Statement::Impl(a) => a.span,
+				Statement::Import(a) => a.span,
+				Statement::IndexAssign(a) => a.span,
+				Statement::Return(a) => a.span,
+				Statement::TraitDeclaration(a) => a.span,
+				Statement::VarAssign(a) => a.span,
+				Statement::VarDeclaration(a) => a.span,
+				Statement::VarDestructure(a) => a.span,
+			}
+		}
 	}
 
 
@@ -136,6 +471,7 @@ pub mod parsed {
 		pub left: Expression,
 		/// The type being cast to
 		pub right: QuToken,
+		pub span: QuSpan,
 	}
 
 	#[derive(Debug, Default, Clone, PartialEq)]
@@ -145,6 +481,7 @@ pub mod parsed {
 		pub parameters: TupleExpression,
 		pub open_parenthesy: QuToken,
 		pub close_parenthesy: QuToken,
+		pub span: QuSpan,
 	} impl From<&OperationExpression> for CallExpression {
 		fn from(value: &OperationExpression) -> Self {
 			// TODO: Don't use this function anymore
@@ -154,6 +491,7 @@ pub mod parsed {
 					.into(),
 				parameters: vec![value.left.clone(), value.right.clone()]
 					.into(),
+				span: value.span,
 				..Default::default()
 			}
 		}
@@ -164,20 +502,70 @@ pub mod parsed {
 					.name()
 					.into(),
 				parameters: vec![value.left, value.right].into(),
+				span: value.span,
 				..Default::default()
 			}
 		}
 	}
 
 
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct ClassDeclaration {
+		pub class_keyword: QuToken,
+		pub name: QuToken,
+		pub body: CodeScope,
+		pub span: QuSpan,
+	}
+
+
 	#[derive(Debug, Default, Clone, PartialEq)]
 	pub struct CodeBlock {
 		pub statements: Vec<Statement>,
+		pub span: QuSpan,
 	} impl CodeBlock {
 		pub fn new(statements:Vec<Statement>) -> Self {
+			let span = match (statements.first(), statements.last()) {
+				(Some(first), Some(last)) => first.span().to(&last.span()),
+				_ => QuSpan::default(),
+			};
 			Self {
 				statements,
+				span,
+			}
+		}
+
+
+		/// Returns the names this code block uses without declaring them, in
+		/// the order they're first used.
+		/// 
+		/// Names are collected without resolving them, so some of them may
+		/// name functions instead of variables.
+		pub fn free_names(&self) -> Vec<String> {
+			let mut used = vec![];
+			let mut declared = vec![];
+			for statement in &self.statements {
+				statement.collect_names(&mut used, &mut declared);
 			}
+			used.retain(|name| !declared.contains(name));
+			return used;
+		}
+
+
+		/// Returns *true* if this code block may change the variable `name`.
+		/// 
+		/// Like [`CodeBlock::free_names`], names are compared without
+		/// resolving them.
+		pub fn changes_var(&self, name:&str) -> bool {
+			return self.statements.iter()
+				.any(|statement| statement.changes_var(name));
+		}
+	}
+
+
+	/// Adds `name` to `names` if it isn't in it yet.
+	fn push_name(names:&mut Vec<String>, name:&str) {
+		if !names.iter().any(|other| other == name) {
+			names.push(name.to_owned());
 		}
 	}
 
@@ -186,45 +574,96 @@ pub mod parsed {
 	pub struct CodeScope {
 		pub colon: QuToken,
 		pub code_block: CodeBlock,
+		pub span: QuSpan,
 	} impl CodeScope {
 		pub fn new(statements:Vec<Statement>) -> Self {
+			let code_block = CodeBlock::new(statements);
 			Self {
 				colon: QuToken::from(":"),
-				code_block: CodeBlock::new(statements),
+				span: code_block.span,
+				code_block,
 			}
 		}
 	}
 
 
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct FieldAssignment {
+		pub field: DotIndex,
+		pub equals_sign: QuToken,
+		pub new_value: Expression,
+		pub span: QuSpan,
+	}
+
+
 	#[derive(Debug, Clone, PartialEq)]
 	pub struct FlowStatement {
 		pub flow_keyword: QuToken,
 		pub condition: Expression,
 		pub body: CodeScope,
+		/// The `elif` branches that follow an `if`, in order.
+		pub elif_branches: Vec<FlowStatement>,
+		/// The `else` branch that ends an `if` chain.
+		pub else_branch: Option<ElseBranch>,
+		pub span: QuSpan,
 	} impl FlowStatement {
 		pub fn new(
 			flow_keyword:&str, expression:Expression, code_scope:CodeScope
 		) -> Self {
 			Self {
 				flow_keyword: QuToken::from(flow_keyword),
+				span: expression.span().to(&code_scope.span),
 				condition: expression,
 				body: code_scope,
+				elif_branches: Vec::new(),
+				else_branch: None,
 			}
 		}
 	}
 
 
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct ElseBranch {
+		pub else_keyword: QuToken,
+		pub body: CodeScope,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct ForLoop {
+		pub for_keyword: QuToken,
+		/// The name of the variable that holds each item.
+		pub name: QuToken,
+		pub in_keyword: QuToken,
+		/// The value being iterated over.
+		pub iterable: Expression,
+		pub body: CodeScope,
+		pub span: QuSpan,
+	}
+
+
+	/// A `break` or `continue` statement.
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct LoopJump {
+		pub keyword: QuToken,
+		pub span: QuSpan,
+	}
+
+
 	#[derive(Debug, Clone, PartialEq)]
 	pub struct FunctionDeclaration {
 		pub fn_keyword: QuToken,
 		pub identity: FunctionIdentity,
 		pub body: CodeScope,
+		pub span: QuSpan,
 	} impl FunctionDeclaration {
 		pub fn new(
 			function_identity:FunctionIdentity, body:CodeScope
 		) -> Self {
 			Self {
 				fn_keyword: QuToken::from("fn"),
+				span: function_identity.span.to(&body.span),
 				identity: function_identity,
 				body,
 			}
@@ -237,6 +676,7 @@ pub mod parsed {
 		pub name: QuToken,
 		pub parameters: Vec<FunctionParameterElement>,
 		pub return_type: Option<QuToken>,
+		pub span: QuSpan,
 	}
 
 
@@ -245,6 +685,7 @@ pub mod parsed {
 		pub name: QuToken,
 		pub static_type: Option<QuToken>,
 		pub comma: Option<QuToken>,
+		pub span: QuSpan,
 	} impl FunctionParameterElement {
 		pub fn name(&self) -> &str {
 			&self.name.slice
@@ -252,17 +693,29 @@ pub mod parsed {
 	}
 
 
+	/// A function made as a value. Its identity has no name.
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct Lambda {
+		pub fn_keyword: QuToken,
+		pub identity: FunctionIdentity,
+		pub body: CodeScope,
+		pub span: QuSpan,
+	}
+
+
 	#[derive(Debug, Default, Clone, PartialEq)]
 	pub struct DotIndex {
 		pub left: Expression,
 		pub dot: QuToken,
 		pub right: QuToken,
+		pub span: QuSpan,
 	}
 
 
 	#[derive(Debug, Default, Clone, PartialEq)]
 	pub struct IdentityExpression {
 		pub value: Expression,
+		pub span: QuSpan,
 	} impl Display for IdentityExpression {
 		fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 			write!(f, "{}", &self.value)
@@ -274,6 +727,14 @@ pub mod parsed {
 	pub enum Identity {
 		Single(Box<QuToken>),
 		Index(Box<IdentityIndex>),
+	} impl Identity {
+		/// Returns the part of the script this identity was parsed from.
+		pub fn span(&self) -> QuSpan {
+			match self {
+				Identity::Single(a) => a.span(),
+				Identity::Index(a) => a.span,
+			}
+		}
 	} impl Default for Identity {
 		fn default() -> Self {
 			unreachable!()
@@ -286,6 +747,16 @@ pub mod parsed {
 		pub left: QuToken,
 		pub dot: QuToken,
 		pub right: Identity,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct ImplBlock {
+		pub impl_keyword: QuToken,
+		pub name: QuToken,
+		pub body: Option<CodeScope>,
+		pub span: QuSpan,
 	}
 
 
@@ -293,19 +764,76 @@ pub mod parsed {
 	pub struct Import {
 		pub import: QuToken,
 		pub identity_path: Identity,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	/// The value of a [`NumberLiteral`].
+	pub enum Number {
+		Int(Int),
+		Float(Float),
 	}
 
 
 	#[derive(Debug, Clone, PartialEq)]
 	pub struct NumberLiteral {
+		/// An int or float token.
 		pub value: QuToken,
-		pub decimal: Option<QuToken>,
+		pub number: Number,
+		pub span: QuSpan,
 	}
 
 
 	#[derive(Debug, Clone, PartialEq)]
 	pub struct BoolLiteral {
 		pub value: QuToken,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct IndexExpression {
+		/// The value being indexed
+		pub left: Expression,
+		pub open_bracket: QuToken,
+		pub index: Expression,
+		pub close_bracket: QuToken,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct IndexAssignment {
+		pub index: IndexExpression,
+		pub equals_sign: QuToken,
+		pub new_value: Expression,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct ListLiteral {
+		pub elements: Vec<Expression>,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct MapLiteral {
+		/// The keys and values of the map, in the order they're written.
+		pub entries: Vec<(Expression, Expression)>,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct StringLiteral {
+		/// The string as it's written in the script, including quotes.
+		pub value: QuToken,
+		/// The text of the string with its escape sequences resolved.
+		pub text: String,
+		pub span: QuSpan,
 	}
 
 
@@ -314,9 +842,11 @@ pub mod parsed {
 		pub left: Expression,
 		pub operator: QuToken,
 		pub right: Expression,
+		pub span: QuSpan,
 	} impl OperationExpression {
 		pub fn new(left:Expression, operator:&str, right:Expression) -> Self {
 			Self {
+				span: left.span().to(&right.span()),
 				left,
 				operator: QuToken::from(operator),
 				right,
@@ -325,12 +855,22 @@ pub mod parsed {
 	}
 
 
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct UnaryExpression {
+		pub operator: QuToken,
+		pub operand: Expression,
+		pub span: QuSpan,
+	}
+
+
 	#[derive(Debug, Default, Clone, PartialEq)]
 	pub struct ReturnStatement {
 		pub value: Option<Expression>,
+		pub span: QuSpan,
 	} impl ReturnStatement {
 		pub fn new(value:Option<Expression>) -> Self {
 			Self {
+				span: value.as_ref().map(|v| v.span()).unwrap_or_default(),
 				value,
 			}
 		}
@@ -340,10 +880,16 @@ pub mod parsed {
 	#[derive(Debug, Default, Clone, PartialEq)]
 	pub struct TupleExpression {
 		pub elements:Vec<Expression>,
+		pub span: QuSpan,
 	} impl TupleExpression {
 		pub fn new(elements:Vec<Expression>) -> Self {
+			let span = match (elements.first(), elements.last()) {
+				(Some(first), Some(last)) => first.span().to(&last.span()),
+				_ => QuSpan::default(),
+			};
 			Self {
 				elements,
+				span,
 			}
 		}
 
@@ -352,16 +898,34 @@ pub mod parsed {
 		}
 	} impl From<Vec<Expression>> for TupleExpression {
 		fn from(elements: Vec<Expression>) -> Self {
-			Self { elements }
+			Self::new(elements)
 		}
 	}
 
 
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct TraitDeclaration {
+		pub trait_keyword: QuToken,
+		pub name: QuToken,
+		pub functions: Vec<TraitFunction>,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct TraitFunction {
+		pub fn_keyword: QuToken,
+		pub identity: FunctionIdentity,
+		pub span: QuSpan,
+	}
+
+
 	#[derive(Debug, Clone, PartialEq)]
 	pub struct VarAssignment {
 		pub name: QuToken,
 		pub equals_sign: QuToken,
 		pub new_value: Expression,
+		pub span: QuSpan,
 	}
 
 
@@ -372,12 +936,26 @@ pub mod parsed {
 		pub static_type: Option<QuToken>,
 		pub equals_sign: Option<QuToken>,
 		pub initial_value: Option<Expression>,
+		pub span: QuSpan,
+	}
+
+
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct VarDestructuring {
+		pub var_keyword: QuToken,
+		/// The names of the variables, in the order of the tuple's elements.
+		pub names: Vec<QuToken>,
+		pub equals_sign: QuToken,
+		/// The tuple being destructured.
+		pub value: Expression,
+		pub span: QuSpan,
 	}
 
 
 	#[derive(Debug, Default, Clone, PartialEq)]
 	pub struct VarExpression {
 		pub name: QuToken,
+		pub span: QuSpan,
 	}
 }
 
@@ -399,7 +977,7 @@ pub enum QuOperator {
 	Mod,
 	/// The `**` or power math operator.
 	Pow,
-	/// The `//` or square root math operator.
+	/// The `//` or root math operator. `x // n` is the n-th root of x.
 	Sqrt,
 
 	/// The `<` logical operator.
@@ -418,6 +996,10 @@ pub enum QuOperator {
 	And,
 	/// The `or` logical operator.
 	Or,
+	/// The `not` logical operator.
+	Not,
+	/// The `-` negation operator.
+	Neg,
 	Is,
 
 } impl QuOperator {
@@ -449,6 +1031,19 @@ pub enum QuOperator {
 	}
 
 
+	/// Returns the operator of an expression with a single operand, like
+	/// `not x` or `-x`.
+	pub fn from_unary_symbol(symbol:&str) -> Self {
+		use QuOperator::*;
+		match symbol {
+			OP_EXPR_NOT => Not,
+			OP_EXPR_SUB => Neg,
+
+			_ => unimplemented!(),
+		}
+	}
+
+
 	pub fn name(&self) -> &'static str {
 		match self {
 			QuOperator::Add => "add",
@@ -466,6 +1061,8 @@ pub enum QuOperator {
 			QuOperator::NotEq => "not_equal",
 			QuOperator::And => "and",
 			QuOperator::Or => "or",
+			QuOperator::Not => "not",
+			QuOperator::Neg => "neg",
 			QuOperator::Is => "is",
 		}
 	}
@@ -489,6 +1086,10 @@ pub struct QuParser {
 	tk_stack:Vec<usize>, // TODO: Fix tk_stack mem-leak
 	/// The [QuTokens] being parsed.
 	tokens:Vec<QuToken>,
+	/// The errors found so far.
+	diagnostics:Vec<QuMsg>,
+	/// How the indentation of lines is measured.
+	indentation:Indentation,
 
 } impl QuParser {
 
@@ -500,68 +1101,100 @@ pub struct QuParser {
 			tk_idx:0,
 			tk_stack:vec![],
 			tokens:vec![],
+			diagnostics:vec![],
+			indentation:Indentation::default(),
 		}
 	}
 
 
-	/// Attempts to parse a code block.
-	fn ck_code_block(&mut self) -> Result<Option<CodeBlock>, QuMsg> {
-		let mut leafs:Vec<Statement> = vec![];
-
-		macro_rules! ck_parse {
-			($fn_name:ident, $statement:path) => {
-				if let Some(data) = self.$fn_name()? {
-					leafs.push($statement(Box::new(data)));
-					continue;
-				}
-			};
-		}
-
-		while self.tk_idx < self.tokens.len()-1 {
-			// Variable declaration
-			ck_parse!(ck_var_decl, Statement::VarDeclaration);
+	/// Sets how the indentation of lines is measured.
+	pub fn set_indentation(&mut self, indentation:Indentation) {
+		self.indentation = indentation;
+	}
 
-			// Import
-			ck_parse!(ck_import, Statement::Import);
 
-			// Variable assignment
-			ck_parse!(ck_var_assign, Statement::VarAssign);
+	/// Attempts to parse a class declaration.
+	fn ck_class_decl(&mut self) -> Result<Option<ClassDeclaration>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
 
-			// If Statement
-			ck_parse!(ck_flow_if, Statement::FlowStatement);
+		let start = self.tk_idx;
+		let Some(class_keyword) = self.ck_str(KEYWORD_CLASS)?
+			else {return Ok(None)};
+		let Some(name) = self.ck_type_name()?
+			else {return Err(QuMsg::missing_name("class", "class Point:"))};
+		let Some(body) = self.ck_code_scope()?
+			else {return Err(QuMsg::missing_code_block())};
 
-			// while Statement
-			ck_parse!(ck_flow_while, Statement::FlowStatement);
+		return Ok(Some(ClassDeclaration {
+			class_keyword,
+			name,
+			body,
+			span: self.utl_span_from(start),
+		}));
+	}
 
-			// Return Statement
-			ck_parse!(ch_keyword_return, Statement::Return);
 
-			// Function declaration
-			ck_parse!(ck_fn_decl, Statement::FunctionDeclaration);
+	/// Attempts to parse a code block.
+	/// 
+	/// Statements that fail to parse are added to the parser's diagnostics
+	/// and skipped, so the rest of the block is still parsed.
+	fn ck_code_block(&mut self) -> Result<Option<CodeBlock>, QuMsg> {
+		let start = self.tk_idx;
+		let indent = self.indent;
+		let errors_before = self.diagnostics.len();
+		let mut leafs:Vec<Statement> = vec![];
 
-			// Expressions
-			if !self.utl_statement_start()?.is_none() {
-				if let Some(expr_leaf) = self.ck_expr()? {
-					leafs.push(Statement::Expression(Box::new(expr_leaf)));
-					continue;
-				}
+		while self.tk_idx < self.tokens.len()-1 {
+			let statement_start = self.tk_idx;
+			match self.ck_statement() {
+				Ok(Some(statement)) => leafs.push(statement),
+				Ok(None) => {
+					// Tokens left over on the line of the last statement
+					let tk = self.tk_spy(0);
+					let is_leftover = self.tk_idx > 0
+						&& tk.kind != TokenKind::End
+						&& tk.char_index.row
+							== self.tokens[self.tk_idx-1].char_index.row;
+					if !is_leftover {
+						break;
+					}
+					let msg = QuMsg::invalid_token(&tk.slice)
+						.or_span(tk.span());
+					self.diagnostics.push(msg);
+					let block_indent = self.utl_block_indent(indent, start);
+					self.utl_recover(self.tk_idx, block_indent);
+				},
+				Err(msg) => {
+					let msg = msg.or_span(self.tk_spy(0).span());
+					self.diagnostics.push(msg);
+					let block_indent = self.utl_block_indent(indent, start);
+					self.utl_recover(statement_start, block_indent);
+				},
 			}
-
-			break;
 		}
 
 		if leafs.len() == 0 {
+			// The block had code, but none of it could be parsed
+			if self.diagnostics.len() > errors_before {
+				return Ok(Some(CodeBlock::new(leafs)));
+			}
 			// TODO: Add real token to QuMsg
 			return Err(QuMsg::missing_code_block()
 			);
 		}
 
-		return Ok(Some(CodeBlock {statements: leafs}));
+		return Ok(Some(CodeBlock {
+			statements: leafs,
+			span: self.utl_span_from(start),
+		}));
 	}
 
 
 	/// Attempts to pasrse a code scope.
 	fn ck_code_scope(&mut self) -> Result<Option<CodeScope>, QuMsg> {
+		let start = self.tk_idx;
 		self.tk_state_save();
 
 		// Check operator
@@ -577,50 +1210,78 @@ pub struct QuParser {
 		};
 		self.indent -= 1;
 
-		return Ok(Some(CodeScope {colon, code_block}));
+		return Ok(Some(CodeScope {
+			colon,
+			code_block,
+			span: self.utl_span_from(start),
+		}));
 	}
 
 
 	/// Attempts to parse a dot indexing (Ex: foo.bar). If it doesn't it
 	/// then attempts to parse a value. 
 	fn ck_dot_index(&mut self) -> Result<Option<Expression>, QuMsg> {
+		let start = self.tk_idx;
+
 		// Parse any expression
-		let Some(left) = self.ck_value()? else {
+		let Some(mut left) = self.ck_value()? else {
 			return Ok(None);
 		};
 
-		// Parse a dot indexing
-		let Some(dot) = self.ck_str(OP_DOT_INDEX)? else {
-			// Does not match dot index, return expression
-			return Ok(Some(left));
-		};
-		let Some(right) = self.ck_identity()? else { return Err(
-			format!(
-				"Expected an identity after '.', but found something else. TODO",
-			).into()
-		) };
+		// Dot indexes can be chained (Ex: foo.bar.baz())
+		loop {
+			// Parse an index (Ex: foo[0])
+			if let Some(open_bracket) = self.ck_str(OP_INDEX_OPEN)? {
+				let Some(index) = self.ck_ops()?
+					else {return Err(QuMsg::index_lacks_value())};
+				let Some(close_bracket) = self.ck_str(OP_INDEX_CLOSE)?
+					else {return Err(QuMsg::missing_token(OP_INDEX_CLOSE))};
+				left = Expression::Index(Box::new( IndexExpression {
+					left,
+					open_bracket,
+					index,
+					close_bracket,
+					span: self.utl_span_from(start),
+				} ));
+				continue;
+			}
 
-		// Parse a function call
-		let Some(open_parenthesy) = self.ck_str("(")? else {
-			// Does not match function call, return dot index
-			return Ok(Some(Expression::DotIndex(Box::new( DotIndex {
-				left,
-				dot,
-				right,
-			} ))));
-		};
-		let parameters = self.ck_fn_call_parameters()?;
-		let Some(close_parenthesy) = self.ck_str(")")? else {
-			return Err(QuMsg::missing_token(")"))
-		};
+			// Parse a dot indexing
+			let Some(dot) = self.ck_str(OP_DOT_INDEX)? else {
+				// Does not match dot index, return expression
+				return Ok(Some(left));
+			};
+			let Some(right) = self.ck_identity()? else { return Err(
+				format!(
+					"Expected an identity after '.', but found something else. TODO",
+				).into()
+			) };
+
+			// Parse a function call
+			let Some(open_parenthesy) = self.ck_str("(")? else {
+				// Does not match function call, index a field
+				left = Expression::DotIndex(Box::new( DotIndex {
+					left,
+					dot,
+					right,
+					span: self.utl_span_from(start),
+				} ));
+				continue;
+			};
+			let parameters = self.ck_fn_call_parameters()?;
+			let Some(close_parenthesy) = self.ck_str(")")? else {
+				return Err(QuMsg::missing_token(")"))
+			};
 
-		Ok(Some(Expression::Call(Box::new( CallExpression {
-			caller: Some(left),
-			name: right,
-			parameters,
-			open_parenthesy,
-			close_parenthesy,
-		} ))))
+			left = Expression::Call(Box::new( CallExpression {
+				caller: Some(left),
+				name: right,
+				parameters,
+				open_parenthesy,
+				close_parenthesy,
+				span: self.utl_span_from(start),
+			} ));
+		}
 	}
 
 
@@ -630,57 +1291,176 @@ pub struct QuParser {
 	}
 
 
-	/// Attempts to parse a flow statement (Ex: if, while, for, etc).
-	fn ck_flow(
-		&mut self, token_type:u8
-	) -> Result<Option<FlowStatement>, QuMsg> {
+	/// Attempts to parse an assignment to a field (Ex: foo.bar = 5).
+	fn ck_field_assign(&mut self) -> Result<Option<FieldAssignment>, QuMsg> {
 		if self.utl_statement_start()?.is_none() {
 			return Ok(None);
 		}
 
-		self.tk_state_save();
+		// Parsing a dot index can leave saved states behind, so the start is
+		// restored directly instead of with the state stack
+		let start = self.tk_idx;
 
-		// Check keyword
-		let keyword = match token_type {
-			FLOW_TYPE_IF => KEYWORD_IF,
-			FLOW_TYPE_WHILE => KEYWORD_WHILE,
-			_ => unimplemented!(),
+		// Match field
+		let Some(Expression::DotIndex(field)) = self.ck_dot_index()? else {
+			self.tk_idx = start;
+			return Ok(None);
 		};
-		let flow_keyword = self.tk_next()
-				.expect("Improper indentation TODO: Bette message")
-				.clone();
-		if flow_keyword != keyword {
-			self.tk_state_pop();
+
+		// Match assign operator
+		let Some(equals_sign) = self.ck_str(OP_ASSIGN_SYMBOL)? else {
+			self.tk_idx = start;
 			return Ok(None);
-		}
+		};
 
-		// Check expression
-		let expression = self.ck_expr()?.ok_or_else(||{
+		// Match expression
+		let Some(new_value) = self.ck_expr()? else {
+			return Err(QuMsg::var_assign_invalid_value(
+				&field.right.slice, &self.tk_spy(0).slice,
+			));
+		};
+
+		return Ok(Some(FieldAssignment {
+			field: *field,
+			equals_sign,
+			new_value,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
+	/// Attempts to parse an assignment to an index (Ex: foo[0] = 5).
+	fn ck_index_assign(&mut self) -> Result<Option<IndexAssignment>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		// See `Parser::ck_field_assign` for why the start is restored directly
+		let start = self.tk_idx;
+
+		// Match index
+		let Some(Expression::Index(index)) = self.ck_dot_index()? else {
+			self.tk_idx = start;
+			return Ok(None);
+		};
+
+		// Match assign operator
+		let Some(equals_sign) = self.ck_str(OP_ASSIGN_SYMBOL)? else {
+			self.tk_idx = start;
+			return Ok(None);
+		};
+
+		// Match expression
+		let Some(new_value) = self.ck_expr()? else {
+			return Err(QuMsg::index_assign_lacks_value());
+		};
+
+		return Ok(Some(IndexAssignment {
+			index: *index,
+			equals_sign,
+			new_value,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
+	/// Attempts to parse a flow statement (Ex: if, while, for, etc).
+	fn ck_flow(
+		&mut self, token_type:u8
+	) -> Result<Option<FlowStatement>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		let start = self.tk_idx;
+		self.tk_state_save();
+
+		// Check keyword
+		let keyword = match token_type {
+			FLOW_TYPE_IF => KEYWORD_IF,
+			FLOW_TYPE_WHILE => KEYWORD_WHILE,
+			_ => unimplemented!(),
+		};
+		let flow_keyword = self.tk_next()?.clone();
+		if flow_keyword != keyword {
+			self.tk_state_pop();
+			return Ok(None);
+		}
+
+		// Check expression
+		let expression = self.ck_expr()?.ok_or_else(||{
 			QuMsg::flow_statement_lacks_expression()
 		})?;
 
 		// Check for code block
-		let code_scope = match self.ck_code_scope() {
+		let code_scope = self.ck_flow_body()?;
+
+		let mut flow = FlowStatement {
+			flow_keyword,
+			condition: expression,
+			body: code_scope,
+			elif_branches: Vec::new(),
+			else_branch: None,
+			span: self.utl_span_from(start),
+		};
+
+		if token_type == FLOW_TYPE_IF {
+			// Check for elif branches
+			while self.utl_flow_branch_start(KEYWORD_ELIF) {
+				self.line = self.tk_spy(0).char_index.row as usize;
+				let elif_start = self.tk_idx;
+				let elif_keyword = self.tk_next()?.clone();
+				let condition = self.ck_expr()?.ok_or_else(||{
+					QuMsg::flow_statement_lacks_expression()
+				})?;
+				let body = self.ck_flow_body()?;
+				flow.elif_branches.push(FlowStatement {
+					flow_keyword: elif_keyword,
+					condition,
+					body,
+					elif_branches: Vec::new(),
+					else_branch: None,
+					span: self.utl_span_from(elif_start),
+				});
+			}
+
+			// Check for else branch
+			if self.utl_flow_branch_start(KEYWORD_ELSE) {
+				self.line = self.tk_spy(0).char_index.row as usize;
+				let else_start = self.tk_idx;
+				let else_keyword = self.tk_next()?.clone();
+				let body = self.ck_flow_body()?;
+				flow.else_branch = Some(ElseBranch {
+					else_keyword,
+					body,
+					span: self.utl_span_from(else_start),
+				});
+			}
+
+			flow.span = self.utl_span_from(start);
+		}
+
+		return Ok(Some(flow));
+	}
+
+
+	/// Parses the code block of a flow statement or one of its branches.
+	fn ck_flow_body(&mut self) -> Result<CodeScope, QuMsg> {
+		return match self.ck_code_scope() {
 			Ok(leaf) => leaf.ok_or_else(|| {
 				QuMsg::missing_code_block()
-			} )?,
+			} ),
 			Err(msg) => {
 				// If the error is related to indentation, replace the error
 				// with a missing value error
-				if msg.title == errors::ERR_TITLE_INVALID_INDENTATION {
+				if msg.kind == QuErrorKind::InvalidIndentation {
 					self.tk_idx -= 1;
 					return Err(QuMsg::missing_code_block_in_flow());
 				}
 				// Return normal error
-				return Err(msg);
+				Err(msg)
 			}
 		};
-
-		return Ok(Some(FlowStatement {
-			flow_keyword,
-			condition: expression,
-			body: code_scope,
-		}));
 	}
 
 
@@ -696,12 +1476,54 @@ pub struct QuParser {
 	}
 
 
+	/// Attempts to parse a for loop (Ex: for i in range(0, 10):).
+	fn ck_for_loop(&mut self) -> Result<Option<ForLoop>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		let start = self.tk_idx;
+		let Some(for_keyword) = self.ck_str(KEYWORD_FOR)?
+			else {return Ok(None)};
+		let Some(name) = self.ck_var_name()?
+			else {return Err(QuMsg::for_loop_lacks_variable())};
+		let Some(in_keyword) = self.ck_str(KEYWORD_IN)?
+			else {return Err(QuMsg::missing_token(KEYWORD_IN))};
+		let iterable = self.ck_expr()?.ok_or_else(||{
+			QuMsg::flow_statement_lacks_expression()
+		})?;
+		let body = self.ck_flow_body()?;
+
+		return Ok(Some(ForLoop {
+			for_keyword,
+			name,
+			in_keyword,
+			iterable,
+			body,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
+	/// Attempts to parse a break statement.
+	fn ck_break(&mut self) -> Result<Option<LoopJump>, QuMsg> {
+		return self.utl_loop_jump(KEYWORD_BREAK);
+	}
+
+
+	/// Attempts to parse a continue statement.
+	fn ck_continue(&mut self) -> Result<Option<LoopJump>, QuMsg> {
+		return self.utl_loop_jump(KEYWORD_CONTINUE);
+	}
+
+
 	/// Attempts to parse a function call.
 	fn ck_fn_call(&mut self) -> Result<Option<CallExpression>, QuMsg> {
 		if self.utl_statement_start()?.is_none() {
 			return Ok(None);
 		}
 		
+		let start = self.tk_idx;
 		self.tk_state_save();
 
 		// Check function name
@@ -728,6 +1550,7 @@ pub struct QuParser {
 			parameters,
 			open_parenthesy,
 			close_parenthesy,
+			span: self.utl_span_from(start),
 		}));
 	}
 
@@ -735,52 +1558,66 @@ pub struct QuParser {
 	fn ck_fn_call_parameters(
 		&mut self,
 	) -> Result<TupleExpression, QuMsg> {
-		let parameters = match self.ck_expr()? {
-			Some(l) => {
-				match l {
-					Expression::Tuple(tuple_expression)
-						=> *tuple_expression,
-					_ => {
-						TupleExpression {elements: vec![l]}
-					},
-				}
-			},
-			None => TupleExpression::default(),
-		};
-		Ok(parameters)
+		// Parameters are parsed one by one rather than as a tuple, so that a
+		// tuple in parenthesis is passed as a single parameter
+		let span = self.tk_spy(0).span();
+		let mut elements = vec![];
+		while let Some(parameter) = self.ck_ops()? {
+			elements.push(parameter);
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		if elements.is_empty() {
+			return Ok(TupleExpression {elements, span});
+		}
+		Ok(TupleExpression::new(elements))
 	}
 
 
 	/// Attempts to parse a function definition.
 	fn ck_fn_decl(&mut self) -> Result<Option<FunctionDeclaration>, QuMsg> {
+		let start = self.tk_idx;
+		let Some((fn_keyword, function_identity)) = self.ck_fn_signature()?
+			else {return Ok(None)};
+		let Some(code_scope) = self.ck_code_scope()?
+			else {return Err(QuMsg::missing_code_block())};
+
+		return Ok(Some(FunctionDeclaration {
+			fn_keyword,
+			identity: function_identity,
+			body: code_scope,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
+	/// Attempts to parse the signature of a function, which is everything
+	/// before its body.
+	fn ck_fn_signature(
+		&mut self,
+	) -> Result<Option<(QuToken, FunctionIdentity)>, QuMsg> {
 		if self.utl_statement_start()?.is_none() {
 			return Ok(None);
 		}
 
 		let Some(fn_keyword) = self.ck_str(KEYWORD_FN)?
 			else {return Ok(None)};
+		let identity_start = self.tk_idx;
 		let Some(function_name) = self.ck_fn_name()?
-			// TODO: Change to more appropriate message
-			else {return Err(QuMsg::missing_code_block())};
+			else {return Err(QuMsg::missing_name("fn", "fn area() int:"))};
 		let Some(parameters) = self.ck_fn_parameters()?
-			else {return Err(QuMsg::general(
-				"Function definition expected parameters. TODO: better msg"
-			))};
+			else {return Err(QuMsg::fn_lacks_parameters(&function_name.slice))};
 		let return_type = self.ck_type_name()?;
-		let Some(code_scope) = self.ck_code_scope()?
-			else {return Err(QuMsg::missing_code_block())};
-		
+
 		let function_identity = FunctionIdentity {
 			name: function_name,
 			parameters,
 			return_type,
+			span: self.utl_span_from(identity_start),
 		};
 
-		return Ok(Some(FunctionDeclaration {
-			fn_keyword,
-			identity: function_identity,
-			body: code_scope,
-		}));
+		return Ok(Some((fn_keyword, function_identity)));
 	}
 
 
@@ -804,6 +1641,7 @@ pub struct QuParser {
 
 		let mut params = vec![];
 		loop {
+			let param_start = self.tk_idx;
 			let Some(param)
 				= self.ck_var_name_type()? else {break};
 			let comma = self.ck_str(",")?.clone();
@@ -812,6 +1650,7 @@ pub struct QuParser {
 				name: param.0,
 				static_type: param.1,
 				comma,
+				span: self.utl_span_from(param_start),
 			};
 			params.push(element);
 			if !has_comma {
@@ -835,14 +1674,15 @@ pub struct QuParser {
 		}
 
 		// Match keyword
+		let start = self.tk_idx;
 		let Some(_) = self.ck_str(KEYWORD_RETURN)?
 			else {return Ok(None)};
 
-		return match self.ck_expr()? {
-			Some(value)
-				=> Ok(Some(ReturnStatement {value:Some(value)})),
-			None => Ok(Some(ReturnStatement {value: None})),
-		};
+		let value = self.ck_expr()?;
+		return Ok(Some(ReturnStatement {
+			value,
+			span: self.utl_span_from(start),
+		}));
 	}
 
 
@@ -856,6 +1696,7 @@ pub struct QuParser {
 	/// mainly used for the import statement, not expressions. See
 	/// `[Parser::ck_dot_index]` for expressions.
 	fn ck_identity_index(&mut self) -> Result<Option<Identity>, QuMsg> {
+		let start = self.tk_idx;
 		let Some(left) = self.ck_identity()? else {
 			return Ok(None);
 		};
@@ -871,7 +1712,8 @@ pub struct QuParser {
 		Ok(Some(Identity::Index(Box::new( IdentityIndex {
 			left,
 			dot,
-			right
+			right,
+			span: self.utl_span_from(start),
 		} ))))
 	}
 
@@ -883,7 +1725,7 @@ pub struct QuParser {
 		let identity_option = self.tk_next_option();
 		match identity_option {
 			Some(identity) => {
-				if identity.tk_type == TOKEN_TYPE_NAME {
+				if identity.kind == TokenKind::Name {
 					return Ok(Some(
 						identity.clone()
 					));
@@ -899,12 +1741,39 @@ pub struct QuParser {
 	}
 
 
+	/// Attempts to parse the implementation of a trait in a class.
+	fn ck_impl_block(&mut self) -> Result<Option<ImplBlock>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		let start = self.tk_idx;
+		let Some(impl_keyword) = self.ck_str(KEYWORD_IMPL)?
+			else {return Ok(None)};
+		let Some(name) = self.ck_type_name()?
+			else {return Err(QuMsg::missing_name("impl", "impl Add:"))};
+		// Traits without functions can be implemented without a body
+		let body = match self.tk_spy(0) == OP_BLOCK_START {
+			true => self.ck_code_scope()?,
+			false => None,
+		};
+
+		return Ok(Some(ImplBlock {
+			impl_keyword,
+			name,
+			body,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
 	/// Attempts to parse an import statement.
 	fn ck_import(&mut self) -> Result<Option<Import>, QuMsg> {
 		if self.utl_statement_start()?.is_none() {
 			return Ok(None);
 		}
 
+		let start = self.tk_idx;
 		let Some(import) = self.ck_str(KEYWORD_IMPORT)? else {
 			return Ok(None)
 		};
@@ -919,6 +1788,7 @@ pub struct QuParser {
 		Ok(Some( Import {
 			import,
 			identity_path,
+			span: self.utl_span_from(start),
 		} ))
 	}
 
@@ -929,44 +1799,102 @@ pub struct QuParser {
 			return Ok(Some(number));
 		}
 
+		if let Some(string) = self.ck_string()? {
+			return Ok(Some(string));
+		}
+
+		if let Some(list) = self.ck_list()? {
+			return Ok(Some(list));
+		}
+
+		if let Some(map) = self.ck_map()? {
+			return Ok(Some(map));
+		}
+
 		let tk = self.tk_spy(0);
-		if
-			tk.tk_type == TOKEN_TYPE_NUMBER
-			||tk.slice == KEYWORD_BOOL_TRUE
-			|| tk.slice == KEYWORD_BOOL_FALSE
-		{
+		if tk.slice == KEYWORD_BOOL_TRUE || tk.slice == KEYWORD_BOOL_FALSE {
 			let value = self.tk_next()?.clone();
 			return Ok(Some(Expression::Bool(Box::new(
-				BoolLiteral { value }
+				BoolLiteral { span: value.span(), value }
 			))));
 		}
 		return Ok(None);
 	}
 
 
-	fn ck_number(&mut self) -> Result<Option<Expression>, QuMsg> {
-		if self.tk_spy(0).tk_type == TOKEN_TYPE_NUMBER {
-			let value = self.tk_next()?.clone();
-			let mut decimal = None;
-			if self.tk_spy(0) == "." {
-				let maybe_decimal = self.tk_spy(1);
-				if maybe_decimal.tk_type == TOKEN_TYPE_NUMBER {
-					// Matched decimal after dot
-					self.tk_next()?;
-					decimal = Some(self.tk_next()?.clone());
-				} else if maybe_decimal.tk_type != TOKEN_TYPE_NAME || !self.is_indent_ok(maybe_decimal) {
-					// No number after dot, and it's not part of an
-					// indexing, so it must be part of the decimal
-					self.tk_next()?;
-					decimal = Some(QuToken::from("0"));
-				}
+	/// Attempts to parse a list literal (Ex: [1, 2, 3]).
+	fn ck_list(&mut self) -> Result<Option<Expression>, QuMsg> {
+		let start = self.tk_idx;
+		if self.ck_str(OP_INDEX_OPEN)?.is_none() {
+			return Ok(None);
+		}
+
+		// A trailing comma is allowed (Ex: [1, 2,])
+		let mut elements = vec![];
+		while let Some(element) = self.ck_ops()? {
+			elements.push(element);
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		if self.ck_str(OP_INDEX_CLOSE)?.is_none() {
+			return Err(QuMsg::missing_token(OP_INDEX_CLOSE));
+		}
+
+		return Ok(Some(Expression::List(Box::new(ListLiteral {
+			elements,
+			span: self.utl_span_from(start),
+		}))));
+	}
+
+
+	/// Attempts to parse a map literal (Ex: {"a": 1, "b": 2}).
+	fn ck_map(&mut self) -> Result<Option<Expression>, QuMsg> {
+		let start = self.tk_idx;
+		if self.ck_str(OP_MAP_OPEN)?.is_none() {
+			return Ok(None);
+		}
+
+		// A trailing comma is allowed (Ex: {1: 2,})
+		let mut entries = vec![];
+		while let Some(key) = self.ck_ops()? {
+			if self.ck_str(OP_BLOCK_START)?.is_none() {
+				return Err(QuMsg::missing_token(OP_BLOCK_START));
 			}
+			let Some(value) = self.ck_ops()?
+				else {return Err(QuMsg::map_entry_lacks_value())};
+			entries.push((key, value));
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		if self.ck_str(OP_MAP_CLOSE)?.is_none() {
+			return Err(QuMsg::missing_token(OP_MAP_CLOSE));
+		}
+
+		return Ok(Some(Expression::Map(Box::new(MapLiteral {
+			entries,
+			span: self.utl_span_from(start),
+		}))));
+	}
+
 
+	fn ck_number(&mut self) -> Result<Option<Expression>, QuMsg> {
+		let kind = self.tk_spy(0).kind;
+		if kind == TokenKind::Int || kind == TokenKind::Float {
+			let value = self.tk_next()?.clone();
+			let number = match parse_number(&value, false) {
+				Ok(number) => number,
+				Err(msg) => {
+					return Err(msg.or_span(value.span()));
+				},
+			};
 			return Ok(Some(
 				Expression::Number(Box::new(
 					NumberLiteral {
+						span: value.span(),
 						value,
-						decimal,
+						number,
 					}
 				))
 			));
@@ -975,9 +1903,45 @@ pub struct QuParser {
 	}
 
 
+	fn ck_string(&mut self) -> Result<Option<Expression>, QuMsg> {
+		if self.tk_spy(0).kind != TokenKind::String {
+			return Ok(None);
+		}
+		let value = self.tk_next()?.clone();
+		let text = match unescape_string(&value.slice) {
+			Ok(text) => text,
+			Err(msg) => {
+				return Err(msg.or_span(value.span()));
+			},
+		};
+
+		return Ok(Some(Expression::String(Box::new(
+			StringLiteral { span: value.span(), value, text }
+		))));
+	}
+
+
 	/// The top level function for checking operators
 	fn ck_ops(&mut self) -> Result<Option<Expression>, QuMsg>{
-		return self.ck_op_is();
+		return self.ck_op_or();
+	}
+
+
+	/// Attempts to parse a logical or expression.
+	fn ck_op_or(&mut self) -> Result<Option<Expression>, QuMsg>{
+		return self.ck_operation(OP_EXPR_OR, &Self::ck_op_and);
+	}
+
+
+	/// Attempts to parse a logical and expression.
+	fn ck_op_and(&mut self) -> Result<Option<Expression>, QuMsg>{
+		return self.ck_operation(OP_EXPR_AND, &Self::ck_op_not);
+	}
+
+
+	/// Attempts to parse a logical not expression.
+	fn ck_op_not(&mut self) -> Result<Option<Expression>, QuMsg>{
+		return self.ck_unary(OP_EXPR_NOT, &Self::ck_op_is);
 	}
 
 
@@ -988,26 +1952,26 @@ pub struct QuParser {
 
 	/// Attempts to parse a lesser than expression.
 	fn ck_op_les(&mut self) -> Result<Option<Expression>, QuMsg>{
-		return self.ck_operation(OP_EXPR_LES, &Self::ck_op_grt);
+		return self.ck_operation(OP_EXPR_LES, &Self::ck_op_lse);
 	}
 
 
 	/// Attempts to parse a lesser or equal than expression.
-	//fn ck_op_lse(&mut self) -> Result<Option<QuLeafExpr>, QuMsg>{
-	//	return self.ck_operation(OP_EXPR_LSE, &Self::ck_op_grt);
-	//}
+	fn ck_op_lse(&mut self) -> Result<Option<Expression>, QuMsg>{
+		return self.ck_operation(OP_EXPR_LSE, &Self::ck_op_grt);
+	}
 
 
 	/// Attempts to parse a greater than expression.
 	fn ck_op_grt(&mut self) -> Result<Option<Expression>, QuMsg> {
-		return self.ck_operation(OP_EXPR_GRT, &Self::ck_op_eql);
+		return self.ck_operation(OP_EXPR_GRT, &Self::ck_op_gte);
 	}
 
 
 	/// Attempts to parse a greater or equal than expression.
-	//fn ck_op_gte(&mut self) -> Result<Option<QuLeafExpr>, QuMsg> {
-	//	return self.ck_operation(OP_EXPR_GTE, &Self::ck_op_eql);
-	//}
+	fn ck_op_gte(&mut self) -> Result<Option<Expression>, QuMsg> {
+		return self.ck_operation(OP_EXPR_GTE, &Self::ck_op_eql);
+	}
 
 
 	/// Attempts to parse an equal to expression.
@@ -1030,19 +1994,34 @@ pub struct QuParser {
 
 	/// Attempts to parse an addition expression.
 	fn ck_op_add(&mut self) -> Result<Option<Expression>, QuMsg>  {
-		return self.ck_operation(OP_EXPR_ADD, &Self::ck_op_div);
+		return self.ck_operation(OP_EXPR_ADD, &Self::ck_op_mul);
 	}
 
 
-	/// Attempts to parse a division expression.
-	fn ck_op_div(&mut self) -> Result<Option<Expression>, QuMsg> {
-		return self.ck_operation(OP_EXPR_DIV, &Self::ck_op_mul);
+	/// Attempts to parse a multiplication, division or modulo expression.
+	fn ck_op_mul(&mut self) -> Result<Option<Expression>, QuMsg> {
+		return self.ck_operation_group(
+			&[OP_EXPR_MUL, OP_EXPR_DIV, OP_EXPR_MOD],
+			&Self::ck_op_sqrt,
+		);
 	}
 
 
-	/// Attempts to parse a multiplication expression.
-	fn ck_op_mul(&mut self) -> Result<Option<Expression>, QuMsg> {
-		return self.ck_operation(OP_EXPR_MUL, &Self::ck_op_paren_expr);
+	/// Attempts to parse a root expression.
+	fn ck_op_sqrt(&mut self) -> Result<Option<Expression>, QuMsg> {
+		return self.ck_operation(OP_EXPR_SQRT, &Self::ck_op_pow);
+	}
+
+
+	/// Attempts to parse a power expression.
+	fn ck_op_pow(&mut self) -> Result<Option<Expression>, QuMsg> {
+		return self.ck_operation(OP_EXPR_POW, &Self::ck_op_neg);
+	}
+
+
+	/// Attempts to parse a negation expression.
+	fn ck_op_neg(&mut self) -> Result<Option<Expression>, QuMsg> {
+		return self.ck_unary(OP_EXPR_SUB, &Self::ck_op_paren_expr);
 	}
 
 
@@ -1085,7 +2064,7 @@ pub struct QuParser {
 		&mut self, operator:&str,
 		next:&dyn Fn(&mut Self)->Result<Option<Expression>, QuMsg>,
 	) -> Result<Option<Expression>, QuMsg> {
-
+		let start = self.tk_idx;
 		self.tk_state_save();
 
 		// Check left side for value
@@ -1101,7 +2080,7 @@ pub struct QuParser {
 		if tk_op != operator {
 			return Ok(Some(left));
 		}
-		self.tk_next().expect("Improper indentation TODO: Bette message");
+		self.tk_next()?;
 
 		// Check right side for expression
 		let right = self.ck_operation(operator, next)?;
@@ -1115,6 +2094,90 @@ pub struct QuParser {
 			left,
 			operator: tk_op,
 			right,
+			span: self.utl_span_from(start),
+		}))));
+	}
+
+
+	/// A helper function for checking operations whose operators have the
+	/// same precedence. They're grouped from left to right (Ex: a / b * c is
+	/// parsed as (a / b) * c).
+	fn ck_operation_group(
+		&mut self, operators:&[&str],
+		next:&dyn Fn(&mut Self)->Result<Option<Expression>, QuMsg>,
+	) -> Result<Option<Expression>, QuMsg> {
+		let start = self.tk_idx;
+		self.tk_state_save();
+
+		// Check left side for value
+		let Some(mut left) = next(self)? else {
+			self.tk_state_pop();
+			return Ok(None);
+		};
+
+		loop {
+			// Check operator
+			let tk_op = self.tk_spy(0).clone();
+			if !operators.iter().any(|operator| tk_op == *operator) {
+				return Ok(Some(left));
+			}
+			self.tk_next()?;
+
+			// Check right side for value
+			let Some(right) = next(self)? else {
+				self.tk_state_pop();
+				return Ok(None);
+			};
+
+			left = Expression::Operation(Box::new(OperationExpression {
+				left,
+				operator: tk_op,
+				right,
+				span: self.utl_span_from(start),
+			}));
+		}
+	}
+
+
+	/// A helper function for checking operations with a single operand, like
+	/// negation. The operator can be repeated (Ex: not not x).
+	fn ck_unary(
+		&mut self, operator:&str,
+		next:&dyn Fn(&mut Self)->Result<Option<Expression>, QuMsg>,
+	) -> Result<Option<Expression>, QuMsg> {
+		// Check operator
+		let start = self.tk_idx;
+		let tk_op = self.tk_spy(0).clone();
+		if tk_op != operator {
+			return next(self);
+		}
+		self.tk_next()?;
+
+		// Negative numbers are read with their sign, so that the smallest
+		// int can be written (Ex: -2147483648). Numbers followed by an index
+		// or call are negated after it (Ex: -5.hash() is -(5.hash())).
+		let is_number = matches!(self.tk_spy(0).kind, TokenKind::Int | TokenKind::Float)
+			&& !matches!(self.tk_spy(1).slice.as_str(), "." | "(" | "[");
+		if operator == OP_EXPR_SUB && is_number {
+			let value = self.tk_next()?.clone();
+			let number = parse_number(&value, true)
+				.map_err(|msg| msg.or_span(value.span()))?;
+			return Ok(Some(Expression::Number(Box::new(NumberLiteral {
+				value,
+				number,
+				span: self.utl_span_from(start),
+			}))));
+		}
+
+		// Check operand
+		let Some(operand) = self.ck_unary(operator, next)? else {
+			return Err(QuMsg::unary_lacks_operand(&tk_op.slice));
+		};
+
+		return Ok(Some(Expression::Unary(Box::new(UnaryExpression {
+			operator: tk_op,
+			operand,
+			span: self.utl_span_from(start),
 		}))));
 	}
 
@@ -1125,6 +2188,7 @@ pub struct QuParser {
 	/// A tuple is denoted by expressions separated by commas (Ex: "1,2,3").
 	/// Parenthesis technicly have nothing to do with tuples.
 	fn ck_tuple(&mut self) -> Result<Option<Expression>, QuMsg>{
+		let start = self.tk_idx;
 		match self.ck_ops()? {
 			// Matched an expression
 			Some(expr) => {
@@ -1141,7 +2205,10 @@ pub struct QuParser {
 									// No comma found, tuple must have ended.
 									// Return tuple.
 									return Ok(Some(Expression::Tuple(
-										Box::new(TupleExpression {elements})
+										Box::new(TupleExpression {
+											elements,
+											span: self.utl_span_from(start),
+										})
 									)))
 								} else {
 									// Comma found, continue adding to tuple
@@ -1151,7 +2218,10 @@ pub struct QuParser {
 							None => {
 								// No more expressions found, return tuple
 								return Ok(Some(Expression::Tuple(
-									Box::new(TupleExpression {elements})
+									Box::new(TupleExpression {
+										elements,
+										span: self.utl_span_from(start),
+									})
 								)))
 							},
 						};
@@ -1168,6 +2238,80 @@ pub struct QuParser {
 	}
 
 
+	/// Attempts to parse a single statement.
+	fn ck_statement(&mut self) -> Result<Option<Statement>, QuMsg> {
+		macro_rules! ck_parse {
+			($fn_name:ident, $statement:path) => {
+				if let Some(data) = self.$fn_name()? {
+					return Ok(Some($statement(Box::new(data))));
+				}
+			};
+		}
+
+		// Variable declaration
+		ck_parse!(ck_var_destructure, Statement::VarDestructure);
+		ck_parse!(ck_var_decl, Statement::VarDeclaration);
+
+		// Import
+		ck_parse!(ck_import, Statement::Import);
+
+		// Variable assignment
+		ck_parse!(ck_var_assign, Statement::VarAssign);
+
+		// Field assignment
+		ck_parse!(ck_field_assign, Statement::FieldAssign);
+
+		// Index assignment
+		ck_parse!(ck_index_assign, Statement::IndexAssign);
+
+		// If Statement
+		ck_parse!(ck_flow_if, Statement::FlowStatement);
+
+		// while Statement
+		ck_parse!(ck_flow_while, Statement::FlowStatement);
+
+		// for loop
+		ck_parse!(ck_for_loop, Statement::ForLoop);
+
+		// Loop jumps
+		ck_parse!(ck_break, Statement::Break);
+		ck_parse!(ck_continue, Statement::Continue);
+
+		// Return Statement
+		ck_parse!(ch_keyword_return, Statement::Return);
+
+		// Function declaration
+		ck_parse!(ck_fn_decl, Statement::FunctionDeclaration);
+
+		// Class declaration
+		ck_parse!(ck_class_decl, Statement::ClassDeclaration);
+
+		// Trait declaration
+		ck_parse!(ck_trait_decl, Statement::TraitDeclaration);
+
+		// Trait implementation
+		ck_parse!(ck_impl_block, Statement::Impl);
+
+		// Branches without an if statement
+		if self.utl_flow_branch_start(KEYWORD_ELIF)
+			|| self.utl_flow_branch_start(KEYWORD_ELSE)
+		{
+			return Err(QuMsg::flow_branch_without_if(
+				&self.tk_spy(0).slice
+			));
+		}
+
+		// Expressions
+		if !self.utl_statement_start()?.is_none() {
+			if let Some(expr_leaf) = self.ck_expr()? {
+				return Ok(Some(Statement::Expression(Box::new(expr_leaf))));
+			}
+		}
+
+		return Ok(None);
+	}
+
+
 	/// Attempts to parse `text`.
 	fn ck_str(&mut self, text:&str) -> Result<Option<QuToken>, QuMsg> {
 		if self.tk_spy(0) == text {
@@ -1177,15 +2321,188 @@ pub struct QuParser {
 	}
 
 
+	/// Attempts to parse a trait declaration.
+	fn ck_trait_decl(&mut self) -> Result<Option<TraitDeclaration>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		let start = self.tk_idx;
+		let Some(trait_keyword) = self.ck_str(KEYWORD_TRAIT)?
+			else {return Ok(None)};
+		let Some(name) = self.ck_type_name()?
+			else {return Err(QuMsg::missing_name("trait", "trait Named:"))};
+
+		// Traits without functions can be declared without a body
+		let mut functions = vec![];
+		if self.ck_str(OP_BLOCK_START)?.is_some() {
+			self.indent += 1;
+			while self.tk_idx < self.tokens.len()-1 {
+				let fn_start = self.tk_idx;
+				let Some((fn_keyword, identity)) = self.ck_fn_signature()?
+					else {break};
+				if self.tk_spy(0) == OP_BLOCK_START {
+					return Err(QuMsg::general(&format!(
+						"Function '{}' of trait '{}' can't have a body.",
+						identity.name.slice,
+						name.slice,
+					)));
+				}
+				functions.push(TraitFunction {
+					fn_keyword,
+					identity,
+					span: self.utl_span_from(fn_start),
+				});
+			}
+			self.indent -= 1;
+
+			if functions.is_empty() {
+				return Err(QuMsg::missing_code_block());
+			}
+		}
+
+		return Ok(Some(TraitDeclaration {
+			trait_keyword,
+			name,
+			functions,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
 	/// Attempts to parse a type name.
+	/// 
+	/// The types a collection holds are given in brackets (Ex: List[int],
+	/// Map[str, int]) and are kept as part of the name. So are the types of
+	/// the elements of a tuple (Ex: (int, float)).
 	fn ck_type_name(&mut self) -> Result<Option<QuToken>, QuMsg> {
+		if let Some(tuple) = self.ck_tuple_type_name()? {
+			return Ok(Some(tuple));
+		}
+		if let Some(function_type) = self.ck_fn_type_name()? {
+			return Ok(Some(function_type));
+		}
+
 		// TODO: Implement type specific check for names
-		return self.ck_identity();
+		let Some(mut name) = self.ck_identity()?
+			else {return Ok(None)};
+		if self.ck_str(OP_INDEX_OPEN)?.is_none() {
+			return Ok(Some(name));
+		}
+		let mut items = vec![];
+		loop {
+			let Some(item) = self.ck_type_name()?
+				else {return Err(QuMsg::missing_token("type name"))};
+			items.push(item.slice);
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		let Some(close_bracket) = self.ck_str(OP_INDEX_CLOSE)?
+			else {return Err(QuMsg::missing_token(OP_INDEX_CLOSE))};
+		name.slice = format!("{}[{}]", name.slice, items.join(", "));
+		name.end = close_bracket.end;
+		return Ok(Some(name));
+	}
+
+
+	/// Attempts to parse the type name of a tuple (Ex: (int, float)).
+	/// 
+	/// Tuples with a single element are named with a trailing comma (Ex:
+	/// (int,)).
+	fn ck_tuple_type_name(&mut self) -> Result<Option<QuToken>, QuMsg> {
+		let Some(mut name) = self.ck_str("(")?
+			else {return Ok(None)};
+		let mut elements = vec![];
+		while let Some(element) = self.ck_type_name()? {
+			elements.push(element.slice);
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		if elements.is_empty() {
+			return Err(QuMsg::missing_token("type name"));
+		}
+		let Some(close_parenthesy) = self.ck_str(")")?
+			else {return Err(QuMsg::missing_token(")"))};
+		name.slice = match elements.len() {
+			1 => format!("({},)", elements[0]),
+			_ => format!("({})", elements.join(", ")),
+		};
+		name.end = close_parenthesy.end;
+		return Ok(Some(name));
+	}
+
+
+	/// Attempts to parse the type name of a function value (Ex: fn(int)
+	/// int).
+	/// 
+	/// The return type has to be on the same line as the parameters. Functions
+	/// that return nothing are named without one (Ex: fn(str)).
+	fn ck_fn_type_name(&mut self) -> Result<Option<QuToken>, QuMsg> {
+		let start = self.tk_idx;
+		let Some(mut name) = self.ck_str(KEYWORD_FN)?
+			else {return Ok(None)};
+		if self.ck_str("(")?.is_none() {
+			self.tk_idx = start;
+			return Ok(None);
+		}
+		let mut parameters = vec![];
+		while let Some(parameter) = self.ck_type_name()? {
+			parameters.push(parameter.slice);
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		let Some(close_parenthesy) = self.ck_str(")")?
+			else {return Err(QuMsg::missing_token(")"))};
+		name.slice = format!("fn({})", parameters.join(", "));
+		name.end = close_parenthesy.end;
+
+		let is_same_line = self.tk_spy(0).char_index.row
+			== close_parenthesy.char_index.row;
+		if is_same_line {
+			if let Some(return_type) = self.ck_type_name()? {
+				name.slice = format!("{} {}", name.slice, return_type.slice);
+				name.end = return_type.end;
+			}
+		}
+		return Ok(Some(name));
+	}
+
+
+	/// Attempts to parse a lambda (Ex: fn(x int) int: ...).
+	fn ck_lambda(&mut self) -> Result<Option<Lambda>, QuMsg> {
+		let start = self.tk_idx;
+		let Some(fn_keyword) = self.ck_str(KEYWORD_FN)?
+			else {return Ok(None)};
+		let identity_start = self.tk_idx;
+		let Some(parameters) = self.ck_fn_parameters()? else {
+			self.tk_idx = start;
+			return Ok(None);
+		};
+		let return_type = self.ck_type_name()?;
+		let identity = FunctionIdentity {
+			name: QuToken::default(),
+			parameters,
+			return_type,
+			span: self.utl_span_from(identity_start),
+		};
+		let Some(body) = self.ck_code_scope()?
+			else {return Err(QuMsg::missing_code_block())};
+
+		return Ok(Some(Lambda {
+			fn_keyword,
+			identity,
+			body,
+			span: self.utl_span_from(start),
+		}));
 	}
 
 
 	/// Attempts to parse a value.
 	fn ck_value(&mut self) -> Result<Option<Expression>, QuMsg> {
+		let start = self.tk_idx;
 		self.tk_state_save();
 		if self.tk_next_option().is_none() {
 			self.tk_state_pop();
@@ -1194,7 +2511,9 @@ pub struct QuParser {
 		self.tk_state_pop();
 
 		let Some(expr) = (
-			if let Some(expr) = self.ck_literal()? {
+			if let Some(lambda) = self.ck_lambda()? {
+				Some(Expression::Lambda(Box::new(lambda)))
+			} else if let Some(expr) = self.ck_literal()? {
 				Some(expr)
 			} else if let Some(call) = self.ck_fn_call()? {
 				Some(Expression::Call(Box::new(call)))
@@ -1220,6 +2539,7 @@ pub struct QuParser {
 		Ok(Some(Expression::As(Box::new(AsExpression {
 			left: expr,
 			right,
+			span: self.utl_span_from(start),
 		}))))
 	}
 
@@ -1230,7 +2550,7 @@ pub struct QuParser {
 			else {return Ok(None)};
 
 		return Ok(Some(
-			VarExpression {name: variable_name}
+			VarExpression {span: variable_name.span(), name: variable_name}
 		));
 	}
 
@@ -1241,6 +2561,7 @@ pub struct QuParser {
 			return Ok(None);
 		}
 
+		let start = self.tk_idx;
 		self.tk_state_save();
 
 		// Match variable name
@@ -1269,9 +2590,12 @@ pub struct QuParser {
 			},
 		};
 
-		return Ok(Some(
-			VarAssignment { name: ident, equals_sign, new_value }
-		));
+		return Ok(Some(VarAssignment {
+			name: ident,
+			equals_sign,
+			new_value,
+			span: self.utl_span_from(start),
+		}));
 	}
 
 
@@ -1281,6 +2605,7 @@ pub struct QuParser {
 			return Ok(None);
 		}
 
+		let start = self.tk_idx;
 		let Some(var_keyword) = self.ck_str(KEYWORD_VAR)?
 			else {return Ok(None);};
 		
@@ -1305,7 +2630,7 @@ pub struct QuParser {
 			.or_else(|msg|{
 				// If the error is related to indentation, replace the error
 				// with a missing value error
-				if msg.title == errors::ERR_TITLE_INVALID_INDENTATION {
+				if msg.kind == QuErrorKind::InvalidIndentation {
 					self.tk_idx -= 1;
 					return Err(QuMsg::var_assign_lacks_value(
 						&String::from(name.clone())))
@@ -1318,9 +2643,64 @@ pub struct QuParser {
 
 		} else {None};
 		
-		return Ok(Some(
-			VarDeclaration {var_keyword, name, static_type, equals_sign, initial_value: value}
-		));
+		return Ok(Some(VarDeclaration {
+			var_keyword,
+			name,
+			static_type,
+			equals_sign,
+			initial_value: value,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
+	/// Attempts to parse a variable declaration that destructures a tuple
+	/// (Ex: var (x, y) = pos).
+	fn ck_var_destructure(
+		&mut self,
+	) -> Result<Option<VarDestructuring>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		let start = self.tk_idx;
+		let Some(var_keyword) = self.ck_str(KEYWORD_VAR)?
+			else {return Ok(None)};
+		if self.ck_str("(")?.is_none() {
+			self.tk_idx = start;
+			return Ok(None);
+		}
+
+		// Match variable names
+		let mut names = vec![];
+		while let Some(name) = self.ck_var_name()? {
+			names.push(name);
+			if self.ck_str(",")?.is_none() {
+				break;
+			}
+		}
+		if names.is_empty() {
+			return Err(QuMsg::missing_token("variable name"));
+		}
+		if self.ck_str(")")?.is_none() {
+			return Err(QuMsg::missing_token(")"));
+		}
+
+		// Match value
+		let Some(equals_sign) = self.ck_str(OP_ASSIGN_SYMBOL)? else {
+			return Err(QuMsg::destructure_lacks_value());
+		};
+		let Some(value) = self.ck_expr()? else {
+			return Err(QuMsg::destructure_lacks_value());
+		};
+
+		return Ok(Some(VarDestructuring {
+			var_keyword,
+			names,
+			equals_sign,
+			value,
+			span: self.utl_span_from(start),
+		}));
 	}
 
 
@@ -1359,36 +2739,62 @@ pub struct QuParser {
 
 
 	/// Parses a Qu script.
-	pub fn parse(&mut self, script:&str) -> Result<CodeBlock, QuMsg> {
+	/// 
+	/// # Errors
+	/// 
+	/// Returns every error found in the script if any part of it couldn't be
+	/// parsed.
+	pub fn parse(&mut self, script:&str) -> Result<CodeBlock, Vec<QuMsg>> {
 		self.tk_idx = 0;
 		self.line = 0;
 		self.indent = u8::MAX;
-		self.tokens = tokenize(&script.to_owned(), RULES);
-		self.tokens.push(
-			QuToken::new(
-				0, 0, 0, u8::MAX, "",
-			)
-		);
+		self.tokens = tokenize(script, self.indentation);
+
+		// The end of the script is placed right after the last token so that
+		// errors at the end point to the right line
+		let mut end = match self.tokens.last() {
+			Some(last) => QuToken::new(
+				last.char_index.row,
+				last.char_index.column + last.slice.chars().count() as u32,
+				0,
+				TokenKind::End,
+				"",
+			),
+			None => QuToken::new(0, 0, 0, TokenKind::End, ""),
+		};
+		end.start = script.len();
+		end.end = script.len();
+		self.tokens.push(end);
 
-		let res = self.ck_code_block();
-		match res {
-			Ok(data_opt) => {
-				if self.tk_idx != self.tokens.len()-1 {
-					// Parsing ended early, must be an unexpected token
-					panic!("Parsing ended without all tokens being searched");
-				}
+		self.diagnostics.clear();
 
-				let Some(data) = data_opt
-					else {return Ok(CodeBlock {statements: vec![]})};
-				return Ok(data);
+		let mut statements = vec![];
+		loop {
+			match self.ck_code_block() {
+				Ok(Some(block)) => statements.extend(block.statements),
+				Ok(None) => {},
+				Err(msg) => {
+					let msg = msg.or_span(self.tk_spy(0).span());
+					self.diagnostics.push(msg);
+				},
 			}
-
-			// Add token for location to error
-			Err(mut msg) => {
-				msg.token = self.tk_spy(0).char_index.clone();
-				return Err(msg);
+			if self.tk_idx >= self.tokens.len()-1 {
+				break;
 			}
+
+			// Parsing ended early, must be an unexpected token
+			let tk = self.tk_spy(0);
+			let msg = QuMsg::invalid_token(&tk.slice).or_span(tk.span());
+			let indent = tk.char_index.indent;
+			self.diagnostics.push(msg);
+			self.utl_recover(self.tk_idx, indent);
+			self.indent = u8::MAX;
 		}
+
+		if !self.diagnostics.is_empty() {
+			return Err(std::mem::take(&mut self.diagnostics));
+		}
+		return Ok(CodeBlock::new(statements));
 	}
 
 
@@ -1420,6 +2826,73 @@ pub struct QuParser {
 	}
 
 
+	/// Returns the indentation of the code block that started at the token
+	/// index `start`, given the parser's indentation when the block started.
+	fn utl_block_indent(&self, indent:u8, start:usize) -> u8 {
+		if indent != u8::MAX {
+			return indent;
+		}
+		// The first statement of a script sets its indentation
+		return self.tokens[start.min(self.tokens.len()-1)].char_index.indent;
+	}
+
+
+	/// Skips the statement that starts at the token index `start`, so that
+	/// parsing can continue after an error.
+	/// 
+	/// Every token on the same line as the statement is skipped, along with
+	/// any following lines that are indented more than `indent`.
+	fn utl_recover(&mut self, start:usize, indent:u8) {
+		self.indent = indent;
+		let row = self.tokens[start].char_index.row;
+		self.tk_idx = start;
+		while self.tk_idx < self.tokens.len()-1 {
+			let tk = &self.tokens[self.tk_idx];
+			if tk.char_index.row != row && tk.char_index.indent <= indent {
+				break;
+			}
+			self.tk_idx += 1;
+		}
+		self.line = self.tokens[self.tk_idx-1].char_index.row as usize;
+	}
+
+
+	/// Returns the span from the token at index `start` to the last parsed
+	/// token.
+	fn utl_span_from(&self, start:usize) -> QuSpan {
+		let first = self.tokens[start.min(self.tokens.len()-1)].span();
+		if self.tk_idx <= start {
+			return first;
+		}
+		return first.to(&self.tokens[self.tk_idx-1].span());
+	}
+
+
+	/// Attempts to parse a statement made of only the given loop jump keyword
+	/// (Ex: break, continue).
+	fn utl_loop_jump(&mut self, keyword:&str) -> Result<Option<LoopJump>, QuMsg> {
+		if self.utl_statement_start()?.is_none() {
+			return Ok(None);
+		}
+
+		let start = self.tk_idx;
+		let Some(keyword) = self.ck_str(keyword)?
+			else {return Ok(None)};
+		return Ok(Some(LoopJump {
+			keyword,
+			span: self.utl_span_from(start),
+		}));
+	}
+
+
+	/// Returns true if the next token is the given branch keyword (Ex: elif,
+	/// else) at the current indentation level.
+	fn utl_flow_branch_start(&self, keyword:&str) -> bool {
+		let tk = self.tk_spy(0);
+		return tk == keyword && tk.char_index.indent == self.indent;
+	}
+
+
 	/// Returns the next token to parse.
 	/// 
 	/// Error:
@@ -1449,7 +2922,7 @@ pub struct QuParser {
 			return None;
 		}
 
-		if tk.tk_type == u8::MAX {
+		if tk.kind == TokenKind::End {
 			return None;
 		}
 
@@ -1485,5 +2958,85 @@ pub struct QuParser {
 }
 
 
+/// Removes the quotes from a string literal and resolves its escape sequences.
+fn unescape_string(literal:&str) -> Result<String, QuMsg> {
+	let mut chars = literal.chars();
+	let quote = chars.next();
+	if literal.len() < 2 || chars.next_back() != quote {
+		return Err(QuMsg::unclosed_string());
+	}
+
+	let mut text = String::with_capacity(literal.len());
+	while let Some(char) = chars.next() {
+		if char != '\\' {
+			text.push(char);
+			continue;
+		}
+		let escaped = match chars.next() {
+			Some('n') => '\n',
+			Some('t') => '\t',
+			Some('r') => '\r',
+			Some('0') => '\0',
+			Some('\\') => '\\',
+			Some('"') => '"',
+			Some('\'') => '\'',
+			Some(other) => return Err(QuMsg::invalid_escape_sequence(
+				&format!("\\{other}")
+			)),
+			// The closing quote was escaped
+			None => return Err(QuMsg::unclosed_string()),
+		};
+		text.push(escaped);
+	}
+
+	return Ok(text);
+}
+
+
+/// Converts an int or float token to its value.
+/// 
+/// Hex, octal and binary literals can use all 32 bits, so `0xFFFFFFFF` is -1.
+/// Decimal literals have to fit in an [`Int`].
+fn parse_number(token:&QuToken, negative:bool) -> Result<Number, QuMsg> {
+	let literal = token.slice.replace('_', "");
+
+	if token.kind == TokenKind::Float {
+		let digits = literal.strip_suffix('f').unwrap_or(&literal);
+		return match digits.parse::<Float>() {
+			Ok(value) if value.is_finite() && negative => Ok(Number::Float(-value)),
+			Ok(value) if value.is_finite() => Ok(Number::Float(value)),
+			Ok(_) => Err(QuMsg::number_literal_overflow(&token.slice, "float")),
+			Err(_) => Err(QuMsg::invalid_number_literal(&token.slice)),
+		};
+	}
+
+	let radix = match literal.get(..2) {
+		Some("0x" | "0X") => 16,
+		Some("0o" | "0O") => 8,
+		Some("0b" | "0B") => 2,
+		_ => 10,
+	};
+	let result = match radix {
+		// Parsed with the sign, as the smallest int has no positive
+		10 if negative => format!("-{literal}").parse::<Int>(),
+		10 => literal.parse::<Int>(),
+		// Other radixes write the int's bits, which are then negated
+		_ => u32::from_str_radix(&literal[2..], radix).map(|bits| bits as Int),
+	};
+	let overflow = || QuMsg::number_literal_overflow(&token.slice, "int");
+	return match result {
+		Ok(value) if negative && radix != 10 => value.checked_neg()
+			.map(Number::Int)
+			.ok_or_else(overflow),
+		Ok(value) => Ok(Number::Int(value)),
+		Err(err) => match err.kind() {
+			IntErrorKind::PosOverflow | IntErrorKind::NegOverflow =>
+				Err(overflow()),
+			_ => Err(QuMsg::invalid_number_literal(&token.slice)),
+		},
+	};
+}
+
+
 #[cfg(test)]
 mod test_qu_matcher {}
@@ -0,0 +1,32 @@
+use crate::QuMsg;
+use crate::Uuid;
+use crate::compiler::ModuleId;
+
+
+/// A script compiled by [`Qu::compile`](crate::Qu::compile).
+///
+/// A script can be run any number of times with
+/// [`Qu::run_script`](crate::Qu::run_script) without being compiled again.
+/// It can only be run by the [`Qu`](crate::Qu) instance that compiled it.
+#[derive(Debug, Clone)]
+pub struct Script {
+	/// The unique identifier of the Qu instance that compiled the script.
+	pub(crate) uuid: Uuid,
+	/// The code block of the script's top level code.
+	pub(crate) code_block: usize,
+	/// The module the script's items were compiled into.
+	pub(crate) module_id: ModuleId,
+	/// The warnings and notes found while compiling the script.
+	pub(crate) diagnostics: Vec<QuMsg>,
+} impl Script {
+	/// Returns the warnings and notes found while compiling the script.
+	pub fn diagnostics(&self) -> &[QuMsg] {
+		return &self.diagnostics;
+	}
+
+
+	/// Returns the id of the module the script's items were compiled into.
+	pub fn module_id(&self) -> ModuleId {
+		return self.module_id;
+	}
+}
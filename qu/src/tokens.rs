@@ -4,241 +4,398 @@ use std::fmt::Display;
 use std::fmt::Debug;
 
 use crate::parser::KEYWORD_VAR;
+use crate::parser::KEYWORD_BOOL_TRUE;
+use crate::parser::KEYWORD_BOOL_FALSE;
 use crate::parser::KEYWORD_FN;
 use crate::parser::KEYWORD_CLASS;
 use crate::parser::KEYWORD_IF;
+use crate::parser::KEYWORD_BREAK;
+use crate::parser::KEYWORD_CONTINUE;
 use crate::parser::KEYWORD_ELSE;
 use crate::parser::KEYWORD_ELIF;
+use crate::parser::KEYWORD_FOR;
+use crate::parser::KEYWORD_WHILE;
+use crate::parser::KEYWORD_RETURN;
+use crate::parser::KEYWORD_IMPORT;
+use crate::parser::KEYWORD_IN;
+use crate::parser::KEYWORD_IMPL;
+use crate::parser::KEYWORD_TRAIT;
+use crate::parser::OP_EXPR_AND;
+use crate::parser::OP_EXPR_NOT;
+use crate::parser::OP_EXPR_OR;
+
+
+/// Words that can't be used as names.
+const KEYWORDS:&[&str] = &[
+	KEYWORD_VAR,
+	KEYWORD_FN,
+	KEYWORD_CLASS,
+	KEYWORD_IF,
+	KEYWORD_ELSE,
+	KEYWORD_ELIF,
+	KEYWORD_FOR,
+	KEYWORD_WHILE,
+	KEYWORD_IN,
+	KEYWORD_BREAK,
+	KEYWORD_CONTINUE,
+	KEYWORD_TRAIT,
+	KEYWORD_IMPL,
+	KEYWORD_RETURN,
+	KEYWORD_IMPORT,
+	KEYWORD_BOOL_TRUE,
+	KEYWORD_BOOL_FALSE,
+	OP_EXPR_AND,
+	OP_EXPR_NOT,
+	OP_EXPR_OR,
+];
 
+/// Symbols made of two characters.
+const SYMBOLS_DOUBLE:&[&str] = &[
+	"**", "//", "==", "!=", ">=", "<=", "+=", "-=", "*=", "/=", "%=",
+];
 
-pub const TOKEN_TYPE_KEYWORD:u8 = 1;
-pub const TOKEN_TYPE_SYMBOL:u8 = 2;
-pub const TOKEN_TYPE_NUMBER:u8 = 3;
-pub const TOKEN_TYPE_NAME:u8 = 4;
+/// Symbols made of a single character.
+const SYMBOLS_SINGLE:&str = ".*/\\=!?><+-%&@|^:;,()[]{}`";
+
+/// Makes a number literal a float (Ex: 2f).
+const FLOAT_SUFFIX:char = 'f';
+
+/// Starts a comment that ends at the end of the line.
+const COMMENT_LINE:char = '#';
+/// Starts a comment that ends with [`COMMENT_BLOCK_END`].
+const COMMENT_BLOCK_START:&str = "#/";
+/// Ends a comment started with [`COMMENT_BLOCK_START`].
+const COMMENT_BLOCK_END:&str = "/#";
+
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The kind of a [`QuToken`].
+pub enum TokenKind {
+	/// A word with a special meaning, like `var` or `if`.
+	Keyword,
+	/// The name of a variable, function, class, etc.
+	Name,
+	/// An integer literal, like `12`.
+	Int,
+	/// A float literal, like `3.12`.
+	Float,
+	/// A string literal, like `"text"`, including its quotes.
+	String,
+	/// An operator or punctuation, like `+` or `(`.
+	Symbol,
+	/// A character that doesn't start any token.
+	#[default]
+	Unknown,
+	/// The end of a script.
+	End,
+}
 
 
-/// Base token rules for the Qu language.
-pub const RULES:&Rules = &[
-	(&tokenrule_keyword, TOKEN_TYPE_KEYWORD),
-	(&tokenrule_symbols, TOKEN_TYPE_SYMBOL),
-	(&tokenrule_number, TOKEN_TYPE_NUMBER),
-	(&tokenrule_identity, TOKEN_TYPE_NAME),
-];
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How the indentation at the start of a line is measured.
+pub enum Indentation {
+	/// Every tab is a level of indentation. Spaces are ignored.
+	#[default]
+	Tabs,
+	/// Every given number of spaces is a level of indentation. Tabs are still
+	/// a level each.
+	Spaces(u8),
+}
 
 
-/// A [Vec] of tokenfule_* functions and their types.
-pub type Rules<'a> = [(&'a dyn Fn(&[char])->bool, u8)];
+/// Tokenizes a script in a single pass.
+pub fn tokenize(script:&str, indentation:Indentation) -> Vec<QuToken> {
+	let mut lexer = Lexer::new(script, indentation);
+	lexer.run();
+	return lexer.tokens;
+}
 
 
-/// Returns *true* if the passed characters match to a name.
-pub fn tokenrule_identity(added_so_far:&[char]) -> bool {
-	for char in  added_so_far {
-		if *char == ' ' {
-			return false;
-		}
-		if !char.is_alphanumeric() && *char != '_' {
-			return false;
+/// Splits a script into [`QuToken`]s.
+struct Lexer<'a> {
+	script:&'a str,
+	indentation:Indentation,
+	tokens:Vec<QuToken>,
+	/// The byte index of the next character.
+	index:usize,
+	/// The row of the next character.
+	row:u32,
+	/// The number of characters read on the current row.
+	column:u32,
+	/// The indentation of the current row. Rows indented more than `u8::MAX`
+	/// levels count as `u8::MAX` levels.
+	indent:u8,
+	/// The number of spaces at the start of the current row that haven't
+	/// made up a full level of indentation yet.
+	spaces:u8,
+	/// True until a token is found on the current row.
+	in_new_line:bool,
+
+} impl<'a> Lexer<'a> {
+
+	fn new(script:&'a str, indentation:Indentation) -> Self {
+		return Self {
+			script,
+			indentation,
+			// Most tokens are followed by at least one other character
+			tokens: Vec::with_capacity(script.len() / 2),
+			index: 0,
+			row: 0,
+			column: 0,
+			indent: 0,
+			spaces: 0,
+			in_new_line: true,
+		};
+	}
+
+
+	/// Reads every token of the script.
+	fn run(&mut self) {
+		while let Some(char) = self.peek(0) {
+			match char {
+				'\n' => {
+					self.bump();
+					self.row += 1;
+					self.column = 0;
+					self.indent = 0;
+					self.spaces = 0;
+					self.in_new_line = true;
+				},
+				'\t' => {
+					if self.in_new_line {
+						self.indent = self.indent.saturating_add(1);
+					}
+					self.bump();
+				},
+				' ' => {
+					if self.in_new_line {
+						self.count_space();
+					}
+					self.bump();
+				},
+				COMMENT_LINE => self.skip_comment(),
+				'"' | '\'' => self.read_string(char),
+				_ if char.is_whitespace() => self.bump(),
+				_ if char.is_ascii_digit() => self.read_number(),
+				_ if char.is_alphanumeric() || char == '_' => self.read_name(),
+				_ => self.read_symbol(),
+			}
 		}
-		if char.is_numeric() && added_so_far.len() == 0 {
-			return false;
+	}
+
+
+	/// Counts a space at the start of a row towards its indentation.
+	fn count_space(&mut self) {
+		let Indentation::Spaces(per_level) = self.indentation else {
+			return;
+		};
+		self.spaces += 1;
+		if self.spaces >= per_level {
+			self.indent = self.indent.saturating_add(1);
+			self.spaces = 0;
 		}
 	}
 
-	return true;
-}
 
+	/// Returns the character `at` characters ahead without reading it.
+	fn peek(&self, at:usize) -> Option<char> {
+		return self.script[self.index..].chars().nth(at);
+	}
 
-/// Returns *true* if the passed characters match to a number (like *int* or
-/// *float*).
-pub fn tokenrule_number(added_so_far:&[char]) -> bool {
-	for char in  added_so_far {
-		if char == &' ' {
-			return false;
-		}
-		if *char == '_' && added_so_far.len() == 0 {
-			return false;
-		}
-		if !(char.is_numeric()) && *char != '_'{
-			return false;
+
+	/// Reads the next character.
+	fn bump(&mut self) {
+		if let Some(char) = self.peek(0) {
+			self.index += char.len_utf8();
+			self.column += 1;
 		}
 	}
 
-	return true;
-}
-
 
-/// Returns *true* if the passed characters match to a keyword.
-pub fn tokenrule_keyword(added_so_far:&[char]) -> bool {
-	for word in [
-		KEYWORD_VAR,
-		KEYWORD_FN,
-		KEYWORD_CLASS,
-		KEYWORD_IF,
-		KEYWORD_ELSE,
-		KEYWORD_ELIF,
-	] {
-		let mut mismatched = false;
-		for (char1, char2) in added_so_far.iter().zip(word.chars()) {
-			mismatched = char1 != &char2;
-			if mismatched {
+	/// Reads characters while `condition` is true.
+	fn bump_while(&mut self, condition:impl Fn(char) -> bool) {
+		while let Some(char) = self.peek(0) {
+			if !condition(char) {
 				break;
 			}
-		}
-		if !mismatched {
-			return true && added_so_far.len() == word.len();
+			self.bump();
 		}
 	}
 
-	return false;
-}
-
 
-/// Returns *true* if the passed characters match to a symbol.
-pub fn tokenrule_symbols(added_so_far:&[char]) -> bool {
-	return match added_so_far {
-		['.',] => true,
-		['*',] => true,
-		['/',] => true,
-		['\\',] => true, // This is just '\'
-		['=',] => true,
-		['!',] => true,
-		['?',] => true,
-		['>',] => true,
-		['<',] => true,
-		['+',] => true,
-		['-',] => true,
-		['%',] => true,
-		['*', '*',] => true,
-		['/', '/',] => true,
-		['=', '=',] => true,
-		['!', '=',] => true,
-		['>', '=',] => true,
-		['<', '=',] => true,
-		['+', '=',] => true,
-		['-', '=',] => true,
-		['*', '=',] => true,
-		['/', '=',] => true,
-		['%', '=',] => true,
-		['&',] => true,
-		['@',] => true,
-		['|',] => true,
-		['^',] => true,
-		[':',] => true,
-		[';',] => true,
-		[',',] => true,
-		['(',] => true,
-		[')',] => true,
-		['[',] => true,
-		[']',] => true,
-		['{',] => true,
-		['}',] => true,
-		['`',] => true,
-		['"',] => true,
-		['\'',] => true,
-		_ => false,
-	};
-}
+	/// Adds a token of everything read since the byte index `start`, which
+	/// was at `column`.
+	fn push_token(&mut self, kind:TokenKind, start:usize, column:u32) {
+		let mut token = QuToken::new(
+			self.row,
+			column,
+			self.indent,
+			kind,
+			&self.script[start..self.index],
+		);
+		token.start = start;
+		token.end = self.index;
+		self.tokens.push(token);
+		self.in_new_line = false;
+	}
 
 
-/// Tokenizes a [String] according to the passed [Rules].
-pub fn tokenize<'a>(script:&'a str, rules:&Rules<'a>) -> Vec<QuToken> {
-	let mut tokens = vec!();
+	/// Reads a name or keyword.
+	fn read_name(&mut self) {
+		let (start, column) = (self.index, self.column+1);
+		self.bump_while(|c| c.is_alphanumeric() || c == '_');
+		let kind = match KEYWORDS.contains(&&self.script[start..self.index]) {
+			true => TokenKind::Keyword,
+			false => TokenKind::Name,
+		};
+		self.push_token(kind, start, column);
+	}
 
-	/* WARNING: This does not account for grapheme clusters. Currently hoping
-	This won't be a problem. */
-	let mut row:u32 = 0;
-	let mut column:u8 = 0;
-	let mut indent:u8 = 0;
-	let mut tk_start:usize = 0;
-	let mut in_new_line:bool = true;
-	let mut added_so_far:Vec<char> = Vec::with_capacity(20);
-	let mut curr_token = 0;
 
-	for (index, char) in script.char_indices() {
-		column += 1;
+	/// Reads an int or float literal.
+	/// 
+	/// Ints can be written in hexadecimal (`0xFF`), binary (`0b1010`) or octal
+	/// (`0o17`). Floats can have a decimal part (`3.12`), an exponent (`1e-3`)
+	/// or an `f` suffix (`2f`). Any number can be separated with underscores
+	/// (`1_000_000`).
+	fn read_number(&mut self) {
+		let (start, column) = (self.index, self.column+1);
+
+		// Ints with a radix prefix. Every following letter is read, so that
+		// invalid digits are reported by the parser.
+		let has_prefix = self.peek(0) == Some('0') && matches!(
+			self.peek(1),
+			Some('x' | 'X' | 'b' | 'B' | 'o' | 'O'),
+		);
+		if has_prefix {
+			self.bump();
+			self.bump();
+			self.bump_while(|c| c.is_ascii_alphanumeric() || c == '_');
+			self.push_token(TokenKind::Int, start, column);
+			return;
+		}
 
-		if char != '\t' && char != ' ' {
-			in_new_line = false;
+		self.bump_while(|c| c.is_ascii_digit() || c == '_');
+		let mut is_float = false;
+
+		// Numbers after a dot index a value, so they can't have a decimal
+		// part (Ex: foo.0.1)
+		let after_dot = self.tokens.last()
+			.is_some_and(|tk| tk.kind == TokenKind::Symbol && tk.slice == ".");
+
+		// A dot followed by a name indexes the number (Ex: 5.abs())
+		let has_decimal = !after_dot && self.peek(0) == Some('.')
+			&& !self.peek(1).is_some_and(|c| c.is_alphabetic() || c == '_');
+		if has_decimal {
+			self.bump();
+			self.bump_while(|c| c.is_ascii_digit() || c == '_');
+			is_float = true;
 		}
 
-		// Check tab
-		if char == '\t' {
-			if in_new_line {
-				indent += 1;
-				added_so_far.clear();
+		// Exponent. An `e` is read as part of the number even without
+		// digits after it, so that the parser reports the malformed exponent
+		// (Ex: 1e).
+		if matches!(self.peek(0), Some('e' | 'E')) {
+			self.bump();
+			if matches!(self.peek(0), Some('+' | '-'))
+				&& self.peek(1).is_some_and(|c| c.is_ascii_digit())
+			{
+				self.bump();
 			}
-			
-		// Check newline
-		} else if char == '\n' {
-			column = 0;
-			row += 1;
-			indent = 0;
-			in_new_line = true;		
+			self.bump_while(|c| c.is_ascii_alphanumeric() || c == '_');
+			is_float = true;
 		}
 
-		// Any other characters
-		added_so_far.push(char);
-		
-		// Update token end if it fits rule, 
-		// otherwise clear the added so far
-		loop {
-			let (does_fit, tk_type) = chars_fit_rule(
-				&added_so_far,
-				rules,
-			);
-			if does_fit{
-				if curr_token <= tokens.len() && added_so_far.len() == 1 {
-					tokens.push(QuToken::new(
-						row,
-						column,
-						indent,
-						tk_type,
-						""
-					));
-					tk_start = index;
-				}
-				tokens[curr_token].tk_type = tk_type;
-				if index+1 == script.len() {
-					tokens[curr_token].slice
-						= script[tk_start..=index].to_owned();
-				}
+		// Float suffix
+		let has_suffix = self.peek(0) == Some(FLOAT_SUFFIX)
+			&& !self.peek(1).is_some_and(|c| c.is_alphanumeric() || c == '_');
+		if has_suffix {
+			self.bump();
+			is_float = true;
+		}
+
+		let kind = match is_float {
+			true => TokenKind::Float,
+			false => TokenKind::Int,
+		};
+		self.push_token(kind, start, column);
+	}
+
+
+	/// Reads a string literal that starts with `quote`.
+	/// 
+	/// Escaped quotes don't close the string. A string that is never closed
+	/// ends at the end of its row, so the parser can report it.
+	fn read_string(&mut self, quote:char) {
+		let (start, column) = (self.index, self.column+1);
+		self.bump();
+		let mut escaped = false;
+		while let Some(char) = self.peek(0) {
+			if char == '\n' {
 				break;
-				
-			} else if added_so_far.len() == 1 {
-				added_so_far.clear();
+			}
+			self.bump();
+			if escaped {
+				escaped = false;
+			} else if char == '\\' {
+				escaped = true;
+			} else if char == quote {
 				break;
-			}else {
-				if curr_token+1 == tokens.len() {
-					tokens[curr_token].slice
-						= script[tk_start..index].to_owned();
-					curr_token += 1;
-				}
-				added_so_far.clear();
-				added_so_far.push(char);
 			}
 		}
+		self.push_token(TokenKind::String, start, column);
+	}
+
+
+	/// Reads a symbol, or a single character if it isn't part of a symbol.
+	fn read_symbol(&mut self) {
+		let (start, column) = (self.index, self.column+1);
+		let rest = &self.script[self.index..];
+		if SYMBOLS_DOUBLE.iter().any(|symbol| rest.starts_with(symbol)) {
+			self.bump();
+			self.bump();
+			self.push_token(TokenKind::Symbol, start, column);
+			return;
+		}
+
+		let char = self.peek(0).unwrap_or_default();
+		self.bump();
+		let kind = match SYMBOLS_SINGLE.contains(char) {
+			true => TokenKind::Symbol,
+			false => TokenKind::Unknown,
+		};
+		self.push_token(kind, start, column);
 	}
 
-	return tokens;
-}
 
+	/// Skips a single line or a multi-line comment.
+	fn skip_comment(&mut self) {
+		if !self.script[self.index..].starts_with(COMMENT_BLOCK_START) {
+			self.bump_while(|c| c != '\n');
+			return;
+		}
 
-/// Checks a [`Vec`] of [`char`]s against [`RULES`].
-/// 
-/// Returns *true* if the [`Vec`] of [`char`]s fits at least one of the rules
-/// specified in [`RULES`].
-pub fn chars_fit_rule<'a>(chars:&Vec<char>, rules:&Rules<'a>) -> (bool, u8) {
-	let mut fits_rule = false;
-	let mut tk_type = u8::MAX;
-	for rule in rules {
-		fits_rule = fits_rule || rule.0(&chars);
-		if fits_rule{
-			tk_type = rule.1;
-			break;
+		self.bump();
+		self.bump();
+		while self.index < self.script.len() {
+			if self.script[self.index..].starts_with(COMMENT_BLOCK_END) {
+				self.bump();
+				self.bump();
+				return;
+			}
+			if self.peek(0) == Some('\n') {
+				self.index += 1;
+				self.row += 1;
+				self.column = 0;
+				self.indent = 0;
+				self.spaces = 0;
+				self.in_new_line = true;
+				continue;
+			}
+			self.bump();
 		}
 	}
 
-	return (fits_rule, tk_type);
 }
 
 
@@ -247,8 +404,12 @@ pub fn chars_fit_rule<'a>(chars:&Vec<char>, rules:&Rules<'a>) -> (bool, u8) {
 #[derive(Clone, Default)]
 pub struct QuToken {
 	pub char_index:QuCharIndex,
-	/// The type of this token.
-	pub tk_type:u8,
+	/// The byte index in the script where this token starts.
+	pub start:usize,
+	/// The byte index in the script right after this token ends.
+	pub end:usize,
+	/// The kind of this token.
+	pub kind:TokenKind,
 	/// The slice of text this token represents.
 	pub slice:String,
 
@@ -256,11 +417,13 @@ pub struct QuToken {
 
 	/// Makes a new [`QuToken`].
 	pub fn new(
-			row:u32, column:u8, indent:u8, tk_type:u8, slice:&str
+			row:u32, column:u32, indent:u8, kind:TokenKind, slice:&str
 	) -> QuToken {
 		return QuToken {
 			char_index: QuCharIndex::new(row, column, indent),
-			tk_type,
+			start: 0,
+			end: 0,
+			kind,
 			slice: slice.to_owned(),
 		};
 	}
@@ -269,11 +432,24 @@ pub struct QuToken {
 	pub fn from(slice:&str) -> QuToken {
 		return Self {
 			char_index: QuCharIndex::default(),
-			tk_type: 0,
+			start: 0,
+			end: 0,
+			kind: TokenKind::Unknown,
 			slice: slice.to_owned(),
 		};
 	}
 
+
+	/// Returns the [`QuSpan`] of the script this token was sliced from.
+	pub fn span(&self) -> QuSpan {
+		return QuSpan {
+			start: self.start,
+			end: self.end,
+			row: self.char_index.row,
+			column: self.char_index.column,
+		};
+	}
+
 } impl Display for QuToken {
 	
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -289,28 +465,28 @@ pub struct QuToken {
 	}
 } impl PartialEq for QuToken {
 	fn eq(&self, other:&Self) -> bool {
-		if self.tk_type == u8::MAX {
+		if self.kind == TokenKind::End {
 			return false;
 		}
 		return self.slice == other.slice;
 	}
 } impl PartialEq<str> for QuToken {
 	fn eq(&self, other:&str) -> bool {
-		if self.tk_type == u8::MAX {
+		if self.kind == TokenKind::End {
 			return false;
 		}
 		return self.slice == other;
 	}
 } impl PartialEq<&str> for QuToken {
 	fn eq(&self, other:&&str) -> bool {
-		if self.tk_type == u8::MAX {
+		if self.kind == TokenKind::End {
 			return false;
 		}
 		return self.slice == *other;
 	}
 } impl PartialEq<String> for QuToken {
 	fn eq(&self, other:&String) -> bool {
-		if self.tk_type == u8::MAX {
+		if self.kind == TokenKind::End {
 			return false;
 		}
 		return &self.slice == other;
@@ -340,12 +516,12 @@ pub struct QuCharIndex {
 	/// The row this chararacter is on.
 	pub row:u32,
 	/// The column this chararacter on.
-	pub column:u8,
+	pub column:u32,
 	/// The indentation of this chararacter.
 	pub indent:u8,
 } impl QuCharIndex {
 
-	fn new(row:u32, column:u8, indent:u8) -> Self {
+	fn new(row:u32, column:u32, indent:u8) -> Self {
 		return Self {
 			row,
 			column,
@@ -353,4 +529,30 @@ pub struct QuCharIndex {
 		};
 	}
 
-}
\ No newline at end of file
+}
+
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// A range of bytes in a script, along with the row and column it starts at.
+pub struct QuSpan {
+	/// The byte index where the span starts.
+	pub start:usize,
+	/// The byte index right after the span ends.
+	pub end:usize,
+	/// The row the span starts on.
+	pub row:u32,
+	/// The column the span starts on.
+	pub column:u32,
+} impl QuSpan {
+
+	/// Returns a span that starts at this span and ends at `other`.
+	pub fn to(&self, other:&QuSpan) -> QuSpan {
+		return QuSpan {
+			start: self.start,
+			end: other.end.max(self.end),
+			row: self.row,
+			column: self.column,
+		};
+	}
+
+}
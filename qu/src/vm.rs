@@ -1,20 +1,46 @@
 
+use std::any::Any;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::mem::needs_drop;
 use std::mem::size_of;
-
+use std::ops::Bound;
+use std::ops::RangeBounds;
+
+use crate::Bool;
+use crate::Callable;
+use crate::Class;
+use crate::Float;
+use crate::Function;
+use crate::Int;
+use crate::Module;
+use crate::QuErrorKind;
 use crate::QuMsg;
 use crate::Register;
+use crate::Str;
+use crate::Void;
 use crate::Uuid;
 use crate::compiler::ConstantId;
 use crate::compiler::Definitions;
+use crate::compiler::FunctionIdentity;
 use crate::compiler::FunctionReference;
+use crate::compiler::ItemId;
+use crate::compiler::VariableId;
 use crate::import::ArgsAPI;
 use crate::import::ClassId;
+use crate::import::DYN_HEADER_SIZE;
 use crate::import::FunctionId;
+use crate::objects::Capture;
 use crate::objects::fundamentals_module;
 use crate::objects::math_module;
 
 pub const MAIN_MODULE:&str = "__main__";
+/// The number of bytes of registers each call can use, starting at its
+/// frame's offset.
+pub(crate) const FRAME_SIZE:usize = u8::MAX as usize;
+/// How many calls deep scripts can go before they're stopped, such as by a
+/// function that calls itself without end.
+pub(crate) const MAX_CALL_DEPTH:usize = 256;
 
 #[derive(Clone)]
 /// The low level operations of [`QuVm`].
@@ -23,10 +49,17 @@ pub(crate) enum QuOp {
 	Call(FunctionId, Box<[RegId]>, RegId),
 	/// Calls a function defined by Qu via a vtable.
 	/// 
-	/// Looks up a function that is overriden by the given class.
+	/// Looks up the function that overrides the trait's (first [`ClassId`])
+	/// function in the class of the first argument. The second [`ClassId`] is
+	/// the class of the first argument as known when compiling. If it's a
+	/// trait then the argument's class is read from the argument at runtime.
 	CallV(ClassId, ClassId, FunctionId, Box<[RegId]>, RegId),
+	/// Calls the [`Callable`] in the first register.
+	CallValue(RegId, Box<[RegId]>, RegId),
 	/// Ends the current scope
 	End,
+	/// Sets *hold* to the [`Bool`] value in the register.
+	Hold(RegId),
 	/// Moves the program counter by the given [`isize`].
 	JumpBy(isize),
 	/// Moves the program counter by the given [`isize`] if the last expression
@@ -36,19 +69,31 @@ pub(crate) enum QuOp {
 	LoadArg(u8, RegId),
 	/// Loads a constant onto the stack
 	LoadConstant(ConstantId, RegId),
+	/// Copies a static variable onto the stack
+	LoadStatic(VariableId, RegId),
+	/// Makes a [`Callable`] of a function that captures the values in the
+	/// registers.
+	MakeCallable(FunctionId, Box<[RegId]>, RegId),
 	/// Specifies to the Vm what class can be retrieved from the API.
 	Return(ClassId),
+	/// Copies a value from the stack into a static variable
+	StoreStatic(RegId, VariableId),
 } impl QuOp {
 	pub(crate) fn get_output(&self) -> RegId {
 		match self {
 			QuOp::Call(_, _, output) => *output,
 			QuOp::CallV(_, _, _, _, output) => *output,
+			QuOp::CallValue(_, _, output) => *output,
 			QuOp::End => unreachable!(),
+			QuOp::Hold(_) => unreachable!(),
 			QuOp::JumpBy(_) => unreachable!(),
 			QuOp::JumpByIfNot(_) => unreachable!(),
 			QuOp::LoadArg(_, output) => *output,
 			QuOp::LoadConstant(_, output) => *output,
+			QuOp::LoadStatic(_, output) => *output,
+			QuOp::MakeCallable(_, _, output) => *output,
 			QuOp::Return(_) => unreachable!(),
+			QuOp::StoreStatic(_, _) => unreachable!(),
 		}
 	}
 
@@ -61,8 +106,24 @@ pub(crate) enum QuOp {
 				Self::readable_args(args),
 				fn_id.0,
 			),
-			QuOp::CallV(_, _, _, _, _) => todo!(),
+			QuOp::CallV(trait_id, _, fn_id, args, reg) => format!(
+				"{0} = {1}.{2}:{4}( {3} )",
+				reg.readable(),
+				d.get_class(*trait_id)
+					.map(|class| class.common.name.as_str())
+					.unwrap_or("?"),
+				Self::readable_fn(*fn_id, d),
+				Self::readable_args(args),
+				fn_id.0,
+			),
+			QuOp::CallValue(callable, args, reg) => format!(
+				"{} = {}( {} )",
+				reg.readable(),
+				callable.readable(),
+				Self::readable_args(args),
+			),
 			QuOp::End => "End".into(),
+			QuOp::Hold(reg) => format!("hold {}", reg.readable()),
 			QuOp::JumpBy(by) => format!("JumpyBy ({by})"),
 			QuOp::JumpByIfNot(by) => format!("JumpyByIfNot ({by})"),
 			QuOp::LoadArg(arg, reg) => format!(
@@ -75,7 +136,29 @@ pub(crate) enum QuOp {
 				reg.readable(),
 				Self::readable_const(*con, d),
 			),
-			QuOp::Return(_) => "return".into(),
+			QuOp::LoadStatic(var, reg) => format!(
+				"{} = static {}",
+				reg.readable(),
+				d.static_variables[*var].name,
+			),
+			QuOp::MakeCallable(fn_id, captures, reg) => format!(
+				"{} = callable {}:{}[ {} ]",
+				reg.readable(),
+				Self::readable_fn(*fn_id, d),
+				fn_id.0,
+				Self::readable_args(captures),
+			),
+			QuOp::Return(class_id) => format!(
+				"return {}",
+				d.get_class(*class_id)
+					.map(|class| class.common.name.as_str())
+					.unwrap_or("?"),
+			),
+			QuOp::StoreStatic(reg, var) => format!(
+				"static {} = {}",
+				d.static_variables[*var].name,
+				reg.readable(),
+			),
 		}
 	}
 
@@ -104,6 +187,8 @@ pub(crate) enum QuOp {
 				write!(f, "&{:?} = {:?}({:?}) (Call)", arg2, arg0, arg1),
 			QuOp::End =>
 				write!(f, "End"),
+			QuOp::Hold(arg0) =>
+				write!(f, "Hold({:?})", arg0),
 			QuOp::JumpBy(arg0) =>
 				write!(f, "JumpBy({:?})", arg0),
 			QuOp::JumpByIfNot(arg0) =>
@@ -112,15 +197,83 @@ pub(crate) enum QuOp {
 				write!(f, "&{arg1:?} = LoadArg({arg0:?})"),
 			QuOp::LoadConstant(arg0, arg1) =>
 				write!(f, "&{:?} = LoadConstant({:?})", arg1, arg0),
+			QuOp::LoadStatic(arg0, arg1) =>
+				write!(f, "&{:?} = LoadStatic({:?})", arg1, arg0),
+			QuOp::MakeCallable(arg0, arg1, arg2) =>
+				write!(f, "&{:?} = MakeCallable({:?}, {:?})", arg2, arg0, arg1),
 			QuOp::Return(arg0) =>
 				write!(f, "Return({:?})", arg0),
+			QuOp::StoreStatic(arg0, arg1) =>
+				write!(f, "StoreStatic({:?}) = &{:?}", arg1, arg0),
     		QuOp::CallV(arg0,  arg1, arg2, arg3, arg4) => 
-				write!(f, "&{arg4:?} = {arg0:?}@{arg1:?}.{arg2:?}({arg3:?}) (CallV)")
+				write!(f, "&{arg4:?} = {arg0:?}@{arg1:?}.{arg2:?}({arg3:?}) (CallV)"),
+			QuOp::CallValue(arg0, arg1, arg2) =>
+				write!(f, "&{:?} = &{:?}({:?}) (CallValue)", arg2, arg0, arg1),
 		}
 	}
 }
 
 
+/// Returns every code block in `d` as readable text.
+/// 
+/// Each block is headed by the functions compiled into it, and the targets
+/// of jumps are shown as labels (Ex: `jump L0 if not held`).
+pub(crate) fn disassemble(d:&Definitions) -> String {
+	let mut block_functions = vec![vec![]; d.byte_code_blocks.len()];
+	for function in &d.functions {
+		let FunctionReference::Internal(block) = function.code_block
+			else { continue };
+		if let Some(functions) = block_functions.get_mut(block) {
+			functions.push(function.identity.display_pretty(d));
+		}
+	}
+
+	let mut text = String::new();
+	for (i, block) in d.byte_code_blocks.iter().enumerate() {
+		let heading = match block_functions[i].is_empty() {
+			true => "top level".to_owned(),
+			false => format!("fn {}", block_functions[i].join(", fn ")),
+		};
+		text.push_str(&format!("block {i}: {heading}\n"));
+
+		// The program counter moves past a jump after jumping
+		let jump_target = |pc:usize, by:isize| pc as isize + by + 1;
+		let mut targets:Vec<isize> = block.iter()
+			.enumerate()
+			.filter_map(|(pc, op)| match op {
+				QuOp::JumpBy(by) | QuOp::JumpByIfNot(by) => Some(jump_target(pc, *by)),
+				_ => None,
+			})
+			.collect();
+		targets.sort();
+		targets.dedup();
+		let label = |target:isize| match targets.binary_search(&target) {
+			Ok(index) => format!("L{index}"),
+			Err(_) => "?".to_owned(),
+		};
+
+		for (pc, op) in block.iter().enumerate() {
+			if targets.contains(&(pc as isize)) {
+				text.push_str(&format!("{}:\n", label(pc as isize)));
+			}
+			let readable = match op {
+				QuOp::JumpBy(by) => format!("jump {}", label(jump_target(pc, *by))),
+				QuOp::JumpByIfNot(by) => format!(
+					"jump {} if not held",
+					label(jump_target(pc, *by)),
+				),
+				op => op.readable(d),
+			};
+			text.push_str(&format!("\t{pc:>4}  {readable}\n"));
+		}
+		if targets.contains(&(block.len() as isize)) {
+			text.push_str(&format!("{}:\n", label(block.len() as isize)));
+		}
+	}
+	return text;
+}
+
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct QuConstId(pub usize);
 impl From<usize> for QuConstId {
@@ -303,13 +456,26 @@ impl From<TypedRegId> for u8 {
 pub struct QuVm {
 	/// Holds the outputed value of the last executed operation.
 	pub hold_is_true: bool,
-	/// Holds the value returned from a Qu script.
-	return_type: ClassId,
+	/// Holds the class of the value returned from a Qu script, if any.
+	return_type: Option<ClassId>,
 	/// Contains all the defined class, funcitons, and more for the Vm. 
 	pub definitions: Definitions,
 	/// Holds the Vm's memory.
 	stack: VmStack,
+	/// Holds the values of static variables.
+	statics: Vec<u8>,
+	/// Whether each static variable has been given a value.
+	stored_statics: Vec<bool>,
+	/// Owns the values written by [`QuVm::write`] that need to be dropped,
+	/// by the index in the stack they were written to. The stack only holds
+	/// bitwise copies of these values.
+	owned_values: BTreeMap<usize, Box<dyn Any>>,
+	/// Owns the values of static variables that need to be dropped, by their
+	/// index in `statics`.
+	static_values: BTreeMap<usize, Box<dyn Any>>,
 	args: Vec<Box<[u8]>>,
+	/// The number of calls to code blocks that haven't returned yet.
+	call_depth: usize,
 
 } impl QuVm {
 
@@ -325,7 +491,7 @@ pub struct QuVm {
 
 		let vm = QuVm { 
 			hold_is_true: false,
-			stack: VmStack::new(u8::MAX as usize),
+			stack: VmStack::new(FRAME_SIZE),
 			definitions: def,
 			..Default::default()
 		};
@@ -334,10 +500,10 @@ pub struct QuVm {
 	}
 
 
-	fn call_function(
+	pub(crate) fn call_function(
 		&mut self,
 		fn_id: FunctionId,
-		args: Box<[RegId]>,
+		args: &[RegId],
 		output: RegId,
 	) -> Result<(), QuMsg> {
 		let fn_data = self.definitions.get_function(fn_id)?;
@@ -354,31 +520,265 @@ pub struct QuVm {
 
 		match fn_data.code_block {
 			FunctionReference::Internal(code_block) => {
-				self.op_call_fn(code_block, output)
+				let return_size = self.definitions
+					.get_class(fn_data.identity.return_type)?
+					.size as usize;
+				let write_back = match fn_data.mutable_self {
+					true => Some((
+						args[0],
+						self.definitions.get_class(
+							fn_data.identity.parameters[0]
+						)?.size as usize,
+					)),
+					false => None,
+				};
+				self.op_call_fn(code_block, output, return_size, write_back)
 			},
 			FunctionReference::External(fn_ptr) => {
 				// Call the external function
 				let mut api = ArgsAPI {
 					vm: self,
 					fn_id,
-					arg_ids: &args,
+					arg_ids: args,
 					out_id: output,
 				};
 				(fn_ptr)(&mut api,)
 			},
+			FunctionReference::Dyn(function) => {
+				let mut api = ArgsAPI {
+					vm: self,
+					fn_id,
+					arg_ids: args,
+					out_id: output,
+				};
+				function.call(&mut api)
+			},
+			FunctionReference::Abstract => Err(QuMsg::of_kind(
+				QuErrorKind::Runtime,
+				format!(
+					"Function '{}' has no implementation.",
+					fn_data.identity.display_pretty(&self.definitions),
+				),
+			)),
 		}
 	}
 
 
+	/// Calls the function held by `callable`. Its captured values are passed
+	/// after the arguments.
+	pub(crate) fn call_callable(
+		&mut self,
+		callable: Callable,
+		args: &[RegId],
+		output: RegId,
+	) -> Result<(), QuMsg> {
+		// Which function is called is only known once the callable is read,
+		// so its arguments can't be checked when bytecode is loaded
+		let identity = &self.definitions.get_function(callable.fn_id)?.identity;
+		let mut fits_frame = identity.parameters.len()
+			== args.len() + callable.captures.len();
+		for (arg, param_id) in args.iter().zip(&identity.parameters) {
+			let size = self.definitions.get_class(*param_id)?.size as usize;
+			fits_frame &= arg.0 + size <= FRAME_SIZE;
+		}
+		if !fits_frame {
+			return Err(QuMsg::of_kind(QuErrorKind::Runtime, format!(
+				"Function '{}' can't be called with {} arguments.",
+				identity.display_pretty(&self.definitions),
+				args.len(),
+			)));
+		}
+		if callable.captures.is_empty() {
+			return self.call_function(callable.fn_id, args, output);
+		}
+
+		// Only lambdas capture values, and they're always compiled by Qu
+		let fn_data = self.definitions.get_function(callable.fn_id)?;
+		let FunctionReference::Internal(code_block) = fn_data.code_block else {
+			unreachable!()
+		};
+		self.args = Vec::with_capacity(args.len() + callable.captures.len());
+		for (arg, param_id) in args.iter().zip(&fn_data.identity.parameters) {
+			let size = self.definitions.get_class(*param_id)?.size;
+			self.args.push(Box::from(
+				self.stack.read_dyn(*arg, size as usize)
+			));
+		}
+		self.args.extend(
+			callable.captures.iter().map(|capture| capture.bytes.clone())
+		);
+		let return_size = self.definitions
+			.get_class(fn_data.identity.return_type)?
+			.size as usize;
+		// `callable` keeps the captured values alive until the call is done
+		return self.op_call_fn(code_block, output, return_size, None);
+	}
+
+
+	/// Calls the function that overrides the trait function `fn_id` in the
+	/// class of the first argument.
+	/// 
+	/// Trait-typed arguments are unwrapped to their values if the overriding
+	/// function expects a concrete class, and the output is wrapped into a
+	/// trait-typed value if the trait function returns one.
+	fn call_virtual(
+		&mut self,
+		trait_id: ClassId,
+		receiver_class: ClassId,
+		fn_id: FunctionId,
+		args: &[RegId],
+		output: RegId,
+	) -> Result<(), QuMsg> {
+		let d = &self.definitions;
+		let class_id = match d.get_class(receiver_class)?.is_trait {
+			true => *self.stack.read::<ClassId>(args[0]),
+			false => receiver_class,
+		};
+		let override_id = d.get_class(class_id)?
+			.common
+			.get_trait_implementation(trait_id)?
+			.get_implemented_item_maybe(ItemId::Function(fn_id));
+		let Some(ItemId::Function(override_id)) = override_id else {
+			// Not overridden, use the trait's own function
+			return self.call_function(fn_id, args, output);
+		};
+
+		let trait_fn = &d.get_function(fn_id)?.identity;
+		let override_fn = &d.get_function(override_id)?.identity;
+
+		// Unwrap arguments
+		let mut arg_regs = Vec::with_capacity(args.len());
+		for (i, arg) in args.iter().enumerate() {
+			let expected = override_fn.parameters[i];
+			let is_unwrapped = d.get_class(trait_fn.parameters[i])?.is_trait
+				&& !d.get_class(expected)?.is_trait;
+			if !is_unwrapped {
+				arg_regs.push(*arg);
+				continue;
+			}
+			let arg_class = *self.stack.read::<ClassId>(*arg);
+			if !arg_class.is(expected, d) {
+				return Err(QuMsg::of_kind(QuErrorKind::TypeMismatch, format!(
+					"Function '{}' expected argument {} to be of type '{}', but it's of type '{}'.",
+					override_fn.display_pretty(d),
+					i,
+					d.get_class(expected)?.common.name,
+					d.get_class(arg_class)?.common.name,
+				)));
+			}
+			arg_regs.push(RegId(arg.0 + DYN_HEADER_SIZE));
+		}
+
+		// Wrap output
+		let return_type = override_fn.return_type;
+		let is_wrapped = d.get_class(trait_fn.return_type)?.is_trait
+			&& !d.get_class(return_type)?.is_trait;
+		if !is_wrapped {
+			return self.call_function(override_id, &arg_regs, output);
+		}
+
+		// The header is written after the call because the output may
+		// overlap with the arguments.
+		self.call_function(
+			override_id,
+			&arg_regs,
+			RegId(output.0 + DYN_HEADER_SIZE),
+		)?;
+		self.stack.write(output, return_type);
+		Ok(())
+	}
+
+
+	/// Copies `size` bytes from one register to another.
+	pub(crate) fn copy_reg(&mut self, from: RegId, to: RegId, size: usize) {
+		let bytes = self.stack.read_dyn(from, size).to_vec();
+		self.stack.write_dyn(to, &bytes);
+	}
+
+
 	/// Returns the value returned by the last run Qu script.
 	/// 
 	/// # Examples
 	/// 
 	/// //TODO: Example
 	pub fn return_value_id(&mut self) -> ClassId {
-		let return_type = self.return_type;
-		self.return_type = 0.into();
-		return_type
+		self.return_type.take().unwrap_or_default()
+	}
+
+
+	/// Returns the class of the value returned by the last run Qu script, or
+	/// [`None`] if it did not return a value.
+	pub fn return_value_id_maybe(&mut self) -> Option<ClassId> {
+		self.return_type.take()
+	}
+
+
+	/// Makes a human readable [`String`] of the value at `reg`.
+	/// 
+	/// Values of classes that Qu can't read are shown by their class name.
+	pub(crate) fn display_value(
+		&self,
+		reg: RegId,
+		class_id: ClassId,
+	) -> Result<String, QuMsg> {
+		let d = &self.definitions;
+		let text = if class_id == Int::id() {
+			self.read::<Int>(reg)?.to_string()
+		} else if class_id == Float::id() {
+			format!("{:?}", self.read::<Float>(reg)?)
+		} else if class_id == Bool::id() {
+			self.read::<Bool>(reg)?.to_string()
+		} else if class_id == Void::id() {
+			"void".into()
+		} else if class_id == Class::id() {
+			d.get_class(self.read::<Class>(reg)?.id)?.common.name.clone()
+		} else if class_id == Module::id() {
+			d.get_module(self.read::<Module>(reg)?.id)?.common.name.clone()
+		} else if class_id == Function::id() {
+			let fn_id = self.read::<Function>(reg)?.id;
+			d.get_function(fn_id)?.identity.display_pretty(d)
+		} else if class_id == Str::id() {
+			format!("{:?}", self.read::<Str>(reg)?.as_str())
+		} else if d.get_class(class_id)?.function_type.is_some() {
+			// Callables are shown by their type
+			d.get_class(class_id)?.common.name.clone()
+		} else if d.get_class(class_id)?.is_trait {
+			// Display the value held by the trait-typed value
+			self.display_value(
+				RegId(reg.0 + DYN_HEADER_SIZE),
+				*self.stack.read::<ClassId>(reg),
+			)?
+		} else if d.get_class(class_id)?.is_tuple {
+			// Display the elements of a tuple
+			let mut elements = vec![];
+			for field in &d.get_class(class_id)?.fields {
+				elements.push(self.display_value(
+					RegId(reg.0 + field.offset),
+					field.class_id,
+				)?);
+			}
+			match elements.len() {
+				1 => format!("({},)", elements[0]),
+				_ => format!("({})", elements.join(", ")),
+			}
+		} else if d.get_class(class_id)?.from_script {
+			// Display the fields of a class declared in a script
+			let mut fields = vec![];
+			for field in &d.get_class(class_id)?.fields {
+				fields.push(format!(
+					"{}: {}",
+					field.name,
+					self.display_value(
+						RegId(reg.0 + field.offset),
+						field.class_id,
+					)?,
+				));
+			}
+			format!("({})", fields.join(", "))
+		} else {
+			format!("<{}>", d.get_class(class_id)?.common.name)
+		};
+		Ok(text)
 	}
 
 
@@ -389,25 +789,243 @@ pub struct QuVm {
 	}
 
 
+	/// Runs a code block in a new frame that starts at `output`.
+	/// 
+	/// The values owned by the frame are dropped when it ends, except for the
+	/// `return_size` bytes of output. If `write_back` is given, the changed
+	/// `self` that follows the output is moved back to that register, see
+	/// `FunctionMetadata::mutable_self`.
 	fn op_call_fn(
 		&mut self,
 		code_block: usize,
 		output: RegId,
+		return_size: usize,
+		write_back: Option<(RegId, usize)>,
 	) -> Result<(), QuMsg> {
+		// The caller's values past the output are kept aside, so that the
+		// function starts with a frame that owns nothing. They may still be
+		// read through the function's arguments.
+		let frame_start = self.stack.offset + output.0;
+		let caller_values = take_values(&mut self.owned_values, frame_start..);
+
 		*self.stack.offset_mut() += usize::from(output);
-		// Assure registers is big enought to fit u8::MAX more values
-		if (self.stack.offset + u8::MAX as usize) > self.stack.len() {
+		// Assure registers is big enought to fit a frame
+		if (self.stack.offset + FRAME_SIZE) > self.stack.len() {
 			self.stack.data.resize(
-				self.stack.offset + u8::MAX as usize, 
+				self.stack.offset + FRAME_SIZE, 
 				0
 			);
 		}
-		self.loop_ops(code_block)?;
+		// The offset is restored even if the function fails, so that the
+		// stack can still be used by later calls
+		let result = match self.call_depth < MAX_CALL_DEPTH {
+			true => {
+				self.call_depth += 1;
+				let result = self.loop_ops(code_block);
+				self.call_depth -= 1;
+				result
+			},
+			false => Err(QuMsg::call_depth_exceeded(MAX_CALL_DEPTH)),
+		};
 		*self.stack.offset_mut() -= usize::from(output);
 
+		let self_start = frame_start + return_size;
+		let self_values = match write_back {
+			Some((_, self_size)) if result.is_ok() => take_values(
+				&mut self.owned_values,
+				self_start..self_start + self_size,
+			),
+			_ => BTreeMap::new(),
+		};
+		drop(take_values(&mut self.owned_values, self_start..));
+		for (at, value) in caller_values {
+			// Values replaced by the output are dropped
+			self.owned_values.entry(at).or_insert(value);
+		}
+
+		// Move the changed `self` back to the caller's value
+		if let (Some((to, self_size)), Ok(())) = (write_back, &result) {
+			self.copy_reg(RegId(output.0 + return_size), to, self_size);
+			let to_start = self.stack.offset + to.0;
+			for (at, value) in self_values {
+				self.owned_values.insert(at - self_start + to_start, value);
+			}
+		}
+
+		return result;
+	}
+
+	/// Makes a [`Callable`] of the function `fn_id`. The values in the
+	/// `captures` registers are the function's last parameters.
+	fn op_make_callable(
+		&mut self,
+		fn_id: FunctionId,
+		captures: &[RegId],
+		output: RegId,
+	) -> Result<(), QuMsg> {
+		let parameters = &self.definitions.get_function(fn_id)?
+			.identity
+			.parameters;
+		let first_capture = parameters.len() - captures.len();
+		let capture_classes = parameters[first_capture..].to_vec();
+		let mut values = Vec::with_capacity(captures.len());
+		for (reg, class_id) in captures.iter().zip(capture_classes) {
+			values.push(self.capture_value(*reg, class_id)?);
+		}
+		let uuid = self.definitions.uuid;
+		self.write(output, Callable {fn_id, uuid, captures: values.into()});
 		return Ok(());
 	}
 
+
+	/// Returns a copy of the value at `reg` that owns what it points to, so
+	/// it can be kept off the stack.
+	pub(crate) fn capture_value(
+		&mut self,
+		reg: RegId,
+		class_id: ClassId,
+	) -> Result<Capture, QuMsg> {
+		let size = self.definitions.get_class(class_id)?.size as usize;
+		// The copy is made past the end of the stack, where it can't
+		// overwrite anything
+		let stack_len = self.stack.len();
+		let copy_reg = RegId(stack_len - self.stack.offset);
+		self.stack.data.resize(stack_len + size, 0);
+		self.copy_reg(reg, copy_reg, size);
+		let result = self.own_value(copy_reg, class_id);
+		let capture = Capture {
+			bytes: self.stack.read_dyn(copy_reg, size).into(),
+			_values: take_values(&mut self.owned_values, stack_len..)
+				.into_values()
+				.collect(),
+		};
+		self.stack.data.truncate(stack_len);
+		result?;
+		return Ok(capture);
+	}
+
+
+	/// Writes a copy of a value kept off the stack to `reg`. The copy owns
+	/// what it points to, and the values `reg` owned before are dropped.
+	pub(crate) fn restore_value(
+		&mut self,
+		capture: &Capture,
+		reg: RegId,
+		class_id: ClassId,
+	) -> Result<(), QuMsg> {
+		let start = self.stack.offset + reg.0;
+		drop(take_values(
+			&mut self.owned_values,
+			start..start + capture.bytes.len(),
+		));
+		self.stack.write_dyn(reg, &capture.bytes);
+		return self.own_value(reg, class_id);
+	}
+
+
+	/// Returns the bytes of the value of class `class_id` at `reg`.
+	pub(crate) fn value_bytes(
+		&self,
+		reg: RegId,
+		class_id: ClassId,
+	) -> Result<&[u8], QuMsg> {
+		let size = self.definitions.get_class(class_id)?.size as usize;
+		return Ok(self.stack.read_dyn(reg, size));
+	}
+
+
+	/// Calls a function with arguments made of the given bytes, and returns
+	/// its output.
+	/// 
+	/// The arguments and output are put past the end of the stack, where they
+	/// can't overwrite anything. The arguments don't own what they point to,
+	/// and what the output owns is dropped once the output is read.
+	pub(crate) fn call_with_bytes<T: Copy + 'static>(
+		&mut self,
+		fn_id: FunctionId,
+		args: &[&[u8]],
+	) -> Result<T, QuMsg> {
+		let stack_len = self.stack.len();
+		let mut end = stack_len - self.stack.offset;
+		let mut arg_regs = Vec::with_capacity(args.len());
+		for arg in args {
+			arg_regs.push(RegId(end));
+			end += arg.len();
+		}
+		let output = RegId(end);
+		self.stack.data.resize(self.stack.offset + end + size_of::<T>(), 0);
+		for (reg, arg) in arg_regs.iter().zip(args) {
+			self.stack.write_dyn(*reg, arg);
+		}
+		let result = self.call_function(fn_id, &arg_regs, output)
+			.map(|_| *self.stack.read::<T>(output));
+		drop(take_values(&mut self.owned_values, stack_len..));
+		self.stack.data.truncate(stack_len);
+		return result;
+	}
+
+
+	/// Makes the value at `reg` own what it points to, by replacing every part
+	/// of it that needs dropping with a copy made by its class's `copy`
+	/// function.
+	/// 
+	/// Values are copied byte by byte on the stack, which is only enough for
+	/// classes that don't need dropping.
+	pub(crate) fn own_value(
+		&mut self,
+		reg: RegId,
+		class_id: ClassId,
+	) -> Result<(), QuMsg> {
+		let class = self.definitions.get_class(class_id)?;
+		if class.function_type.is_some() {
+			let callable = self.read::<Callable>(reg)?.clone();
+			self.write(reg, callable);
+			return Ok(());
+		}
+		if class.is_trait {
+			let value_class = *self.stack.read::<ClassId>(reg);
+			return self.own_value(RegId(reg.0 + DYN_HEADER_SIZE), value_class);
+		}
+		if class.from_script || class.is_tuple {
+			let fields:Vec<(usize, ClassId)> = class.fields.iter()
+				.map(|field| (field.offset, field.class_id))
+				.collect();
+			for (offset, field_class) in fields {
+				self.own_value(RegId(reg.0 + offset), field_class)?;
+			}
+			return Ok(());
+		}
+		if !class.needs_drop {
+			return Ok(());
+		}
+		let Some(copy_id) = self.copy_function(class_id) else {
+			return Ok(());
+		};
+		return self.call_function(copy_id, &[reg], reg);
+	}
+
+
+	/// Returns the `copy` function of a class, if it has one.
+	fn copy_function(&self, class_id: ClassId) -> Option<FunctionId> {
+		let d = &self.definitions;
+		let identity = FunctionIdentity {
+			name: "copy".into(),
+			parameters: Box::new([class_id]),
+			return_type: Default::default(),
+		};
+		let group_id = d.get_class(class_id).ok()?
+			.common
+			.get_function_group_id(&identity.name)
+			.ok()?;
+		return d.get_function_group(group_id).ok()?.get_fn_id(&identity, d).ok();
+	}
+
+
+	fn op_hold(&mut self, reg:RegId) {
+		self.hold_is_true = *self.stack.read::<Bool>(reg);
+	}
+
+
 	fn op_jump_by(&mut self, mut pc:usize, by:isize) -> usize {
 		// Add
 		if by > 0 {
@@ -434,6 +1052,58 @@ pub struct QuVm {
 	}
 
 
+	/// Copies a static variable to `output`. The copy owns what it points
+	/// to, so the variable can be changed while the copy is in use.
+	fn op_load_static(
+		&mut self,
+		variable_id:VariableId,
+		output:RegId,
+	) -> Result<(), QuMsg> {
+		if self.statics.len() < self.definitions.static_memory_size {
+			self.statics.resize(self.definitions.static_memory_size, 0);
+		}
+		let variable = &self.definitions.static_variables[variable_id];
+		// Variables declared by a script that failed to compile, or that
+		// hasn't run yet, have no value
+		if !self.stored_statics.get(variable_id).copied().unwrap_or(false) {
+			return Err(QuMsg::static_lacks_value(&variable.name));
+		}
+		let class_id = variable.class_id;
+		let size = self.definitions.classes[&class_id].size as usize;
+		let value = &self.statics[variable.offset..variable.offset+size];
+		self.stack.write_dyn(output, value);
+		return self.own_value(output, class_id);
+	}
+
+
+	/// Moves the value at `from` into a static variable, dropping the
+	/// variable's old value.
+	fn op_store_static(&mut self, from:RegId, variable_id:VariableId) {
+		let variable = &self.definitions.static_variables[variable_id];
+		let size = self.definitions.classes[&variable.class_id].size as usize;
+		let offset = variable.offset;
+		if self.statics.len() < self.definitions.static_memory_size {
+			self.statics.resize(self.definitions.static_memory_size, 0);
+		}
+		self.statics[offset..offset+size]
+			.copy_from_slice(self.stack.read_dyn(from, size));
+		if self.stored_statics.len() <= variable_id {
+			self.stored_statics.resize(variable_id + 1, false);
+		}
+		self.stored_statics[variable_id] = true;
+
+		drop(take_values(&mut self.static_values, offset..offset+size));
+		let from_start = self.stack.offset + from.0;
+		let values = take_values(
+			&mut self.owned_values,
+			from_start..from_start+size,
+		);
+		for (at, value) in values {
+			self.static_values.insert(at - from_start + offset, value);
+		}
+	}
+
+
 	fn op_load_constant(&mut self, const_id:usize, output:RegId) {
 		let value = &self.definitions.constants[const_id as usize].value;
 		self.stack.write_dyn(output, value);
@@ -442,7 +1112,7 @@ pub struct QuVm {
 
 	#[inline]
 	/// Gets a register value.
-	pub fn read<T: Register + 'static>(&self, at_reg:RegId) -> Result<&T, QuMsg> {
+	pub fn read<T: 'static>(&self, at_reg:RegId) -> Result<&T, QuMsg> {
 		let got = self.stack.read(at_reg);
 		Ok(got)
 	}
@@ -463,8 +1133,56 @@ pub struct QuVm {
 
 	#[inline]
 	/// Sets a register value.
-	pub fn write<T>(&mut self, id:RegId, value:T) {
-		return self.stack.write(id.into(), value);
+	/// 
+	/// If `value` owns any resources (like a [`Str`]) it's kept alive until
+	/// the register is written to again or its frame ends. The values that
+	/// the register owned before are dropped.
+	pub fn write<T: 'static>(&mut self, id:RegId, value:T) {
+		if !needs_drop::<T>() {
+			return self.stack.write(id.into(), value);
+		}
+		let bytes = unsafe { std::slice::from_raw_parts(
+			(&value as *const T).cast::<u8>(),
+			size_of::<T>(),
+		) };
+		self.stack.write_dyn(id, bytes);
+		let start = self.stack.offset + id.0;
+		drop(take_values(&mut self.owned_values, start..start+size_of::<T>()));
+		self.owned_values.insert(start, Box::new(value));
+	}
+
+
+	/// Points an error raised by an op to the part of the script the op was
+	/// compiled from.
+	fn op_error(&self, msg:QuMsg, code_block:usize, pc:usize) -> QuMsg {
+		let span = self.definitions.byte_code_spans.get(code_block)
+			.and_then(|spans| spans.get(pc).copied().flatten());
+		return match span {
+			Some(span) => msg.or_span(span),
+			None => msg,
+		};
+	}
+
+
+	/// Replaces everything the Vm has defined, such as with the definitions
+	/// of a loaded script. The values of static variables are cleared.
+	pub(crate) fn replace_definitions(&mut self, definitions:Definitions) {
+		self.definitions = definitions;
+		self.statics.clear();
+		self.stored_statics.clear();
+		self.static_values.clear();
+		self.return_type = None;
+	}
+
+
+	/// Runs the top level code of a script in a fresh frame at the bottom of
+	/// the stack.
+	pub(crate) fn run_code_block(&mut self, code_block:usize) -> Result<(), QuMsg> {
+		*self.stack.offset_mut() = 0;
+		self.args.clear();
+		// The values left by the last run, such as its result, are dropped
+		self.owned_values.clear();
+		return self.loop_ops(code_block);
 	}
 
 
@@ -473,21 +1191,60 @@ pub struct QuVm {
 		&mut self,
 		code_block:usize,
 	) -> Result<(), QuMsg>{
+		// The block is held apart from the definitions, so that its ops can
+		// be passed to calls without copying them
+		let block = self.definitions.byte_code_blocks[code_block].clone();
 		let mut pc = 0;
-		while pc != self.definitions.byte_code_blocks[code_block].len() {
-			let op = &self.definitions.byte_code_blocks[code_block][pc];
+		while pc != block.len() {
+			let op = &block[pc];
 			const PRINT_RUNNING_OPS:bool = false;
 			if PRINT_RUNNING_OPS { println!("{}", op.readable(&self.definitions)); }
 			match op {
-				QuOp::Call(fn_id, args, ouput) => self.call_function(*fn_id, args.clone(), *ouput)?,
+				QuOp::Call(fn_id, args, ouput) => self.call_function(*fn_id, args, *ouput)
+					.map_err(|msg| self.op_error(msg, code_block, pc))?,
 				QuOp::End => break,
+				QuOp::Hold(reg) => self.op_hold(*reg),
 				QuOp::JumpByIfNot(by) => pc = self.op_jump_by_if_not(pc, *by),
 				QuOp::JumpBy( by) => pc = self.op_jump_by(pc, *by),
 				QuOp::LoadConstant(const_id, output) => self.op_load_constant(*const_id, *output),
-				QuOp::Return(return_type) => self.return_type = *return_type,
-    			QuOp::CallV(_, _, _, _, _) => todo!(),
+				QuOp::LoadStatic(variable_id, output) => self.op_load_static(*variable_id, *output)
+					.map_err(|msg| self.op_error(msg, code_block, pc))?,
+				QuOp::MakeCallable(fn_id, captures, output) => self.op_make_callable(
+					*fn_id,
+					captures,
+					*output,
+				).map_err(|msg| self.op_error(msg, code_block, pc))?,
+				QuOp::CallValue(callable, args, output) => {
+					let callable = self.read::<Callable>(*callable)?.clone();
+					self.call_callable(callable, args, *output)
+						.map_err(|msg| self.op_error(msg, code_block, pc))?
+				},
+				QuOp::StoreStatic(from, variable_id) => self.op_store_static(*from, *variable_id),
+				QuOp::Return(return_type) => self.return_type = Some(*return_type),
+    			QuOp::CallV(
+					trait_id,
+					class_id,
+					fn_id,
+					args,
+					output,
+				) => self.call_virtual(
+					*trait_id,
+					*class_id,
+					*fn_id,
+					args,
+					*output,
+				).map_err(|msg| self.op_error(msg, code_block, pc))?,
     			QuOp::LoadArg(index, output) => {
-					let arg = &self.args[*index as usize];
+					// Calls made by the function replace its arguments
+					let Some(arg) = self.args.get(*index as usize) else {
+						return Err(self.op_error(
+							QuMsg::of_kind(QuErrorKind::Runtime, format!(
+								"Argument {index} was loaded after it was replaced by a call.",
+							)),
+							code_block,
+							pc,
+						));
+					};
 					self.stack.write_dyn(
 						*output,
 						arg,
@@ -501,6 +1258,27 @@ pub struct QuVm {
 }
 
 
+/// Removes the values in `range` from `values` and returns them.
+fn take_values(
+	values: &mut BTreeMap<usize, Box<dyn Any>>,
+	range: impl RangeBounds<usize>,
+) -> BTreeMap<usize, Box<dyn Any>> {
+	let start = match range.start_bound() {
+		Bound::Included(start) => *start,
+		Bound::Excluded(start) => *start + 1,
+		Bound::Unbounded => 0,
+	};
+	let mut taken = values.split_off(&start);
+	let mut kept = match range.end_bound() {
+		Bound::Included(end) => taken.split_off(&(*end + 1)),
+		Bound::Excluded(end) => taken.split_off(end),
+		Bound::Unbounded => BTreeMap::new(),
+	};
+	values.append(&mut kept);
+	return taken;
+}
+
+
 #[derive(Debug, Default, Clone)]
 struct VmStack {
 	data: Vec<u8>,
@@ -604,7 +1382,9 @@ pub trait Stack {
 		let index_pointer = self.data_mut()
 			.as_mut_slice()[index..]
 			.as_mut_ptr();
-		unsafe { *(index_pointer as *mut T) = value };
+		// The old value is not dropped, it may be a copy of a value that
+		// is still in use.
+		unsafe { std::ptr::write_unaligned(index_pointer as *mut T, value) };
 	}
 
 
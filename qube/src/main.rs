@@ -23,6 +23,271 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+//! The command-line runner for Qu scripts.
 
-fn main() {}
+use std::env;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
 
+use qu::Qu;
+use qu::Script;
+
+
+const USAGE:&str = "\
+Usage: qube <command> [arguments]
+
+Commands:
+	run <path>                Runs a .qu script file, or a .quc file made by build.
+	build <path> [output]     Compiles a .qu script file into a .quc bytecode file.
+	disasm <path>             Prints the bytecode of a .qu script or .quc file.
+	repl                      Starts an interactive session.
+	help                      Prints this message.";
+
+/// The extension of bytecode files made by `qube build`.
+const BYTECODE_EXTENSION:&str = "quc";
+
+
+fn main() -> ExitCode {
+	let args:Vec<String> = env::args().skip(1).collect();
+	let command = args.first().map(|x| x.as_str());
+
+	match command {
+		Some("run") => {
+			let Some(path) = args.get(1) else {
+				eprintln!("qube run: expected a path to a .qu script.\n\n{USAGE}");
+				return ExitCode::FAILURE;
+			};
+			cmd_run(path)
+		},
+		Some("build") => {
+			let Some(path) = args.get(1) else {
+				eprintln!("qube build: expected a path to a .qu script.\n\n{USAGE}");
+				return ExitCode::FAILURE;
+			};
+			cmd_build(path, args.get(2))
+		},
+		Some("disasm") => {
+			let Some(path) = args.get(1) else {
+				eprintln!("qube disasm: expected a path to a .qu or .quc file.\n\n{USAGE}");
+				return ExitCode::FAILURE;
+			};
+			cmd_disasm(path)
+		},
+		Some("repl") => cmd_repl(),
+		Some("help") | Some("--help") | Some("-h") => {
+			println!("{USAGE}");
+			ExitCode::SUCCESS
+		},
+		Some(other) => {
+			eprintln!("qube: unknown command '{other}'.\n\n{USAGE}");
+			ExitCode::FAILURE
+		},
+		None => {
+			eprintln!("{USAGE}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+
+/// Runs the script at `path`, printing any error against the script's source.
+fn cmd_run(path:&str) -> ExitCode {
+	if is_bytecode(path) {
+		let mut qu = Qu::new();
+		let Some(script) = load_bytecode(&mut qu, path, "run") else {
+			return ExitCode::FAILURE;
+		};
+		return match qu.run_script(&script) {
+			Ok(()) => ExitCode::SUCCESS,
+			Err(msg) => {
+				eprintln!("{msg}");
+				ExitCode::FAILURE
+			},
+		};
+	}
+
+	let Some(source) = read_source(path, "run") else {
+		return ExitCode::FAILURE;
+	};
+
+	let mut qu = Qu::new();
+	let Some(script) = compile_source(&mut qu, &source) else {
+		return ExitCode::FAILURE;
+	};
+	match qu.run_script(&script) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(msg) => {
+			eprintln!("{}", msg.make_pretty_message(&source));
+			ExitCode::FAILURE
+		},
+	}
+}
+
+
+/// Compiles the script at `path` into a bytecode file at `output`, or next
+/// to the script if no output is given.
+fn cmd_build(path:&str, output:Option<&String>) -> ExitCode {
+	let Some(source) = read_source(path, "build") else {
+		return ExitCode::FAILURE;
+	};
+	let mut qu = Qu::new();
+	let Some(script) = compile_source(&mut qu, &source) else {
+		return ExitCode::FAILURE;
+	};
+
+	let bytes = match qu.save_script(&script) {
+		Ok(bytes) => bytes,
+		Err(msg) => {
+			eprintln!("{msg}");
+			return ExitCode::FAILURE;
+		},
+	};
+	let output = match output {
+		Some(output) => Path::new(output).to_path_buf(),
+		None => Path::new(path).with_extension(BYTECODE_EXTENSION),
+	};
+	if let Err(err) = fs::write(&output, bytes) {
+		eprintln!("qube build: could not write '{}': {err}", output.display());
+		return ExitCode::FAILURE;
+	}
+	println!("Built '{}'.", output.display());
+	ExitCode::SUCCESS
+}
+
+
+/// Prints the bytecode of the script or bytecode file at `path`.
+fn cmd_disasm(path:&str) -> ExitCode {
+	let mut qu = Qu::new();
+	let script = match is_bytecode(path) {
+		true => load_bytecode(&mut qu, path, "disasm"),
+		false => read_source(path, "disasm")
+			.and_then(|source| compile_source(&mut qu, &source)),
+	};
+	if script.is_none() {
+		return ExitCode::FAILURE;
+	}
+
+	print!("{}", qu.disassemble());
+	ExitCode::SUCCESS
+}
+
+
+/// Returns whether `path` is a bytecode file made by `qube build`.
+fn is_bytecode(path:&str) -> bool {
+	Path::new(path).extension().is_some_and(|x| x == BYTECODE_EXTENSION)
+}
+
+
+/// Reads the source of the script at `path`, printing an error for `command`
+/// if it can't be read.
+fn read_source(path:&str, command:&str) -> Option<String> {
+	match fs::read_to_string(path) {
+		Ok(source) => Some(source),
+		Err(err) => {
+			eprintln!("qube {command}: could not read '{path}': {err}");
+			None
+		},
+	}
+}
+
+
+/// Compiles a script's source, printing its errors and warnings against the
+/// source.
+fn compile_source(qu:&mut Qu, source:&str) -> Option<Script> {
+	match qu.compile(source) {
+		Ok(script) => {
+			for msg in script.diagnostics() {
+				eprintln!("{}", msg.make_pretty_message(source));
+			}
+			Some(script)
+		},
+		Err(msgs) => {
+			for msg in msgs {
+				eprintln!("{}", msg.make_pretty_message(source));
+			}
+			None
+		},
+	}
+}
+
+
+/// Loads the bytecode file at `path`, printing an error for `command` if it
+/// can't be loaded.
+fn load_bytecode(qu:&mut Qu, path:&str, command:&str) -> Option<Script> {
+	let bytes = match fs::read(path) {
+		Ok(bytes) => bytes,
+		Err(err) => {
+			eprintln!("qube {command}: could not read '{path}': {err}");
+			return None;
+		},
+	};
+	match qu.load_script(&bytes) {
+		Ok(script) => Some(script),
+		Err(msg) => {
+			eprintln!("{msg}");
+			None
+		},
+	}
+}
+
+
+/// Runs an interactive session that keeps its definitions between inputs.
+/// 
+/// A line ending with ':' starts a multi-line input, which is ended by an
+/// empty line.
+fn cmd_repl() -> ExitCode {
+	const PROMPT:&str = ">>> ";
+	const PROMPT_CONTINUE:&str = "... ";
+	const QUIT:&str = ":quit";
+
+	let mut qu = Qu::new();
+	let stdin = io::stdin();
+	let mut lines = stdin.lock().lines();
+
+	println!("Qu interactive session. Enter '{QUIT}' or end input to exit.");
+	loop {
+		let Some(line) = prompt_line(PROMPT, &mut lines) else { break };
+		if line.trim() == QUIT {
+			break;
+		}
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		// Collect the rest of an indented block
+		let mut chunk = line;
+		if chunk.trim_end().ends_with(':') {
+			while let Some(line) = prompt_line(PROMPT_CONTINUE, &mut lines) {
+				if line.trim().is_empty() {
+					break;
+				}
+				chunk.push('\n');
+				chunk.push_str(&line);
+			}
+		}
+
+		match qu.run_and_display(&chunk) {
+			Ok(Some(value)) => println!("{value}"),
+			Ok(None) => {},
+			Err(msg) => eprintln!("{}", msg.make_pretty_message(&chunk)),
+		}
+	}
+
+	ExitCode::SUCCESS
+}
+
+
+/// Prints `prompt` and reads the next line of input, or [`None`] at the end of
+/// input.
+fn prompt_line(
+	prompt:&str,
+	lines:&mut impl Iterator<Item=io::Result<String>>,
+) -> Option<String> {
+	print!("{prompt}");
+	io::stdout().flush().ok()?;
+	lines.next()?.ok()
+}